@@ -161,7 +161,7 @@ mod complete_staking_rewards_integration_tests {
             }
         }
 
-        pub fn fund_staking_rewards(&mut self, amount: u128) {
+        pub fn fund_trading_rewards_pool(&mut self, amount: u128) {
             self.trading_rewards_pool += amount;
         }
 
@@ -346,7 +346,7 @@ mod complete_staking_rewards_integration_tests {
             let (trading_amount, staking_amount) = self.trading_rewards.receive_fee_allocation(rewards_allocation);
             
             // Envia staking amount para o contrato de staking
-            self.staking.fund_staking_rewards(staking_amount);
+            self.staking.fund_trading_rewards_pool(staking_amount);
         }
 
         pub fn advance_time(&mut self, seconds: u64) {
@@ -0,0 +1,1237 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![warn(clippy::arithmetic_side_effects)]
+#[ink::contract]
+pub mod batch_auction_contract {
+    use psp22::PSP22Error;
+    use ink::prelude::vec::Vec;
+    use ink::prelude::vec;
+    use ink::storage::Mapping;
+    use primitive_types::U256;
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::hash::{Blake2x256, HashOutput};
+    use ink::env::DefaultEnvironment;
+    use router_contract::router_contract::RouterError;
+
+    // ========================================
+    // BATCH AUCTION CONTRACT - SWAPS COMMIT-REVEAL RESISTENTES A MEV
+    // ========================================
+    //
+    // Modo opcional de swap em que o trader não expõe a ordem ao submetê-la:
+    //
+    // 1. **Commit** (fase de commit da época): envia
+    //    `blake2x256(scale_encode((path, amount_in, min_out, salt)))` com um bond em LUNES
+    // 2. **Reveal** (fase de reveal da mesma época, em bloco posterior): revela os
+    //    parâmetros, deposita `amount_in` e recebe o bond de volta
+    // 3. **Settle** (após a época): todas as ordens reveladas do mesmo par são
+    //    liquidadas a um único preço uniforme. O fluxo que se cancela é casado
+    //    internamente e apenas o excedente é trocado no par via router, na fee
+    //    cobrada pelo par (`current_fee_bps`).
+    //
+    // Ordens cujo `min_out` não é atendido pelo preço uniforme são excluídas e
+    // reembolsadas; os valores ficam disponíveis via `claim`.
+    // Bonds de commits não revelados são confiscados. Reveals abaixo de
+    // `MIN_ORDER_AMOUNT_IN` são rejeitados; um reveal válido que encontra o lote
+    // cheio não entra no lote, mas recebe o bond de volta.
+    // Um lote não liquidado até `SETTLEMENT_TIMEOUT_EPOCHS` épocas após a sua
+    // pode ser reembolsado por qualquer conta (`refund_batch`).
+
+    // ========================================
+    // EVENTOS (PARA INDEXADORES E UIS)
+    // ========================================
+
+    /// Emitido quando um commit é registrado
+    #[ink(event)]
+    pub struct Committed {
+        #[ink(topic)]
+        pub owner: AccountId,
+        #[ink(topic)]
+        pub commitment: Hash,
+        pub epoch: u64,
+    }
+
+    /// Emitido quando uma ordem é revelada
+    #[ink(event)]
+    pub struct Revealed {
+        #[ink(topic)]
+        pub owner: AccountId,
+        #[ink(topic)]
+        pub pair: AccountId,
+        pub epoch: u64,
+        pub sell_token_0: bool,
+        pub amount_in: Balance,
+    }
+
+    /// Emitido quando um reveal válido encontra o lote cheio (bond devolvido)
+    #[ink(event)]
+    pub struct RevealRejected {
+        #[ink(topic)]
+        pub owner: AccountId,
+        #[ink(topic)]
+        pub pair: AccountId,
+        pub epoch: u64,
+        pub bond: Balance,
+    }
+
+    /// Emitido quando o lote de um par é liquidado
+    #[ink(event)]
+    pub struct BatchSettled {
+        #[ink(topic)]
+        pub pair: AccountId,
+        pub epoch: u64,
+        /// token_0 vendido pelas ordens executadas
+        pub total_in_0: Balance,
+        /// token_1 vendido pelas ordens executadas
+        pub total_in_1: Balance,
+        /// token_0 distribuído aos vendedores de token_1
+        pub pot_0: Balance,
+        /// token_1 distribuído aos vendedores de token_0
+        pub pot_1: Balance,
+        pub filled_orders: u32,
+        pub excluded_orders: u32,
+    }
+
+    /// Emitido quando um lote não liquidado a tempo é reembolsado
+    #[ink(event)]
+    pub struct BatchRefunded {
+        #[ink(topic)]
+        pub pair: AccountId,
+        pub epoch: u64,
+        pub refunded_orders: u32,
+    }
+
+    /// Emitido quando um bond é confiscado
+    #[ink(event)]
+    pub struct BondForfeited {
+        #[ink(topic)]
+        pub owner: AccountId,
+        #[ink(topic)]
+        pub commitment: Hash,
+        pub bond: Balance,
+    }
+
+    /// Emitido quando um usuário saca valores liquidados
+    #[ink(event)]
+    pub struct Claimed {
+        #[ink(topic)]
+        pub owner: AccountId,
+        #[ink(topic)]
+        pub token: AccountId,
+        pub amount: Balance,
+    }
+
+    // ========================================
+    // ERROS ESPECÍFICOS DO BATCH AUCTION
+    // ========================================
+
+    /// Erros que podem ocorrer no batch auction
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum BatchAuctionError {
+        /// Operação fora da fase correta da época
+        WrongPhase,
+        /// Bond enviado diferente do exigido
+        InvalidBond,
+        /// Commit já registrado
+        CommitmentExists,
+        /// Commit não encontrado
+        CommitmentNotFound,
+        /// Parâmetros revelados não correspondem ao commit
+        InvalidReveal,
+        /// Commit pertence a outra época
+        EpochMismatch,
+        /// Commit ainda pode ser revelado
+        CommitmentStillRevealable,
+        /// Path inválido (apenas swaps diretos de um par)
+        InvalidPath,
+        /// Amount zero
+        ZeroAmount,
+        /// Par não existe no factory
+        PairNotFound,
+        /// Lote não encontrado
+        BatchNotFound,
+        /// Lote já liquidado
+        BatchAlreadySettled,
+        /// Nada a sacar
+        NothingToClaim,
+        /// Caller não autorizado
+        Unauthorized,
+        /// Consulta ao par/factory falhou
+        QueryFailed,
+        /// Chamada ao router falhou
+        RouterCallFailed,
+        /// Transferência falhou
+        TransferFailed,
+        /// Amount abaixo do mínimo por ordem
+        OrderTooSmall,
+        /// Overflow aritmético
+        Overflow,
+        /// Lote ainda dentro da janela de liquidação
+        SettlementWindowOpen,
+        /// Swap do excedente rejeitado pelo router
+        Router(RouterError),
+        /// Erro no token PSP22 subjacente
+        PSP22(PSP22Error),
+    }
+
+    impl From<PSP22Error> for BatchAuctionError {
+        fn from(error: PSP22Error) -> Self {
+            BatchAuctionError::PSP22(error)
+        }
+    }
+
+    // ========================================
+    // TIPOS AUXILIARES
+    // ========================================
+
+    /// Fase atual de uma época
+    #[derive(Debug, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum Phase {
+        Commit,
+        Reveal,
+    }
+
+    /// Commit pendente de reveal
+    #[derive(Debug, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Commitment {
+        pub epoch: u64,
+        pub bond: Balance,
+    }
+
+    /// Ordem revelada dentro de um lote
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct RevealedOrder {
+        pub owner: AccountId,
+        /// `true` se vende token_0 por token_1
+        pub sell_token_0: bool,
+        pub amount_in: Balance,
+        pub min_out: Balance,
+    }
+
+    /// Lote de ordens de um par em uma época
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Batch {
+        pub token_0: AccountId,
+        pub token_1: AccountId,
+        pub orders: Vec<RevealedOrder>,
+        pub settled: bool,
+    }
+
+    /// Resultado do preço uniforme de um lote
+    #[derive(Debug, Default, PartialEq, Eq, Clone)]
+    pub struct Clearing {
+        /// Ordens executadas (as demais são reembolsadas)
+        pub filled: Vec<bool>,
+        pub total_in_0: Balance,
+        pub total_in_1: Balance,
+        /// Swap líquido no par: `(vende token_0, amount_in, output esperado)`
+        pub swap: Option<(bool, Balance, Balance)>,
+        /// token_0 a distribuir aos vendedores de token_1
+        pub pot_0: Balance,
+        /// token_1 a distribuir aos vendedores de token_0
+        pub pot_1: Balance,
+    }
+
+    // ========================================
+    // CONSTANTES
+    // ========================================
+    mod constants {
+        /// Blocos da fase de commit de cada época
+        pub const COMMIT_BLOCKS: u32 = 10;
+
+        /// Blocos da fase de reveal de cada época
+        pub const REVEAL_BLOCKS: u32 = 5;
+
+        /// Duração total de uma época em blocos
+        pub const EPOCH_BLOCKS: u32 = COMMIT_BLOCKS + REVEAL_BLOCKS;
+
+        /// Máximo de ordens por lote (limita o custo da liquidação)
+        pub const MAX_ORDERS_PER_BATCH: usize = 64;
+
+        /// Amount mínimo por ordem (impede ocupar o lote com poeira)
+        pub const MIN_ORDER_AMOUNT_IN: u128 = 1_000_000;
+
+        /// Épocas após a do lote em que ele ainda pode ser liquidado; depois
+        /// disso as ordens podem ser reembolsadas
+        pub const SETTLEMENT_TIMEOUT_EPOCHS: u64 = 10;
+
+        /// Denominador de basis points
+        pub const BPS_DENOMINATOR: u128 = 10_000;
+
+        /// Fee estática do PairContract em bps (0.5% = 995/1000), usada quando
+        /// o par não expõe `current_fee_bps`
+        pub const PAIR_STATIC_FEE_BPS: u16 = 50;
+    }
+
+    /// Selectors das chamadas cross-contract
+    mod selectors {
+        pub const FACTORY_GET_PAIR: [u8; 4] = ink::selector_bytes!("get_pair");
+        pub const PAIR_TOKEN_0: [u8; 4] = ink::selector_bytes!("token_0");
+        pub const PAIR_TOKEN_1: [u8; 4] = ink::selector_bytes!("token_1");
+        pub const PAIR_GET_RESERVES: [u8; 4] = ink::selector_bytes!("get_reserves");
+        pub const PAIR_CURRENT_FEE_BPS: [u8; 4] = ink::selector_bytes!("current_fee_bps");
+        pub const ROUTER_SWAP_EXACT_TOKENS_FOR_TOKENS: [u8; 4] = ink::selector_bytes!("swap_exact_tokens_for_tokens");
+        pub const PSP22_TRANSFER: [u8; 4] = ink::selector_bytes!("PSP22::transfer");
+        pub const PSP22_TRANSFER_FROM: [u8; 4] = ink::selector_bytes!("PSP22::transfer_from");
+        pub const PSP22_APPROVE: [u8; 4] = ink::selector_bytes!("PSP22::approve");
+    }
+
+    // ========================================
+    // STORAGE DO BATCH AUCTION
+    // ========================================
+
+    #[ink(storage)]
+    pub struct BatchAuctionContract {
+        /// Admin (recebe os bonds confiscados)
+        admin: AccountId,
+        /// Router usado para o swap líquido
+        router: AccountId,
+        /// Factory usado para resolver pares
+        factory: AccountId,
+        /// Bond exigido por commit (LUNES nativo)
+        commit_bond: Balance,
+        /// Bonds confiscados ainda não sacados
+        forfeited_bonds: Balance,
+        /// Commits pendentes por (dono, hash)
+        commitments: Mapping<(AccountId, Hash), Commitment>,
+        /// Lotes por (época, par)
+        batches: Mapping<(u64, AccountId), Batch>,
+        /// Valores liquidados a sacar por (dono, token)
+        claimable: Mapping<(AccountId, AccountId), Balance>,
+    }
+
+    impl BatchAuctionContract {
+        /// Construtor do Batch Auction
+        ///
+        /// O deployer se torna o admin.
+        #[ink(constructor)]
+        pub fn new(router: AccountId, factory: AccountId, commit_bond: Balance) -> Self {
+            Self {
+                admin: Self::env().caller(),
+                router,
+                factory,
+                commit_bond,
+                forfeited_bonds: 0,
+                commitments: Mapping::default(),
+                batches: Mapping::default(),
+                claimable: Mapping::default(),
+            }
+        }
+
+        // ========================================
+        // QUERIES (READ-ONLY)
+        // ========================================
+
+        /// Época e fase do bloco atual
+        #[ink(message)]
+        pub fn current_epoch(&self) -> (u64, Phase) {
+            Self::epoch_at(self.env().block_number())
+        }
+
+        /// Bond exigido por commit
+        #[ink(message)]
+        pub fn commit_bond(&self) -> Balance {
+            self.commit_bond
+        }
+
+        /// Retorna um commit pendente
+        #[ink(message)]
+        pub fn get_commitment(&self, owner: AccountId, commitment: Hash) -> Option<Commitment> {
+            self.commitments.get((owner, commitment))
+        }
+
+        /// Retorna o lote de um par em uma época
+        #[ink(message)]
+        pub fn get_batch(&self, epoch: u64, pair: AccountId) -> Option<Batch> {
+            self.batches.get((epoch, pair))
+        }
+
+        /// Valor liquidado a sacar
+        #[ink(message)]
+        pub fn claimable_of(&self, owner: AccountId, token: AccountId) -> Balance {
+            self.claimable.get((owner, token)).unwrap_or(0)
+        }
+
+        /// Calcula o hash de commit (para uso off-chain via dry-run)
+        #[ink(message)]
+        pub fn commitment_hash(&self, path: Vec<AccountId>, amount_in: Balance, min_out: Balance, salt: Hash) -> Hash {
+            Self::hash_order(&path, amount_in, min_out, salt)
+        }
+
+        // ========================================
+        // COMMIT / REVEAL / SETTLE
+        // ========================================
+
+        /// Registra um commit na época atual (fase de commit)
+        ///
+        /// Deve ser enviado com exatamente `commit_bond` em LUNES.
+        #[ink(message, payable)]
+        pub fn commit(&mut self, commitment: Hash) -> Result<u64, BatchAuctionError> {
+            let owner = self.env().caller();
+            let (epoch, phase) = self.current_epoch();
+            if phase != Phase::Commit {
+                return Err(BatchAuctionError::WrongPhase);
+            }
+            let bond = self.env().transferred_value();
+            if bond != self.commit_bond {
+                return Err(BatchAuctionError::InvalidBond);
+            }
+            if self.commitments.contains((owner, commitment)) {
+                return Err(BatchAuctionError::CommitmentExists);
+            }
+
+            self.commitments.insert((owner, commitment), &Commitment { epoch, bond });
+            self.env().emit_event(Committed {
+                owner,
+                commitment,
+                epoch,
+            });
+
+            Ok(epoch)
+        }
+
+        /// Revela uma ordem comprometida (fase de reveal da mesma época)
+        ///
+        /// Deposita `amount_in` do primeiro token do path (requer allowance)
+        /// e devolve o bond.
+        ///
+        /// # Retorna
+        /// - `false` se o lote já estava cheio: a ordem não entra, nada é
+        ///   depositado e o bond é devolvido mesmo assim
+        #[ink(message)]
+        pub fn reveal(
+            &mut self,
+            path: Vec<AccountId>,
+            amount_in: Balance,
+            min_out: Balance,
+            salt: Hash,
+        ) -> Result<bool, BatchAuctionError> {
+            let owner = self.env().caller();
+            let commitment = Self::hash_order(&path, amount_in, min_out, salt);
+            let pending = self
+                .commitments
+                .get((owner, commitment))
+                .ok_or(BatchAuctionError::InvalidReveal)?;
+
+            let (epoch, phase) = self.current_epoch();
+            if pending.epoch != epoch {
+                return Err(BatchAuctionError::EpochMismatch);
+            }
+            if phase != Phase::Reveal {
+                return Err(BatchAuctionError::WrongPhase);
+            }
+            if path.len() != 2 || path[0] == path[1] {
+                return Err(BatchAuctionError::InvalidPath);
+            }
+            if amount_in == 0 {
+                return Err(BatchAuctionError::ZeroAmount);
+            }
+            if amount_in < constants::MIN_ORDER_AMOUNT_IN {
+                return Err(BatchAuctionError::OrderTooSmall);
+            }
+
+            let pair = self.resolve_pair(path[0], path[1])?;
+            let batch = match self.batches.get((epoch, pair)) {
+                Some(batch) => batch,
+                None => Batch {
+                    token_0: self.query(pair, selectors::PAIR_TOKEN_0)?,
+                    token_1: self.query(pair, selectors::PAIR_TOKEN_1)?,
+                    orders: Vec::new(),
+                    settled: false,
+                },
+            };
+            let sell_token_0 = path[0] == batch.token_0;
+            let order = RevealedOrder {
+                owner,
+                sell_token_0,
+                amount_in,
+                min_out,
+            };
+            if !self.admit_order(commitment, pending, pair, batch, order)? {
+                return Ok(false);
+            }
+
+            self.transfer_from_token(path[0], owner, self.env().account_id(), amount_in)?;
+
+            self.env().emit_event(Revealed {
+                owner,
+                pair,
+                epoch,
+                sell_token_0,
+                amount_in,
+            });
+
+            Ok(true)
+        }
+
+        /// Liquida o lote de um par após o fim da época (qualquer conta pode chamar)
+        #[ink(message)]
+        pub fn settle(&mut self, epoch: u64, pair: AccountId) -> Result<(), BatchAuctionError> {
+            let (current_epoch, _) = self.current_epoch();
+            if epoch >= current_epoch {
+                return Err(BatchAuctionError::WrongPhase);
+            }
+            let mut batch = self.batches.get((epoch, pair)).ok_or(BatchAuctionError::BatchNotFound)?;
+            if batch.settled {
+                return Err(BatchAuctionError::BatchAlreadySettled);
+            }
+
+            let (reserve_0, reserve_1, _): (Balance, Balance, Timestamp) =
+                self.query(pair, selectors::PAIR_GET_RESERVES)?;
+            let fee_bps = self.pair_fee_bps(pair);
+            let mut clearing = Self::compute_clearing(reserve_0, reserve_1, &batch.orders, fee_bps)?;
+
+            batch.settled = true;
+            self.batches.insert((epoch, pair), &batch);
+
+            // Swap apenas do excedente; o output real substitui o esperado no pote
+            if let Some((sell_token_0, amount_in, expected_out)) = clearing.swap {
+                let (token_in, token_out) = if sell_token_0 {
+                    (batch.token_0, batch.token_1)
+                } else {
+                    (batch.token_1, batch.token_0)
+                };
+                let received = self.swap_via_router(token_in, token_out, amount_in, expected_out)?;
+                let bonus = received.saturating_sub(expected_out);
+                if sell_token_0 {
+                    clearing.pot_1 = clearing.pot_1.checked_add(bonus).ok_or(BatchAuctionError::Overflow)?;
+                } else {
+                    clearing.pot_0 = clearing.pot_0.checked_add(bonus).ok_or(BatchAuctionError::Overflow)?;
+                }
+            }
+
+            self.credit_payouts(&batch, &clearing)?;
+
+            let filled_orders = clearing.filled.iter().filter(|filled| **filled).count();
+            let excluded_orders = clearing.filled.len().saturating_sub(filled_orders);
+            self.env().emit_event(BatchSettled {
+                pair,
+                epoch,
+                total_in_0: clearing.total_in_0,
+                total_in_1: clearing.total_in_1,
+                pot_0: clearing.pot_0,
+                pot_1: clearing.pot_1,
+                filled_orders: u32::try_from(filled_orders).map_err(|_| BatchAuctionError::Overflow)?,
+                excluded_orders: u32::try_from(excluded_orders).map_err(|_| BatchAuctionError::Overflow)?,
+            });
+
+            Ok(())
+        }
+
+        /// Reembolsa um lote não liquidado dentro de `SETTLEMENT_TIMEOUT_EPOCHS`
+        /// (qualquer conta pode chamar)
+        ///
+        /// Cada ordem volta ao dono via `claim`; o lote não pode mais ser liquidado.
+        #[ink(message)]
+        pub fn refund_batch(&mut self, epoch: u64, pair: AccountId) -> Result<u32, BatchAuctionError> {
+            let (current_epoch, _) = self.current_epoch();
+            let deadline = epoch
+                .checked_add(constants::SETTLEMENT_TIMEOUT_EPOCHS)
+                .ok_or(BatchAuctionError::Overflow)?;
+            if current_epoch <= deadline {
+                return Err(BatchAuctionError::SettlementWindowOpen);
+            }
+            let mut batch = self.batches.get((epoch, pair)).ok_or(BatchAuctionError::BatchNotFound)?;
+            if batch.settled {
+                return Err(BatchAuctionError::BatchAlreadySettled);
+            }
+
+            batch.settled = true;
+            self.batches.insert((epoch, pair), &batch);
+
+            // Nenhuma ordem executada: `credit_payouts` devolve todos os amounts
+            let refunds = Clearing {
+                filled: vec![false; batch.orders.len()],
+                ..Default::default()
+            };
+            self.credit_payouts(&batch, &refunds)?;
+
+            let refunded_orders = u32::try_from(batch.orders.len()).map_err(|_| BatchAuctionError::Overflow)?;
+            self.env().emit_event(BatchRefunded {
+                pair,
+                epoch,
+                refunded_orders,
+            });
+            Ok(refunded_orders)
+        }
+
+        /// Saca valores liquidados ou reembolsados em `token`
+        #[ink(message)]
+        pub fn claim(&mut self, token: AccountId) -> Result<Balance, BatchAuctionError> {
+            let owner = self.env().caller();
+            let amount = self.claimable_of(owner, token);
+            if amount == 0 {
+                return Err(BatchAuctionError::NothingToClaim);
+            }
+
+            self.claimable.remove((owner, token));
+            self.transfer_token(token, owner, amount)?;
+
+            self.env().emit_event(Claimed { owner, token, amount });
+            Ok(amount)
+        }
+
+        /// Confisca o bond de um commit não revelado após sua época (qualquer conta)
+        #[ink(message)]
+        pub fn forfeit_commitment(&mut self, owner: AccountId, commitment: Hash) -> Result<Balance, BatchAuctionError> {
+            let pending = self
+                .commitments
+                .get((owner, commitment))
+                .ok_or(BatchAuctionError::CommitmentNotFound)?;
+            let (epoch, _) = self.current_epoch();
+            if pending.epoch >= epoch {
+                return Err(BatchAuctionError::CommitmentStillRevealable);
+            }
+
+            self.commitments.remove((owner, commitment));
+            self.forfeited_bonds = self
+                .forfeited_bonds
+                .checked_add(pending.bond)
+                .ok_or(BatchAuctionError::Overflow)?;
+
+            self.env().emit_event(BondForfeited {
+                owner,
+                commitment,
+                bond: pending.bond,
+            });
+            Ok(pending.bond)
+        }
+
+        /// Saca os bonds confiscados (apenas admin)
+        #[ink(message)]
+        pub fn withdraw_forfeited_bonds(&mut self, to: AccountId) -> Result<Balance, BatchAuctionError> {
+            if self.env().caller() != self.admin {
+                return Err(BatchAuctionError::Unauthorized);
+            }
+            let amount = self.forfeited_bonds;
+            if amount == 0 {
+                return Err(BatchAuctionError::NothingToClaim);
+            }
+            self.forfeited_bonds = 0;
+            self.env()
+                .transfer(to, amount)
+                .map_err(|_| BatchAuctionError::TransferFailed)?;
+            Ok(amount)
+        }
+
+        // ========================================
+        // FUNÇÕES INTERNAS (ESTADO DOS LOTES)
+        // ========================================
+
+        /// Consome o commit, devolve o bond e inclui a ordem se houver vaga no lote
+        ///
+        /// # Retorna
+        /// - `false` se o lote estava cheio (ordem não incluída)
+        fn admit_order(
+            &mut self,
+            commitment: Hash,
+            pending: Commitment,
+            pair: AccountId,
+            mut batch: Batch,
+            order: RevealedOrder,
+        ) -> Result<bool, BatchAuctionError> {
+            let owner = order.owner;
+            self.commitments.remove((owner, commitment));
+
+            let admitted = batch.orders.len() < constants::MAX_ORDERS_PER_BATCH;
+            if admitted {
+                batch.orders.push(order);
+                self.batches.insert((pending.epoch, pair), &batch);
+            }
+
+            if pending.bond > 0 {
+                self.env()
+                    .transfer(owner, pending.bond)
+                    .map_err(|_| BatchAuctionError::TransferFailed)?;
+            }
+            if !admitted {
+                self.env().emit_event(RevealRejected {
+                    owner,
+                    pair,
+                    epoch: pending.epoch,
+                    bond: pending.bond,
+                });
+            }
+            Ok(admitted)
+        }
+
+        // ========================================
+        // FUNÇÕES INTERNAS (CÁLCULOS)
+        // ========================================
+
+        /// Época e fase de um bloco
+        fn epoch_at(block: BlockNumber) -> (u64, Phase) {
+            let epoch = block.checked_div(constants::EPOCH_BLOCKS).unwrap_or(0);
+            let offset = block.checked_rem(constants::EPOCH_BLOCKS).unwrap_or(0);
+            let phase = if offset < constants::COMMIT_BLOCKS { Phase::Commit } else { Phase::Reveal };
+            (u64::from(epoch), phase)
+        }
+
+        /// Hash de commit de uma ordem
+        fn hash_order(path: &[AccountId], amount_in: Balance, min_out: Balance, salt: Hash) -> Hash {
+            let mut output = <Blake2x256 as HashOutput>::Type::default();
+            ink::env::hash_encoded::<Blake2x256, _>(&(path, amount_in, min_out, salt), &mut output);
+            Hash::from(output)
+        }
+
+        /// Fator `10000 - fee_bps` aplicado ao amount de entrada
+        fn fee_factor(fee_bps: u16) -> Result<U256, BatchAuctionError> {
+            constants::BPS_DENOMINATOR
+                .checked_sub(u128::from(fee_bps))
+                .filter(|factor| *factor > 0)
+                .map(U256::from)
+                .ok_or(BatchAuctionError::Overflow)
+        }
+
+        /// Output de produto constante na fee `fee_bps`, como o router cota
+        fn amount_out(
+            amount_in: Balance,
+            reserve_in: Balance,
+            reserve_out: Balance,
+            fee_bps: u16,
+        ) -> Result<Balance, BatchAuctionError> {
+            let amount_in_with_fee = U256::from(amount_in)
+                .checked_mul(Self::fee_factor(fee_bps)?)
+                .ok_or(BatchAuctionError::Overflow)?;
+            let numerator = amount_in_with_fee
+                .checked_mul(U256::from(reserve_out))
+                .ok_or(BatchAuctionError::Overflow)?;
+            let denominator = U256::from(reserve_in)
+                .checked_mul(U256::from(constants::BPS_DENOMINATOR))
+                .and_then(|value| value.checked_add(amount_in_with_fee))
+                .ok_or(BatchAuctionError::Overflow)?;
+            if denominator.is_zero() {
+                return Ok(0);
+            }
+            let amount_out = numerator.checked_div(denominator).ok_or(BatchAuctionError::Overflow)?;
+            u128::try_from(amount_out).map_err(|_| BatchAuctionError::Overflow)
+        }
+
+        /// Amount líquido a vender no pool para que todos recebam o mesmo preço
+        ///
+        /// Vendendo `n` de X contra reserves `(x, y)` com fee `f`, o preço médio do
+        /// pool `m(n)/n` é o preço uniforme quando `(A_x − n)·m(n) = A_y·n`, o que dá
+        /// `n = (A_x·y·f − A_y·x) / (f·(y + A_y))`.
+        fn net_swap_amount(
+            total_in: Balance,
+            total_other: Balance,
+            reserve_in: Balance,
+            reserve_out: Balance,
+            fee_bps: u16,
+        ) -> Result<Balance, BatchAuctionError> {
+            let fee_numerator = Self::fee_factor(fee_bps)?;
+            let selling = U256::from(total_in)
+                .checked_mul(U256::from(reserve_out))
+                .and_then(|value| value.checked_mul(fee_numerator))
+                .ok_or(BatchAuctionError::Overflow)?;
+            let buying = U256::from(total_other)
+                .checked_mul(U256::from(reserve_in))
+                .and_then(|value| value.checked_mul(U256::from(constants::BPS_DENOMINATOR)))
+                .ok_or(BatchAuctionError::Overflow)?;
+            if selling <= buying {
+                return Ok(0);
+            }
+            let denominator = U256::from(reserve_out)
+                .checked_add(U256::from(total_other))
+                .and_then(|value| value.checked_mul(fee_numerator))
+                .ok_or(BatchAuctionError::Overflow)?;
+            let amount = selling
+                .checked_sub(buying)
+                .and_then(|value| value.checked_div(denominator))
+                .ok_or(BatchAuctionError::Overflow)?;
+            u128::try_from(amount).map_err(|_| BatchAuctionError::Overflow)
+        }
+
+        /// Calcula o preço uniforme do lote contra as reserves do par
+        ///
+        /// Ordens cujo `min_out` não é atendido são excluídas e o preço é
+        /// recalculado até estabilizar. `fee_bps` é a fee cobrada pelo par, de
+        /// modo que o output esperado do swap é o mesmo que o router entrega.
+        fn compute_clearing(
+            reserve_0: Balance,
+            reserve_1: Balance,
+            orders: &[RevealedOrder],
+            fee_bps: u16,
+        ) -> Result<Clearing, BatchAuctionError> {
+            let mut filled = vec![true; orders.len()];
+
+            for _ in 0..=orders.len() {
+                let mut clearing = Clearing {
+                    filled: filled.clone(),
+                    ..Default::default()
+                };
+                for (order, _) in orders.iter().zip(filled.iter()).filter(|(_, filled)| **filled) {
+                    if order.sell_token_0 {
+                        clearing.total_in_0 = clearing.total_in_0.checked_add(order.amount_in).ok_or(BatchAuctionError::Overflow)?;
+                    } else {
+                        clearing.total_in_1 = clearing.total_in_1.checked_add(order.amount_in).ok_or(BatchAuctionError::Overflow)?;
+                    }
+                }
+
+                clearing.pot_0 = clearing.total_in_0;
+                clearing.pot_1 = clearing.total_in_1;
+                let net_0 = Self::net_swap_amount(clearing.total_in_0, clearing.total_in_1, reserve_0, reserve_1, fee_bps)?;
+                let net_1 = Self::net_swap_amount(clearing.total_in_1, clearing.total_in_0, reserve_1, reserve_0, fee_bps)?;
+                if net_0 > 0 {
+                    let out = Self::amount_out(net_0, reserve_0, reserve_1, fee_bps)?;
+                    clearing.pot_0 = clearing.pot_0.checked_sub(net_0).ok_or(BatchAuctionError::Overflow)?;
+                    clearing.pot_1 = clearing.pot_1.checked_add(out).ok_or(BatchAuctionError::Overflow)?;
+                    clearing.swap = Some((true, net_0, out));
+                } else if net_1 > 0 {
+                    let out = Self::amount_out(net_1, reserve_1, reserve_0, fee_bps)?;
+                    clearing.pot_1 = clearing.pot_1.checked_sub(net_1).ok_or(BatchAuctionError::Overflow)?;
+                    clearing.pot_0 = clearing.pot_0.checked_add(out).ok_or(BatchAuctionError::Overflow)?;
+                    clearing.swap = Some((false, net_1, out));
+                }
+
+                // Excluir ordens que o preço uniforme não atende
+                let mut changed = false;
+                for (order, is_filled) in orders.iter().zip(filled.iter_mut()) {
+                    if *is_filled && Self::payout_of(order, &clearing)? < order.min_out {
+                        *is_filled = false;
+                        changed = true;
+                    }
+                }
+                if !changed {
+                    return Ok(clearing);
+                }
+            }
+
+            Err(BatchAuctionError::Overflow)
+        }
+
+        /// Output pro-rata de uma ordem executada
+        fn payout_of(order: &RevealedOrder, clearing: &Clearing) -> Result<Balance, BatchAuctionError> {
+            let (pot, total) = if order.sell_token_0 {
+                (clearing.pot_1, clearing.total_in_0)
+            } else {
+                (clearing.pot_0, clearing.total_in_1)
+            };
+            if total == 0 {
+                return Ok(0);
+            }
+            let payout = U256::from(order.amount_in)
+                .checked_mul(U256::from(pot))
+                .and_then(|value| value.checked_div(U256::from(total)))
+                .ok_or(BatchAuctionError::Overflow)?;
+            u128::try_from(payout).map_err(|_| BatchAuctionError::Overflow)
+        }
+
+        /// Credita outputs das ordens executadas e reembolsos das excluídas
+        fn credit_payouts(&mut self, batch: &Batch, clearing: &Clearing) -> Result<(), BatchAuctionError> {
+            for (order, filled) in batch.orders.iter().zip(clearing.filled.iter()) {
+                let (token, amount) = match (*filled, order.sell_token_0) {
+                    (true, true) => (batch.token_1, Self::payout_of(order, clearing)?),
+                    (true, false) => (batch.token_0, Self::payout_of(order, clearing)?),
+                    (false, true) => (batch.token_0, order.amount_in),
+                    (false, false) => (batch.token_1, order.amount_in),
+                };
+                if amount > 0 {
+                    let balance = self
+                        .claimable_of(order.owner, token)
+                        .checked_add(amount)
+                        .ok_or(BatchAuctionError::Overflow)?;
+                    self.claimable.insert((order.owner, token), &balance);
+                }
+            }
+            Ok(())
+        }
+
+        // ========================================
+        // FUNÇÕES INTERNAS (CHAMADAS CROSS-CONTRACT)
+        // ========================================
+
+        /// Resolve o par de dois tokens no factory
+        fn resolve_pair(&self, token_a: AccountId, token_b: AccountId) -> Result<AccountId, BatchAuctionError> {
+            build_call::<DefaultEnvironment>()
+                .call(self.factory)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selectors::FACTORY_GET_PAIR))
+                        .push_arg(token_a)
+                        .push_arg(token_b),
+                )
+                .returns::<Option<AccountId>>()
+                .try_invoke()
+                .map_err(|_| BatchAuctionError::QueryFailed)?
+                .map_err(|_| BatchAuctionError::QueryFailed)?
+                .ok_or(BatchAuctionError::PairNotFound)
+        }
+
+        /// Consulta uma mensagem sem argumentos do Pair Contract
+        fn query<R: scale::Decode>(&self, pair: AccountId, selector: [u8; 4]) -> Result<R, BatchAuctionError> {
+            build_call::<DefaultEnvironment>()
+                .call(pair)
+                .exec_input(ExecutionInput::new(Selector::new(selector)))
+                .returns::<R>()
+                .try_invoke()
+                .map_err(|_| BatchAuctionError::QueryFailed)?
+                .map_err(|_| BatchAuctionError::QueryFailed)
+        }
+
+        /// Fee de swap atual do par em bps (estática quando o par não expõe `current_fee_bps`)
+        fn pair_fee_bps(&self, pair: AccountId) -> u16 {
+            self.query(pair, selectors::PAIR_CURRENT_FEE_BPS)
+                .unwrap_or(constants::PAIR_STATIC_FEE_BPS)
+        }
+
+        /// Troca o excedente no par via router, com output para este contrato
+        fn swap_via_router(
+            &self,
+            token_in: AccountId,
+            token_out: AccountId,
+            amount_in: Balance,
+            amount_out_min: Balance,
+        ) -> Result<Balance, BatchAuctionError> {
+            build_call::<DefaultEnvironment>()
+                .call(token_in)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selectors::PSP22_APPROVE))
+                        .push_arg(self.router)
+                        .push_arg(amount_in),
+                )
+                .returns::<Result<(), PSP22Error>>()
+                .try_invoke()
+                .map_err(|_| BatchAuctionError::TransferFailed)?
+                .map_err(|_| BatchAuctionError::TransferFailed)??;
+
+            let amounts = build_call::<DefaultEnvironment>()
+                .call(self.router)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selectors::ROUTER_SWAP_EXACT_TOKENS_FOR_TOKENS))
+                        .push_arg(amount_in)
+                        .push_arg(amount_out_min)
+                        .push_arg(vec![token_in, token_out])
+                        .push_arg(self.env().account_id())
+                        .push_arg(self.env().block_timestamp()),
+                )
+                .returns::<Result<Vec<Balance>, RouterError>>()
+                .try_invoke()
+                .map_err(|_| BatchAuctionError::RouterCallFailed)?
+                .map_err(|_| BatchAuctionError::RouterCallFailed)?
+                .map_err(BatchAuctionError::Router)?;
+
+            amounts.last().copied().ok_or(BatchAuctionError::RouterCallFailed)
+        }
+
+        /// Transfere tokens PSP22 mantidos por este contrato
+        fn transfer_token(&self, token: AccountId, to: AccountId, amount: Balance) -> Result<(), BatchAuctionError> {
+            build_call::<DefaultEnvironment>()
+                .call(token)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selectors::PSP22_TRANSFER))
+                        .push_arg(to)
+                        .push_arg(amount)
+                        .push_arg(Vec::<u8>::new()),
+                )
+                .returns::<Result<(), PSP22Error>>()
+                .try_invoke()
+                .map_err(|_| BatchAuctionError::TransferFailed)?
+                .map_err(|_| BatchAuctionError::TransferFailed)??;
+            Ok(())
+        }
+
+        /// Puxa tokens PSP22 de `from` usando a allowance concedida
+        fn transfer_from_token(&self, token: AccountId, from: AccountId, to: AccountId, amount: Balance) -> Result<(), BatchAuctionError> {
+            build_call::<DefaultEnvironment>()
+                .call(token)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selectors::PSP22_TRANSFER_FROM))
+                        .push_arg(from)
+                        .push_arg(to)
+                        .push_arg(amount)
+                        .push_arg(Vec::<u8>::new()),
+                )
+                .returns::<Result<(), PSP22Error>>()
+                .try_invoke()
+                .map_err(|_| BatchAuctionError::TransferFailed)?
+                .map_err(|_| BatchAuctionError::TransferFailed)??;
+            Ok(())
+        }
+    }
+
+    // ========================================
+    // TESTES UNITÁRIOS
+    // ========================================
+
+    #[cfg(test)]
+    #[allow(clippy::arithmetic_side_effects)]
+    mod tests {
+        use super::*;
+
+        const BOND: Balance = 1_000;
+        const FEE_BPS: u16 = constants::PAIR_STATIC_FEE_BPS;
+
+        fn default_accounts() -> ink::env::test::DefaultAccounts<DefaultEnvironment> {
+            ink::env::test::default_accounts::<DefaultEnvironment>()
+        }
+
+        fn set_sender(sender: AccountId) {
+            ink::env::test::set_caller::<DefaultEnvironment>(sender);
+        }
+
+        fn advance_blocks(blocks: u32) {
+            for _ in 0..blocks {
+                ink::env::test::advance_block::<DefaultEnvironment>();
+            }
+        }
+
+        fn order(owner: AccountId, sell_token_0: bool, amount_in: Balance, min_out: Balance) -> RevealedOrder {
+            RevealedOrder {
+                owner,
+                sell_token_0,
+                amount_in,
+                min_out,
+            }
+        }
+
+        #[ink::test]
+        fn test_commit_requires_bond_and_commit_phase() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            let mut contract = BatchAuctionContract::new(accounts.bob, accounts.charlie, BOND);
+            let commitment = Hash::from([7u8; 32]);
+
+            // RED: Bond incorreto
+            assert_eq!(contract.commit(commitment), Err(BatchAuctionError::InvalidBond));
+
+            // GREEN: Commit com bond na fase de commit
+            ink::env::test::set_value_transferred::<DefaultEnvironment>(BOND);
+            assert_eq!(contract.commit(commitment), Ok(0));
+            assert_eq!(contract.get_commitment(accounts.alice, commitment), Some(Commitment { epoch: 0, bond: BOND }));
+
+            // RED: Commit duplicado
+            assert_eq!(contract.commit(commitment), Err(BatchAuctionError::CommitmentExists));
+
+            // RED: Fase de reveal não aceita commits
+            advance_blocks(constants::COMMIT_BLOCKS);
+            assert_eq!(contract.current_epoch(), (0, Phase::Reveal));
+            assert_eq!(contract.commit(Hash::from([8u8; 32])), Err(BatchAuctionError::WrongPhase));
+        }
+
+        #[ink::test]
+        fn test_reveal_checks_hash_and_phase() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            let mut contract = BatchAuctionContract::new(accounts.bob, accounts.charlie, BOND);
+
+            let path = vec![accounts.django, accounts.eve];
+            let salt = Hash::from([1u8; 32]);
+            let commitment = contract.commitment_hash(path.clone(), 500, 400, salt);
+            ink::env::test::set_value_transferred::<DefaultEnvironment>(BOND);
+            contract.commit(commitment).unwrap();
+
+            // RED: Parâmetros diferentes do commit
+            assert_eq!(
+                contract.reveal(path.clone(), 500, 399, salt),
+                Err(BatchAuctionError::InvalidReveal)
+            );
+            // RED: Ainda na fase de commit
+            assert_eq!(contract.reveal(path.clone(), 500, 400, salt), Err(BatchAuctionError::WrongPhase));
+
+            // RED: Reveal em época posterior
+            advance_blocks(constants::EPOCH_BLOCKS);
+            assert_eq!(contract.reveal(path, 500, 400, salt), Err(BatchAuctionError::EpochMismatch));
+        }
+
+        #[ink::test]
+        fn test_reveal_rejects_dust_orders() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            let mut contract = BatchAuctionContract::new(accounts.bob, accounts.charlie, BOND);
+
+            let path = vec![accounts.django, accounts.eve];
+            let salt = Hash::from([2u8; 32]);
+            let dust = constants::MIN_ORDER_AMOUNT_IN - 1;
+            let commitment = contract.commitment_hash(path.clone(), dust, 0, salt);
+            ink::env::test::set_value_transferred::<DefaultEnvironment>(BOND);
+            contract.commit(commitment).unwrap();
+
+            // RED: Ordem abaixo do mínimo não ocupa vaga no lote
+            advance_blocks(constants::COMMIT_BLOCKS);
+            assert_eq!(contract.reveal(path, dust, 0, salt), Err(BatchAuctionError::OrderTooSmall));
+        }
+
+        #[ink::test]
+        fn test_full_batch_refunds_bond() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            let mut contract = BatchAuctionContract::new(accounts.bob, accounts.charlie, BOND);
+            let contract_id = ink::env::test::callee::<DefaultEnvironment>();
+            ink::env::test::set_account_balance::<DefaultEnvironment>(contract_id, 100_000_000);
+
+            let commitment = Hash::from([3u8; 32]);
+            let pending = Commitment { epoch: 0, bond: BOND };
+            contract.commitments.insert((accounts.frank, commitment), &pending);
+            let full = Batch {
+                token_0: accounts.django,
+                token_1: accounts.eve,
+                orders: vec![order(accounts.alice, true, 1_000_000, 0); constants::MAX_ORDERS_PER_BATCH],
+                settled: false,
+            };
+            let before = ink::env::test::get_account_balance::<DefaultEnvironment>(accounts.frank).unwrap();
+
+            // GREEN: Lote cheio não inclui a ordem, mas devolve o bond
+            let admitted = contract
+                .admit_order(commitment, pending, accounts.charlie, full, order(accounts.frank, true, 1_000_000, 0))
+                .unwrap();
+            assert!(!admitted);
+            assert_eq!(contract.get_commitment(accounts.frank, commitment), None);
+            assert_eq!(contract.get_batch(0, accounts.charlie), None);
+            let after = ink::env::test::get_account_balance::<DefaultEnvironment>(accounts.frank).unwrap();
+            assert_eq!(after, before + BOND);
+
+            // RED: Commit consumido não pode mais ser confiscado
+            advance_blocks(constants::EPOCH_BLOCKS);
+            assert_eq!(
+                contract.forfeit_commitment(accounts.frank, commitment),
+                Err(BatchAuctionError::CommitmentNotFound)
+            );
+        }
+
+        #[ink::test]
+        fn test_unrevealed_commitment_forfeits_bond() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            let mut contract = BatchAuctionContract::new(accounts.bob, accounts.charlie, BOND);
+            let commitment = Hash::from([9u8; 32]);
+            ink::env::test::set_value_transferred::<DefaultEnvironment>(BOND);
+            contract.commit(commitment).unwrap();
+
+            // RED: Ainda revelável
+            set_sender(accounts.frank);
+            assert_eq!(
+                contract.forfeit_commitment(accounts.alice, commitment),
+                Err(BatchAuctionError::CommitmentStillRevealable)
+            );
+
+            // GREEN: Após a época o bond é confiscado
+            advance_blocks(constants::EPOCH_BLOCKS);
+            assert_eq!(contract.forfeit_commitment(accounts.alice, commitment), Ok(BOND));
+            assert_eq!(contract.get_commitment(accounts.alice, commitment), None);
+
+            // RED: Apenas admin saca os bonds confiscados
+            assert_eq!(contract.withdraw_forfeited_bonds(accounts.frank), Err(BatchAuctionError::Unauthorized));
+        }
+
+        #[ink::test]
+        fn test_one_sided_batch_matches_single_swap() {
+            let accounts = default_accounts();
+            let orders = vec![
+                order(accounts.alice, true, 1_000, 0),
+                order(accounts.bob, true, 3_000, 0),
+            ];
+
+            // GREEN: Todo o fluxo vai ao pool e o output é dividido pro-rata
+            let clearing = BatchAuctionContract::compute_clearing(1_000_000, 1_000_000, &orders, FEE_BPS).unwrap();
+            let single = BatchAuctionContract::amount_out(4_000, 1_000_000, 1_000_000, FEE_BPS).unwrap();
+            assert_eq!(clearing.swap, Some((true, 4_000, single)));
+            assert_eq!(clearing.pot_1, single);
+            assert_eq!(BatchAuctionContract::payout_of(&orders[0], &clearing), Ok(single / 4));
+            assert_eq!(BatchAuctionContract::payout_of(&orders[1], &clearing), Ok(single * 3 / 4));
+        }
+
+        #[ink::test]
+        fn test_opposing_flow_clears_at_uniform_price() {
+            let accounts = default_accounts();
+
+            // GREEN: Fluxos iguais no preço do pool são casados sem tocar no par
+            let balanced = vec![
+                order(accounts.alice, true, 1_000, 990),
+                order(accounts.bob, false, 1_000, 990),
+            ];
+            let clearing = BatchAuctionContract::compute_clearing(1_000_000, 1_000_000, &balanced, FEE_BPS).unwrap();
+            assert_eq!(clearing.swap, None);
+            assert_eq!((clearing.pot_0, clearing.pot_1), (1_000, 1_000));
+
+            // GREEN: Excedente vendido no pool; todos do mesmo lado recebem o mesmo preço
+            let imbalanced = vec![
+                order(accounts.alice, true, 5_000, 0),
+                order(accounts.bob, false, 1_000, 0),
+            ];
+            let clearing = BatchAuctionContract::compute_clearing(1_000_000, 1_000_000, &imbalanced, FEE_BPS).unwrap();
+            let (sell_token_0, net, _) = clearing.swap.unwrap();
+            assert!(sell_token_0 && net < 4_000);
+            let to_seller_0 = BatchAuctionContract::payout_of(&imbalanced[0], &clearing).unwrap();
+            let to_seller_1 = BatchAuctionContract::payout_of(&imbalanced[1], &clearing).unwrap();
+            // Preço uniforme: token_1/token_0 recebido por um lado ≈ inverso do outro
+            let price_0 = U256::from(to_seller_0) * U256::from(1_000_000u32) / U256::from(5_000u32);
+            let price_1 = U256::from(1_000u32) * U256::from(1_000_000u32) / U256::from(to_seller_1);
+            assert!(price_0.abs_diff(price_1) <= U256::from(1_000u32));
+        }
+
+        #[ink::test]
+        fn test_expected_out_uses_pair_fee() {
+            // GREEN: 1000 * 0.995 * 1e6 / (1e6 + 995) = 994, o que o par (0.5%) entrega
+            assert_eq!(BatchAuctionContract::amount_out(1_000, 1_000_000, 1_000_000, FEE_BPS), Ok(994));
+            // RED: A 0.3% o mínimo exigido ao router (996) seria inalcançável
+            assert_eq!(BatchAuctionContract::amount_out(1_000, 1_000_000, 1_000_000, 30), Ok(996));
+
+            // GREEN: Fee maior reduz o swap líquido do lote
+            let at_pair_fee = BatchAuctionContract::net_swap_amount(5_000, 1_000, 1_000_000, 1_000_000, FEE_BPS).unwrap();
+            let at_low_fee = BatchAuctionContract::net_swap_amount(5_000, 1_000, 1_000_000, 1_000_000, 30).unwrap();
+            assert!(at_pair_fee < at_low_fee);
+        }
+
+        #[ink::test]
+        fn test_unsettled_batch_refunds_after_timeout() {
+            let accounts = default_accounts();
+            set_sender(accounts.frank);
+            let mut contract = BatchAuctionContract::new(accounts.bob, accounts.charlie, BOND);
+            let pair = accounts.charlie;
+            let batch = Batch {
+                token_0: accounts.django,
+                token_1: accounts.eve,
+                orders: vec![
+                    order(accounts.alice, true, 1_000_000, 0),
+                    order(accounts.bob, false, 2_000_000, 0),
+                ],
+                settled: false,
+            };
+            contract.batches.insert((0, pair), &batch);
+
+            // RED: Dentro da janela de liquidação
+            advance_blocks(constants::EPOCH_BLOCKS * constants::SETTLEMENT_TIMEOUT_EPOCHS as u32);
+            assert_eq!(contract.refund_batch(0, pair), Err(BatchAuctionError::SettlementWindowOpen));
+
+            // GREEN: Após o timeout, qualquer conta reembolsa o lote inteiro
+            advance_blocks(constants::EPOCH_BLOCKS);
+            assert_eq!(contract.refund_batch(0, pair), Ok(2));
+            assert_eq!(contract.claimable_of(accounts.alice, accounts.django), 1_000_000);
+            assert_eq!(contract.claimable_of(accounts.bob, accounts.eve), 2_000_000);
+
+            // RED: Lote reembolsado não pode ser liquidado nem reembolsado de novo
+            assert_eq!(contract.settle(0, pair), Err(BatchAuctionError::BatchAlreadySettled));
+            assert_eq!(contract.refund_batch(0, pair), Err(BatchAuctionError::BatchAlreadySettled));
+            assert_eq!(contract.refund_batch(1, pair), Err(BatchAuctionError::SettlementWindowOpen));
+        }
+
+        #[ink::test]
+        fn test_orders_below_min_out_are_excluded() {
+            let accounts = default_accounts();
+            let orders = vec![
+                order(accounts.alice, true, 1_000, 0),
+                // Exige preço melhor que o possível
+                order(accounts.bob, true, 1_000, 1_000),
+            ];
+
+            // GREEN: Ordem excluída e reembolsada; a outra executa sozinha
+            let clearing = BatchAuctionContract::compute_clearing(1_000_000, 1_000_000, &orders, FEE_BPS).unwrap();
+            assert_eq!(clearing.filled, vec![true, false]);
+            assert_eq!(clearing.total_in_0, 1_000);
+
+            let mut contract = BatchAuctionContract::new(accounts.bob, accounts.charlie, BOND);
+            let batch = Batch {
+                token_0: accounts.django,
+                token_1: accounts.eve,
+                orders,
+                settled: true,
+            };
+            contract.credit_payouts(&batch, &clearing).unwrap();
+            assert_eq!(contract.claimable_of(accounts.bob, accounts.django), 1_000);
+            assert_eq!(contract.claimable_of(accounts.alice, accounts.eve), clearing.pot_1);
+        }
+    }
+}
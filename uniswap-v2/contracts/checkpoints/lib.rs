@@ -0,0 +1,125 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Checkpoints de balance/supply por bloco (estilo ERC20Votes) compartilhados
+//! pelo WNATIVE e pelos LP tokens dos pares.
+//!
+//! Cada conta (e o supply) guarda uma lista de checkpoints `(bloco, valor)` em
+//! ordem crescente de bloco, com no máximo um checkpoint por bloco: o primeiro
+//! movimento do bloco grava um checkpoint novo e incrementa a contagem, os
+//! seguintes sobrescrevem o mesmo checkpoint. Uma transferência custa portanto
+//! até quatro escritas de storage (checkpoint e contagem de `from` e de `to`),
+//! e duas quando as contas já se moveram no bloco.
+
+use ink::env::{DefaultEnvironment, Environment};
+
+type Balance = <DefaultEnvironment as Environment>::Balance;
+type BlockNumber = <DefaultEnvironment as Environment>::BlockNumber;
+
+/// Checkpoint de balance/supply ao final de um bloco
+#[derive(Debug, Default, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub struct Checkpoint {
+    pub block: BlockNumber,
+    pub value: Balance,
+}
+
+/// Posição onde gravar o checkpoint do bloco `block`
+///
+/// `last_block` é o bloco do último checkpoint (se houver). Retorna o índice a
+/// escrever e a nova contagem; a contagem só muda no primeiro checkpoint do bloco.
+pub fn slot(count: u32, last_block: Option<BlockNumber>, block: BlockNumber) -> (u32, u32) {
+    match (count.checked_sub(1), last_block) {
+        (Some(index), Some(last)) if last == block => (index, count),
+        _ => (count, count.saturating_add(1)),
+    }
+}
+
+/// Busca binária do último checkpoint com `checkpoint.block <= block`
+pub fn lookup(count: u32, block: BlockNumber, get: impl Fn(u32) -> Option<Checkpoint>) -> Balance {
+    let (mut low, mut high) = (0u32, count);
+    while low < high {
+        let mid = low.saturating_add(high.saturating_sub(low).saturating_div(2));
+        if get(mid).map_or(0, |c| c.block) <= block {
+            low = mid.saturating_add(1);
+        } else {
+            high = mid;
+        }
+    }
+    low.checked_sub(1)
+        .and_then(get)
+        .map_or(0, |c| c.value)
+}
+
+/// Menor valor ao final de cada bloco desde `block` (inclusive)
+///
+/// Percorre os checkpoints do mais recente até o vigente em `block`;
+/// sem checkpoint até `block` o valor naquele bloco era 0.
+pub fn min_since(count: u32, block: BlockNumber, get: impl Fn(u32) -> Option<Checkpoint>) -> Balance {
+    let mut min = Balance::MAX;
+    let mut index = count;
+    while let Some(previous) = index.checked_sub(1) {
+        let Some(checkpoint) = get(previous) else { break };
+        min = min.min(checkpoint.value);
+        if checkpoint.block <= block {
+            return min;
+        }
+        index = previous;
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slot_writes_once_per_block() {
+        // GREEN: Primeiro checkpoint da conta
+        assert_eq!(slot(0, None, 5), (0, 1));
+
+        // GREEN: Mesmo bloco sobrescreve sem mudar a contagem
+        assert_eq!(slot(3, Some(7), 7), (2, 3));
+
+        // GREEN: Bloco novo acrescenta
+        assert_eq!(slot(3, Some(7), 8), (3, 4));
+    }
+
+    #[test]
+    fn test_lookup() {
+        let checkpoints = [
+            Checkpoint { block: 2, value: 10 },
+            Checkpoint { block: 5, value: 20 },
+            Checkpoint { block: 9, value: 5 },
+        ];
+        let get = |index: u32| checkpoints.get(index as usize).copied();
+
+        assert_eq!(lookup(0, 10, get), 0);
+        assert_eq!(lookup(3, 1, get), 0);
+        assert_eq!(lookup(3, 2, get), 10);
+        assert_eq!(lookup(3, 8, get), 20);
+        assert_eq!(lookup(3, 9, get), 5);
+        assert_eq!(lookup(3, 1_000, get), 5);
+    }
+
+    #[test]
+    fn test_min_since_requires_continuous_presence() {
+        let checkpoints = [
+            Checkpoint { block: 10, value: 1_000 },
+            Checkpoint { block: 20, value: 100 },
+            Checkpoint { block: 21, value: 1_000 },
+        ];
+        let get = |index: u32| checkpoints.get(index as usize).copied();
+
+        // RED: Antes do primeiro checkpoint não havia valor
+        assert_eq!(min_since(3, 9, get), 0);
+        assert_eq!(min_since(0, 9, get), 0);
+
+        // RED: A queda no bloco 20 conta mesmo com o valor de volta no 21
+        assert_eq!(min_since(3, 10, get), 100);
+        assert_eq!(min_since(3, 20, get), 100);
+
+        // GREEN: Valor presente em todos os blocos desde o 21
+        assert_eq!(min_since(3, 21, get), 1_000);
+        assert_eq!(min_since(3, 50, get), 1_000);
+    }
+}
@@ -0,0 +1,1081 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![warn(clippy::arithmetic_side_effects)]
+#[ink::contract]
+pub mod dca_vault_contract {
+    use psp22::PSP22Error;
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+    use primitive_types::U256;
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::DefaultEnvironment;
+    use router_contract::router_contract::RouterError;
+
+    // ========================================
+    // DCA VAULT CONTRACT - COMPRAS RECORRENTES VIA ROUTER
+    // ========================================
+    //
+    // Usuários depositam um orçamento (`budget`) em `token_in` e definem um
+    // intervalo, um amount por execução e um path. Keepers disparam as
+    // execuções através de `RouterContract::swap_exact_tokens_for_tokens`,
+    // usando um `amount_out_min` derivado do TWAP dos pares do path.
+    //
+    // ## Funcionalidades:
+    // - Posições com orçamento em escrow e execuções periódicas
+    // - Tokens comprados acumulam na posição até o saque
+    // - Pausar, retomar e cancelar (com reembolso do orçamento restante)
+    //
+    // ## Oráculo TWAP:
+    // Para cada par o vault mantém duas observações dos preços cumulativos.
+    // O TWAP usa a observação mais recente com idade >= `TWAP_PERIOD`, o que
+    // impede que uma manipulação pontual das reserves defina o `amount_out_min`.
+
+    // ========================================
+    // EVENTOS (PARA INDEXADORES E UIS)
+    // ========================================
+
+    /// Emitido quando uma posição DCA é criada
+    #[ink(event)]
+    pub struct PositionCreated {
+        #[ink(topic)]
+        pub position_id: u64,
+        #[ink(topic)]
+        pub owner: AccountId,
+        pub path: Vec<AccountId>,
+        pub budget: Balance,
+        pub amount_per_execution: Balance,
+        pub interval: u64,
+    }
+
+    /// Emitido a cada execução de compra
+    #[ink(event)]
+    pub struct DcaExecuted {
+        #[ink(topic)]
+        pub position_id: u64,
+        #[ink(topic)]
+        pub keeper: AccountId,
+        pub amount_in: Balance,
+        pub amount_out: Balance,
+        pub amount_out_min: Balance,
+        pub budget_remaining: Balance,
+    }
+
+    /// Emitido quando o status de uma posição muda (pausa, retomada ou cancelamento)
+    #[ink(event)]
+    pub struct PositionStatusChanged {
+        #[ink(topic)]
+        pub position_id: u64,
+        pub status: PositionStatus,
+    }
+
+    /// Emitido quando o dono saca tokens (comprados e/ou reembolso)
+    #[ink(event)]
+    pub struct Withdrawn {
+        #[ink(topic)]
+        pub position_id: u64,
+        #[ink(topic)]
+        pub owner: AccountId,
+        pub amount_out: Balance,
+        pub refunded_in: Balance,
+    }
+
+    // ========================================
+    // ERROS ESPECÍFICOS DO DCA VAULT
+    // ========================================
+
+    /// Erros que podem ocorrer nas operações do DCA vault
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum DcaError {
+        /// Endereço zero informado
+        ZeroAddress,
+        /// Amount zero ou amount por execução maior que o orçamento
+        InvalidAmount,
+        /// Path de swap inválido
+        InvalidPath,
+        /// Intervalo abaixo do mínimo
+        InvalidInterval,
+        /// Slippage máxima acima do limite
+        SlippageTooHigh,
+        /// Posição não encontrada
+        PositionNotFound,
+        /// Caller não é o dono da posição
+        Unauthorized,
+        /// Posição não está ativa
+        PositionNotActive,
+        /// Posição não está pausada
+        PositionNotPaused,
+        /// Posição cancelada
+        PositionCancelled,
+        /// Intervalo desde a última execução ainda não passou
+        TooEarly,
+        /// Orçamento da posição esgotado
+        BudgetExhausted,
+        /// Nada a sacar
+        NothingToWithdraw,
+        /// Limite de posições por dono atingido
+        TooManyPositions,
+        /// Par do path não existe no factory
+        PairNotFound,
+        /// Não há observação TWAP com idade suficiente
+        TwapUnavailable,
+        /// Consulta a par/factory falhou
+        QueryFailed,
+        /// Chamada ao router falhou
+        RouterCallFailed,
+        /// Transferência de token falhou
+        TransferFailed,
+        /// Overflow aritmético
+        Overflow,
+        /// Erro no token PSP22 subjacente
+        PSP22(PSP22Error),
+        /// Swap rejeitado pelo router
+        Router(RouterError),
+        /// Vault recebeu menos que o `amount_out_min` da execução
+        InsufficientOutput,
+    }
+
+    impl From<PSP22Error> for DcaError {
+        fn from(error: PSP22Error) -> Self {
+            DcaError::PSP22(error)
+        }
+    }
+
+    // ========================================
+    // TIPOS AUXILIARES
+    // ========================================
+
+    /// Status de uma posição DCA
+    #[derive(Debug, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum PositionStatus {
+        /// Execuções habilitadas
+        Active,
+        /// Execuções suspensas pelo dono
+        Paused,
+        /// Encerrada com reembolso
+        Cancelled,
+    }
+
+    /// Posição DCA de um usuário
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct DcaPosition {
+        /// Identificador único
+        pub id: u64,
+        /// Dono da posição
+        pub owner: AccountId,
+        /// Path do swap (primeiro = token gasto, último = token comprado)
+        pub path: Vec<AccountId>,
+        /// Par de cada hop do path (resolvidos no factory)
+        pub pairs: Vec<AccountId>,
+        /// Orçamento ainda não gasto, em escrow
+        pub budget_remaining: Balance,
+        /// Amount gasto em cada execução
+        pub amount_per_execution: Balance,
+        /// Intervalo mínimo entre execuções (ms)
+        pub interval: u64,
+        /// Timestamp a partir do qual a próxima execução é permitida
+        pub next_execution: Timestamp,
+        /// Slippage máxima tolerada sobre o TWAP (basis points)
+        pub max_slippage_bps: u16,
+        /// Número de execuções realizadas
+        pub executions: u32,
+        /// Total já gasto do orçamento
+        pub total_spent: Balance,
+        /// Tokens comprados aguardando saque
+        pub accrued_out: Balance,
+        /// Status atual
+        pub status: PositionStatus,
+    }
+
+    /// Observação dos preços cumulativos de um par
+    #[derive(Debug, Default, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Observation {
+        pub price_0_cumulative: u128,
+        pub price_1_cumulative: u128,
+        pub timestamp: Timestamp,
+    }
+
+    /// Janela deslizante de duas observações por par
+    #[derive(Debug, Default, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct TwapWindow {
+        pub previous: Option<Observation>,
+        pub latest: Option<Observation>,
+    }
+
+    // ========================================
+    // CONSTANTES
+    // ========================================
+    mod constants {
+        /// Denominador em basis points (100% = 10000)
+        pub const BPS_DENOMINATOR: u128 = 10_000;
+
+        /// Slippage máxima configurável sobre o TWAP (10%)
+        pub const MAX_SLIPPAGE_BPS: u16 = 1_000;
+
+        /// Intervalo mínimo entre execuções (1 hora em ms)
+        pub const MIN_INTERVAL: u64 = 60 * 60 * 1000;
+
+        /// Idade mínima da observação usada no TWAP (30 minutos em ms)
+        pub const TWAP_PERIOD: u64 = 30 * 60 * 1000;
+
+        /// Escala dos preços cumulativos do Pair (UQ112)
+        pub const UQ112: u128 = 2_u128.pow(112);
+
+        /// Número máximo de tokens no path
+        pub const MAX_PATH_LENGTH: usize = 4;
+
+        /// Número máximo de posições abertas por dono
+        pub const MAX_POSITIONS_PER_OWNER: usize = 16;
+    }
+
+    /// Selectors das chamadas cross-contract
+    mod selectors {
+        pub const FACTORY_GET_PAIR: [u8; 4] = ink::selector_bytes!("get_pair");
+        pub const PAIR_TOKEN_0: [u8; 4] = ink::selector_bytes!("token_0");
+        pub const PAIR_GET_RESERVES: [u8; 4] = ink::selector_bytes!("get_reserves");
+        pub const PAIR_PRICE_0_CUMULATIVE_LAST: [u8; 4] = ink::selector_bytes!("price_0_cumulative_last");
+        pub const PAIR_PRICE_1_CUMULATIVE_LAST: [u8; 4] = ink::selector_bytes!("price_1_cumulative_last");
+        pub const ROUTER_SWAP_EXACT_TOKENS_FOR_TOKENS: [u8; 4] = ink::selector_bytes!("swap_exact_tokens_for_tokens");
+        pub const PSP22_TRANSFER: [u8; 4] = ink::selector_bytes!("PSP22::transfer");
+        pub const PSP22_TRANSFER_FROM: [u8; 4] = ink::selector_bytes!("PSP22::transfer_from");
+        pub const PSP22_APPROVE: [u8; 4] = ink::selector_bytes!("PSP22::approve");
+        pub const PSP22_BALANCE_OF: [u8; 4] = ink::selector_bytes!("PSP22::balance_of");
+    }
+
+    // ========================================
+    // STORAGE DO DCA VAULT
+    // ========================================
+
+    #[ink(storage)]
+    pub struct DcaVaultContract {
+        /// Router usado nas execuções
+        router: AccountId,
+        /// Factory usado para resolver os pares do path
+        factory: AccountId,
+        /// Próximo id de posição
+        next_position_id: u64,
+        /// Posições por id
+        positions: Mapping<u64, DcaPosition>,
+        /// Ids das posições não canceladas por dono
+        owner_positions: Mapping<AccountId, Vec<u64>>,
+        /// Observações TWAP por par
+        twap_windows: Mapping<AccountId, TwapWindow>,
+    }
+
+    impl DcaVaultContract {
+        /// Construtor do DCA Vault
+        #[ink(constructor)]
+        pub fn new(router: AccountId, factory: AccountId) -> Self {
+            Self {
+                router,
+                factory,
+                next_position_id: 1,
+                positions: Mapping::default(),
+                owner_positions: Mapping::default(),
+                twap_windows: Mapping::default(),
+            }
+        }
+
+        // ========================================
+        // QUERIES (READ-ONLY)
+        // ========================================
+
+        /// Retorna o router usado nas execuções
+        #[ink(message)]
+        pub fn router(&self) -> AccountId {
+            self.router
+        }
+
+        /// Retorna o factory usado para resolver pares
+        #[ink(message)]
+        pub fn factory(&self) -> AccountId {
+            self.factory
+        }
+
+        /// Retorna uma posição pelo id
+        #[ink(message)]
+        pub fn get_position(&self, position_id: u64) -> Option<DcaPosition> {
+            self.positions.get(position_id)
+        }
+
+        /// Retorna as posições não canceladas de um dono
+        #[ink(message)]
+        pub fn get_positions_by_owner(&self, owner: AccountId) -> Vec<DcaPosition> {
+            self.owner_positions
+                .get(owner)
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|id| self.positions.get(id))
+                .collect()
+        }
+
+        /// Retorna a janela de observações TWAP de um par
+        #[ink(message)]
+        pub fn get_twap_window(&self, pair: AccountId) -> TwapWindow {
+            self.twap_windows.get(pair).unwrap_or_default()
+        }
+
+        /// Output esperado pelo TWAP para a próxima execução de uma posição
+        /// (antes da tolerância de slippage)
+        #[ink(message)]
+        pub fn quote_twap(&self, position_id: u64) -> Result<Balance, DcaError> {
+            let position = self.positions.get(position_id).ok_or(DcaError::PositionNotFound)?;
+            let amount_in = position.amount_per_execution.min(position.budget_remaining);
+            self.twap_amount_out(&position, amount_in)
+        }
+
+        // ========================================
+        // OPERAÇÕES DE POSIÇÃO
+        // ========================================
+
+        /// Cria uma posição DCA depositando `budget` do primeiro token do path
+        ///
+        /// O caller precisa ter aprovado este contrato no token de entrada.
+        /// A criação registra uma observação de cada par do path; a primeira
+        /// execução só é aceita quando houver uma observação com idade
+        /// >= `TWAP_PERIOD` (antes disso `execute` retorna `TwapUnavailable`).
+        ///
+        /// # Parâmetros
+        /// - `path`: Tokens do swap (2 a 4)
+        /// - `budget`: Orçamento total em escrow
+        /// - `amount_per_execution`: Amount gasto por execução
+        /// - `interval`: Intervalo mínimo entre execuções (ms)
+        /// - `max_slippage_bps`: Tolerância sobre o TWAP no `amount_out_min`
+        ///
+        /// # Retorna
+        /// - Id da nova posição
+        #[ink(message)]
+        pub fn create_position(
+            &mut self,
+            path: Vec<AccountId>,
+            budget: Balance,
+            amount_per_execution: Balance,
+            interval: u64,
+            max_slippage_bps: u16,
+        ) -> Result<u64, DcaError> {
+            let owner = self.env().caller();
+            Self::validate_path(&path)?;
+            if budget == 0 || amount_per_execution == 0 || amount_per_execution > budget {
+                return Err(DcaError::InvalidAmount);
+            }
+            if interval < constants::MIN_INTERVAL {
+                return Err(DcaError::InvalidInterval);
+            }
+            if max_slippage_bps > constants::MAX_SLIPPAGE_BPS {
+                return Err(DcaError::SlippageTooHigh);
+            }
+
+            if self.owner_positions.get(owner).unwrap_or_default().len() >= constants::MAX_POSITIONS_PER_OWNER {
+                return Err(DcaError::TooManyPositions);
+            }
+
+            // Resolver o par de cada hop e iniciar sua janela TWAP
+            let mut pairs = Vec::with_capacity(path.len().saturating_sub(1));
+            for hop in path.windows(2) {
+                let pair = self.resolve_pair(hop[0], hop[1])?;
+                self.record_observation(pair)?;
+                pairs.push(pair);
+            }
+
+            let position_id = self.next_position_id;
+            self.next_position_id = position_id.checked_add(1).ok_or(DcaError::Overflow)?;
+
+            let position = DcaPosition {
+                id: position_id,
+                owner,
+                path: path.clone(),
+                pairs,
+                budget_remaining: budget,
+                amount_per_execution,
+                interval,
+                next_execution: self.env().block_timestamp(),
+                max_slippage_bps,
+                executions: 0,
+                total_spent: 0,
+                accrued_out: 0,
+                status: PositionStatus::Active,
+            };
+            self.store_position(&position);
+
+            // Escrow do orçamento
+            self.transfer_from_token(path[0], owner, self.env().account_id(), budget)?;
+
+            self.env().emit_event(PositionCreated {
+                position_id,
+                owner,
+                path,
+                budget,
+                amount_per_execution,
+                interval,
+            });
+
+            Ok(position_id)
+        }
+
+        /// Executa uma compra da posição (qualquer conta pode chamar)
+        ///
+        /// O `amount_out_min` é o output pelo TWAP menos `max_slippage_bps`.
+        /// Sem observação com idade >= `TWAP_PERIOD` não há fallback para o
+        /// preço spot: a execução falha com `TwapUnavailable`.
+        /// Os tokens comprados ficam no vault, creditados na posição pelo
+        /// saldo de `token_out` efetivamente recebido (não pelo retorno do router).
+        ///
+        /// # Retorna
+        /// - Amount comprado nesta execução
+        #[ink(message)]
+        pub fn execute(&mut self, position_id: u64) -> Result<Balance, DcaError> {
+            let mut position = self.positions.get(position_id).ok_or(DcaError::PositionNotFound)?;
+            if position.status != PositionStatus::Active {
+                return Err(DcaError::PositionNotActive);
+            }
+            if position.budget_remaining == 0 {
+                return Err(DcaError::BudgetExhausted);
+            }
+            let now = self.env().block_timestamp();
+            if now < position.next_execution {
+                return Err(DcaError::TooEarly);
+            }
+
+            let amount_in = position.amount_per_execution.min(position.budget_remaining);
+            let expected_out = self.twap_amount_out(&position, amount_in)?;
+            let amount_out_min = Self::apply_slippage(expected_out, position.max_slippage_bps)?;
+
+            // Efeitos antes das chamadas externas
+            position.budget_remaining = position
+                .budget_remaining
+                .checked_sub(amount_in)
+                .ok_or(DcaError::Overflow)?;
+            position.total_spent = position.total_spent.checked_add(amount_in).ok_or(DcaError::Overflow)?;
+            position.executions = position.executions.checked_add(1).ok_or(DcaError::Overflow)?;
+            position.next_execution = now.checked_add(position.interval).ok_or(DcaError::Overflow)?;
+            self.positions.insert(position_id, &position);
+
+            let amount_out = self.swap_via_router(&position.path, amount_in, amount_out_min)?;
+            position.accrued_out = position.accrued_out.checked_add(amount_out).ok_or(DcaError::Overflow)?;
+            self.positions.insert(position_id, &position);
+
+            // Avançar a janela TWAP dos pares usados
+            for pair in position.pairs.iter() {
+                self.record_observation(*pair)?;
+            }
+
+            self.env().emit_event(DcaExecuted {
+                position_id,
+                keeper: self.env().caller(),
+                amount_in,
+                amount_out,
+                amount_out_min,
+                budget_remaining: position.budget_remaining,
+            });
+
+            Ok(amount_out)
+        }
+
+        /// Saca os tokens comprados acumulados na posição (apenas o dono)
+        #[ink(message)]
+        pub fn withdraw(&mut self, position_id: u64) -> Result<Balance, DcaError> {
+            let mut position = self.owned_position(position_id)?;
+            let amount_out = position.accrued_out;
+            if amount_out == 0 {
+                return Err(DcaError::NothingToWithdraw);
+            }
+
+            position.accrued_out = 0;
+            self.positions.insert(position_id, &position);
+            self.transfer_token(Self::token_out(&position), position.owner, amount_out)?;
+
+            self.env().emit_event(Withdrawn {
+                position_id,
+                owner: position.owner,
+                amount_out,
+                refunded_in: 0,
+            });
+
+            Ok(amount_out)
+        }
+
+        /// Pausa as execuções de uma posição ativa (apenas o dono)
+        #[ink(message)]
+        pub fn pause(&mut self, position_id: u64) -> Result<(), DcaError> {
+            let mut position = self.owned_position(position_id)?;
+            if position.status != PositionStatus::Active {
+                return Err(DcaError::PositionNotActive);
+            }
+            self.set_status(&mut position, PositionStatus::Paused);
+            Ok(())
+        }
+
+        /// Retoma uma posição pausada (apenas o dono)
+        ///
+        /// O intervalo desde a última execução continua sendo respeitado.
+        #[ink(message)]
+        pub fn resume(&mut self, position_id: u64) -> Result<(), DcaError> {
+            let mut position = self.owned_position(position_id)?;
+            if position.status != PositionStatus::Paused {
+                return Err(DcaError::PositionNotPaused);
+            }
+            self.set_status(&mut position, PositionStatus::Active);
+            Ok(())
+        }
+
+        /// Cancela a posição, reembolsando o orçamento restante e sacando
+        /// os tokens comprados (apenas o dono)
+        ///
+        /// # Retorna
+        /// - `(refunded_in, amount_out)`
+        #[ink(message)]
+        pub fn cancel(&mut self, position_id: u64) -> Result<(Balance, Balance), DcaError> {
+            let mut position = self.owned_position(position_id)?;
+            if position.status == PositionStatus::Cancelled {
+                return Err(DcaError::PositionCancelled);
+            }
+
+            let refunded_in = position.budget_remaining;
+            let amount_out = position.accrued_out;
+            position.budget_remaining = 0;
+            position.accrued_out = 0;
+            self.set_status(&mut position, PositionStatus::Cancelled);
+
+            let mut owner_ids = self.owner_positions.get(position.owner).unwrap_or_default();
+            owner_ids.retain(|id| *id != position_id);
+            self.owner_positions.insert(position.owner, &owner_ids);
+
+            if refunded_in > 0 {
+                self.transfer_token(position.path[0], position.owner, refunded_in)?;
+            }
+            if amount_out > 0 {
+                self.transfer_token(Self::token_out(&position), position.owner, amount_out)?;
+            }
+
+            self.env().emit_event(Withdrawn {
+                position_id,
+                owner: position.owner,
+                amount_out,
+                refunded_in,
+            });
+
+            Ok((refunded_in, amount_out))
+        }
+
+        /// Registra uma observação de preço do par (qualquer conta pode chamar)
+        ///
+        /// Keepers devem chamar periodicamente para manter o TWAP disponível.
+        /// Só avança a janela se a última observação tiver idade >= `TWAP_PERIOD`.
+        #[ink(message)]
+        pub fn update_observation(&mut self, pair: AccountId) -> Result<(), DcaError> {
+            if pair == AccountId::from([0u8; 32]) {
+                return Err(DcaError::ZeroAddress);
+            }
+            self.record_observation(pair)
+        }
+
+        // ========================================
+        // FUNÇÕES INTERNAS (VALIDAÇÕES E CÁLCULOS)
+        // ========================================
+
+        /// Valida o path de swap
+        fn validate_path(path: &[AccountId]) -> Result<(), DcaError> {
+            if path.len() < 2 || path.len() > constants::MAX_PATH_LENGTH {
+                return Err(DcaError::InvalidPath);
+            }
+            let zero_address = AccountId::from([0u8; 32]);
+            if path.contains(&zero_address) {
+                return Err(DcaError::ZeroAddress);
+            }
+            if path.windows(2).any(|hop| hop[0] == hop[1]) {
+                return Err(DcaError::InvalidPath);
+            }
+            Ok(())
+        }
+
+        /// Carrega uma posição verificando que o caller é o dono
+        /// Grava uma nova posição e a indexa no dono
+        fn store_position(&mut self, position: &DcaPosition) {
+            self.positions.insert(position.id, position);
+            let mut owner_ids = self.owner_positions.get(position.owner).unwrap_or_default();
+            owner_ids.push(position.id);
+            self.owner_positions.insert(position.owner, &owner_ids);
+        }
+
+        /// Output recebido pelo vault, exigindo ao menos `amount_out_min`
+        fn received_output(before: Balance, after: Balance, amount_out_min: Balance) -> Result<Balance, DcaError> {
+            let received = after.checked_sub(before).ok_or(DcaError::InsufficientOutput)?;
+            if received < amount_out_min {
+                return Err(DcaError::InsufficientOutput);
+            }
+            Ok(received)
+        }
+
+        fn owned_position(&self, position_id: u64) -> Result<DcaPosition, DcaError> {
+            let position = self.positions.get(position_id).ok_or(DcaError::PositionNotFound)?;
+            if position.owner != self.env().caller() {
+                return Err(DcaError::Unauthorized);
+            }
+            Ok(position)
+        }
+
+        /// Atualiza o status, persiste e emite evento
+        fn set_status(&mut self, position: &mut DcaPosition, status: PositionStatus) {
+            position.status = status;
+            self.positions.insert(position.id, position);
+            self.env().emit_event(PositionStatusChanged {
+                position_id: position.id,
+                status,
+            });
+        }
+
+        /// Token comprado pela posição (último do path)
+        fn token_out(position: &DcaPosition) -> AccountId {
+            position.path.last().copied().unwrap_or(AccountId::from([0u8; 32]))
+        }
+
+        /// Aplica a tolerância de slippage sobre o output esperado
+        fn apply_slippage(amount: Balance, slippage_bps: u16) -> Result<Balance, DcaError> {
+            let keep_bps = constants::BPS_DENOMINATOR
+                .checked_sub(u128::from(slippage_bps))
+                .ok_or(DcaError::SlippageTooHigh)?;
+            let value = U256::from(amount)
+                .checked_mul(U256::from(keep_bps))
+                .and_then(|value| value.checked_div(U256::from(constants::BPS_DENOMINATOR)))
+                .ok_or(DcaError::Overflow)?;
+            u128::try_from(value).map_err(|_| DcaError::Overflow)
+        }
+
+        /// Preço médio (UQ112) entre duas leituras de um acumulador cumulativo
+        fn average_price(
+            cumulative_start: u128,
+            cumulative_end: u128,
+            start: Timestamp,
+            end: Timestamp,
+        ) -> Result<u128, DcaError> {
+            let elapsed = end.checked_sub(start).filter(|elapsed| *elapsed > 0).ok_or(DcaError::TwapUnavailable)?;
+            cumulative_end
+                .checked_sub(cumulative_start)
+                .ok_or(DcaError::TwapUnavailable)?
+                .checked_div(u128::from(elapsed))
+                .ok_or(DcaError::Overflow)
+        }
+
+        /// Converte um amount usando um preço UQ112
+        fn convert_with_price(amount: Balance, price_uq112: u128) -> Result<Balance, DcaError> {
+            let value = U256::from(amount)
+                .checked_mul(U256::from(price_uq112))
+                .and_then(|value| value.checked_div(U256::from(constants::UQ112)))
+                .ok_or(DcaError::Overflow)?;
+            u128::try_from(value).map_err(|_| DcaError::Overflow)
+        }
+
+        /// Escolhe a observação mais recente com idade >= `TWAP_PERIOD`
+        fn usable_observation(window: &TwapWindow, now: Timestamp) -> Option<Observation> {
+            let old_enough = |observation: &Observation| {
+                now.saturating_sub(observation.timestamp) >= constants::TWAP_PERIOD
+            };
+            window
+                .latest
+                .filter(old_enough)
+                .or_else(|| window.previous.filter(old_enough))
+        }
+
+        /// Avança a janela TWAP se a última observação tiver idade suficiente
+        fn roll_window(window: &mut TwapWindow, observation: Observation) -> bool {
+            match window.latest {
+                Some(latest) if observation.timestamp.saturating_sub(latest.timestamp) < constants::TWAP_PERIOD => false,
+                _ => {
+                    window.previous = window.latest;
+                    window.latest = Some(observation);
+                    true
+                }
+            }
+        }
+
+        /// Output pelo TWAP ao longo de todos os hops da posição
+        fn twap_amount_out(&self, position: &DcaPosition, amount_in: Balance) -> Result<Balance, DcaError> {
+            let now = self.env().block_timestamp();
+            let mut amount = amount_in;
+            for (hop, pair) in position.path.windows(2).zip(position.pairs.iter()) {
+                let window = self.twap_windows.get(pair).unwrap_or_default();
+                let start = Self::usable_observation(&window, now).ok_or(DcaError::TwapUnavailable)?;
+                let current = self.current_observation(*pair)?;
+
+                let token_0: AccountId = self.query(*pair, selectors::PAIR_TOKEN_0)?;
+                let price = if hop[0] == token_0 {
+                    Self::average_price(start.price_0_cumulative, current.price_0_cumulative, start.timestamp, now)?
+                } else {
+                    Self::average_price(start.price_1_cumulative, current.price_1_cumulative, start.timestamp, now)?
+                };
+                amount = Self::convert_with_price(amount, price)?;
+            }
+            Ok(amount)
+        }
+
+        /// Grava a observação atual do par na janela TWAP
+        fn record_observation(&mut self, pair: AccountId) -> Result<(), DcaError> {
+            let observation = self.current_observation(pair)?;
+            let mut window = self.twap_windows.get(pair).unwrap_or_default();
+            if Self::roll_window(&mut window, observation) {
+                self.twap_windows.insert(pair, &window);
+            }
+            Ok(())
+        }
+
+        // ========================================
+        // FUNÇÕES INTERNAS (CHAMADAS CROSS-CONTRACT)
+        // ========================================
+
+        /// Preços cumulativos do par extrapolados até o bloco atual
+        fn current_observation(&self, pair: AccountId) -> Result<Observation, DcaError> {
+            let now = self.env().block_timestamp();
+            let mut price_0_cumulative: u128 = self.query(pair, selectors::PAIR_PRICE_0_CUMULATIVE_LAST)?;
+            let mut price_1_cumulative: u128 = self.query(pair, selectors::PAIR_PRICE_1_CUMULATIVE_LAST)?;
+            let (reserve_0, reserve_1, last_update): (Balance, Balance, Timestamp) =
+                self.query(pair, selectors::PAIR_GET_RESERVES)?;
+
+            // Mesmo acúmulo feito pelo Pair em `update`, para o tempo desde a última interação
+            let elapsed = now.saturating_sub(last_update);
+            if elapsed > 0 && reserve_0 != 0 && reserve_1 != 0 {
+                let price_0 = reserve_1
+                    .checked_mul(constants::UQ112)
+                    .and_then(|price| price.checked_div(reserve_0))
+                    .ok_or(DcaError::Overflow)?;
+                let price_1 = reserve_0
+                    .checked_mul(constants::UQ112)
+                    .and_then(|price| price.checked_div(reserve_1))
+                    .ok_or(DcaError::Overflow)?;
+                price_0_cumulative = price_0
+                    .checked_mul(u128::from(elapsed))
+                    .and_then(|delta| price_0_cumulative.checked_add(delta))
+                    .ok_or(DcaError::Overflow)?;
+                price_1_cumulative = price_1
+                    .checked_mul(u128::from(elapsed))
+                    .and_then(|delta| price_1_cumulative.checked_add(delta))
+                    .ok_or(DcaError::Overflow)?;
+            }
+
+            Ok(Observation {
+                price_0_cumulative,
+                price_1_cumulative,
+                timestamp: now,
+            })
+        }
+
+        /// Resolve o par de dois tokens no factory
+        fn resolve_pair(&self, token_a: AccountId, token_b: AccountId) -> Result<AccountId, DcaError> {
+            build_call::<DefaultEnvironment>()
+                .call(self.factory)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selectors::FACTORY_GET_PAIR))
+                        .push_arg(token_a)
+                        .push_arg(token_b),
+                )
+                .returns::<Option<AccountId>>()
+                .try_invoke()
+                .map_err(|_| DcaError::QueryFailed)?
+                .map_err(|_| DcaError::QueryFailed)?
+                .ok_or(DcaError::PairNotFound)
+        }
+
+        /// Consulta uma mensagem sem argumentos do Pair Contract
+        fn query<R: scale::Decode>(&self, pair: AccountId, selector: [u8; 4]) -> Result<R, DcaError> {
+            build_call::<DefaultEnvironment>()
+                .call(pair)
+                .exec_input(ExecutionInput::new(Selector::new(selector)))
+                .returns::<R>()
+                .try_invoke()
+                .map_err(|_| DcaError::QueryFailed)?
+                .map_err(|_| DcaError::QueryFailed)
+        }
+
+        /// Aprova o router e executa o swap com output para o vault
+        ///
+        /// Retorna a variação do saldo de `token_out` do vault.
+        fn swap_via_router(&self, path: &[AccountId], amount_in: Balance, amount_out_min: Balance) -> Result<Balance, DcaError> {
+            let token_out = path[path.len().saturating_sub(1)];
+            let vault = self.env().account_id();
+            let before = self.token_balance(token_out, vault)?;
+
+            build_call::<DefaultEnvironment>()
+                .call(path[0])
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selectors::PSP22_APPROVE))
+                        .push_arg(self.router)
+                        .push_arg(amount_in),
+                )
+                .returns::<Result<(), PSP22Error>>()
+                .try_invoke()
+                .map_err(|_| DcaError::TransferFailed)?
+                .map_err(|_| DcaError::TransferFailed)??;
+
+            build_call::<DefaultEnvironment>()
+                .call(self.router)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selectors::ROUTER_SWAP_EXACT_TOKENS_FOR_TOKENS))
+                        .push_arg(amount_in)
+                        .push_arg(amount_out_min)
+                        .push_arg(path.to_vec())
+                        .push_arg(vault)
+                        .push_arg(self.env().block_timestamp()),
+                )
+                .returns::<Result<Vec<Balance>, RouterError>>()
+                .try_invoke()
+                .map_err(|_| DcaError::RouterCallFailed)?
+                .map_err(|_| DcaError::RouterCallFailed)?
+                .map_err(DcaError::Router)?;
+
+            Self::received_output(before, self.token_balance(token_out, vault)?, amount_out_min)
+        }
+
+        /// Saldo PSP22 de `owner` em `token`
+        fn token_balance(&self, token: AccountId, owner: AccountId) -> Result<Balance, DcaError> {
+            build_call::<DefaultEnvironment>()
+                .call(token)
+                .exec_input(ExecutionInput::new(Selector::new(selectors::PSP22_BALANCE_OF)).push_arg(owner))
+                .returns::<Balance>()
+                .try_invoke()
+                .map_err(|_| DcaError::QueryFailed)?
+                .map_err(|_| DcaError::QueryFailed)
+        }
+
+        /// Transfere tokens PSP22 mantidos pelo vault
+        fn transfer_token(&self, token: AccountId, to: AccountId, amount: Balance) -> Result<(), DcaError> {
+            build_call::<DefaultEnvironment>()
+                .call(token)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selectors::PSP22_TRANSFER))
+                        .push_arg(to)
+                        .push_arg(amount)
+                        .push_arg(Vec::<u8>::new()),
+                )
+                .returns::<Result<(), PSP22Error>>()
+                .try_invoke()
+                .map_err(|_| DcaError::TransferFailed)?
+                .map_err(|_| DcaError::TransferFailed)??;
+            Ok(())
+        }
+
+        /// Puxa tokens PSP22 de `from` usando a allowance concedida
+        fn transfer_from_token(&self, token: AccountId, from: AccountId, to: AccountId, amount: Balance) -> Result<(), DcaError> {
+            build_call::<DefaultEnvironment>()
+                .call(token)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selectors::PSP22_TRANSFER_FROM))
+                        .push_arg(from)
+                        .push_arg(to)
+                        .push_arg(amount)
+                        .push_arg(Vec::<u8>::new()),
+                )
+                .returns::<Result<(), PSP22Error>>()
+                .try_invoke()
+                .map_err(|_| DcaError::TransferFailed)?
+                .map_err(|_| DcaError::TransferFailed)??;
+            Ok(())
+        }
+    }
+
+    // ========================================
+    // TESTES UNITÁRIOS
+    // ========================================
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::prelude::vec;
+
+        fn default_accounts() -> ink::env::test::DefaultAccounts<DefaultEnvironment> {
+            ink::env::test::default_accounts::<DefaultEnvironment>()
+        }
+
+        fn set_sender(sender: AccountId) {
+            ink::env::test::set_caller::<DefaultEnvironment>(sender);
+        }
+
+        fn set_timestamp(timestamp: u64) {
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(timestamp);
+        }
+
+        /// Posição django -> eve de 10 execuções de 100, como `create_position`
+        /// a deixaria após resolver o par (charlie) e fazer o escrow
+        fn insert_position(vault: &mut DcaVaultContract, owner: AccountId) -> u64 {
+            let accounts = default_accounts();
+            let position_id = vault.next_position_id;
+            vault.next_position_id = position_id.checked_add(1).unwrap();
+            vault.store_position(&DcaPosition {
+                id: position_id,
+                owner,
+                path: vec![accounts.django, accounts.eve],
+                pairs: vec![accounts.charlie],
+                budget_remaining: 1_000,
+                amount_per_execution: 100,
+                interval: constants::MIN_INTERVAL,
+                next_execution: 10_000_000,
+                max_slippage_bps: 100,
+                executions: 0,
+                total_spent: 0,
+                accrued_out: 0,
+                status: PositionStatus::Active,
+            });
+            position_id
+        }
+
+        #[ink::test]
+        fn test_new_dca_vault() {
+            let accounts = default_accounts();
+            let vault = DcaVaultContract::new(accounts.bob, accounts.charlie);
+
+            assert_eq!(vault.router(), accounts.bob);
+            assert_eq!(vault.factory(), accounts.charlie);
+            assert_eq!(vault.get_position(1), None);
+            assert_eq!(vault.get_twap_window(accounts.charlie), TwapWindow::default());
+        }
+
+        #[ink::test]
+        fn test_create_position_validations() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            let mut vault = DcaVaultContract::new(accounts.bob, accounts.charlie);
+            let path = vec![accounts.django, accounts.eve];
+            let interval = constants::MIN_INTERVAL;
+
+            // RED: Path curto ou com hop repetido
+            assert_eq!(
+                vault.create_position(vec![accounts.django], 1_000, 100, interval, 100),
+                Err(DcaError::InvalidPath)
+            );
+            assert_eq!(
+                vault.create_position(vec![accounts.django, accounts.django], 1_000, 100, interval, 100),
+                Err(DcaError::InvalidPath)
+            );
+            // RED: Amount por execução maior que o orçamento
+            assert_eq!(
+                vault.create_position(path.clone(), 100, 1_000, interval, 100),
+                Err(DcaError::InvalidAmount)
+            );
+            // RED: Intervalo curto demais
+            assert_eq!(
+                vault.create_position(path.clone(), 1_000, 100, interval.saturating_sub(1), 100),
+                Err(DcaError::InvalidInterval)
+            );
+            // RED: Slippage acima de 10%
+            assert_eq!(
+                vault.create_position(path, 1_000, 100, interval, 1_001),
+                Err(DcaError::SlippageTooHigh)
+            );
+        }
+
+        #[ink::test]
+        fn test_execute_respects_status_and_interval() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            set_timestamp(1_000);
+
+            let mut vault = DcaVaultContract::new(accounts.bob, accounts.charlie);
+            let position_id = insert_position(&mut vault, accounts.alice);
+
+            // RED: Antes do próximo horário de execução
+            set_sender(accounts.frank);
+            assert_eq!(vault.execute(position_id), Err(DcaError::TooEarly));
+
+            // RED: Posição pausada
+            set_sender(accounts.alice);
+            assert!(vault.pause(position_id).is_ok());
+            set_timestamp(10_000_000);
+            assert_eq!(vault.execute(position_id), Err(DcaError::PositionNotActive));
+
+            // RED: Sem observação TWAP com idade suficiente
+            assert!(vault.resume(position_id).is_ok());
+            assert_eq!(vault.execute(position_id), Err(DcaError::TwapUnavailable));
+
+            // RED: Inexistente
+            assert_eq!(vault.execute(99), Err(DcaError::PositionNotFound));
+        }
+
+        #[ink::test]
+        fn test_pause_resume_only_owner() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            let mut vault = DcaVaultContract::new(accounts.bob, accounts.charlie);
+            let position_id = insert_position(&mut vault, accounts.alice);
+
+            // RED: Apenas o dono
+            set_sender(accounts.frank);
+            assert_eq!(vault.pause(position_id), Err(DcaError::Unauthorized));
+
+            // RED: Retomar posição ativa
+            set_sender(accounts.alice);
+            assert_eq!(vault.resume(position_id), Err(DcaError::PositionNotPaused));
+
+            // GREEN: Pausa e retoma
+            assert!(vault.pause(position_id).is_ok());
+            assert_eq!(vault.get_position(position_id).unwrap().status, PositionStatus::Paused);
+            assert_eq!(vault.pause(position_id), Err(DcaError::PositionNotActive));
+            assert!(vault.resume(position_id).is_ok());
+            assert_eq!(vault.get_position(position_id).unwrap().status, PositionStatus::Active);
+
+            // RED: Nada comprado ainda
+            assert_eq!(vault.withdraw(position_id), Err(DcaError::NothingToWithdraw));
+        }
+
+        #[ink::test]
+        fn test_execution_credits_received_output() {
+            // GREEN: Credita a variação de saldo do vault
+            assert_eq!(DcaVaultContract::received_output(1_000, 1_297, 297), Ok(297));
+            assert_eq!(DcaVaultContract::received_output(1_000, 1_400, 297), Ok(400));
+
+            // RED: Token com taxa/retorno inflado do router não cobre o mínimo
+            assert_eq!(
+                DcaVaultContract::received_output(1_000, 1_200, 297),
+                Err(DcaError::InsufficientOutput)
+            );
+            assert_eq!(
+                DcaVaultContract::received_output(1_000, 900, 0),
+                Err(DcaError::InsufficientOutput)
+            );
+        }
+
+        #[ink::test]
+        fn test_twap_math() {
+            // GREEN: Média entre duas leituras do acumulador
+            let uq = constants::UQ112;
+            let start = uq.checked_mul(1_000).unwrap();
+            let end = start.checked_add(uq.checked_mul(2_000).unwrap()).unwrap();
+            assert_eq!(DcaVaultContract::average_price(start, end, 0, 1_000), Ok(uq.checked_mul(2).unwrap()));
+            assert_eq!(DcaVaultContract::average_price(start, end, 5, 5), Err(DcaError::TwapUnavailable));
+
+            // GREEN: Preço 2.0 converte 150 em 300; slippage de 1% dá 297
+            let amount = DcaVaultContract::convert_with_price(150, uq.checked_mul(2).unwrap()).unwrap();
+            assert_eq!(amount, 300);
+            assert_eq!(DcaVaultContract::apply_slippage(amount, 100), Ok(297));
+        }
+
+        #[ink::test]
+        #[allow(clippy::arithmetic_side_effects)]
+        fn test_twap_window_rolls_after_period() {
+            let period = constants::TWAP_PERIOD;
+            let observation = |timestamp: Timestamp| Observation {
+                price_0_cumulative: 0,
+                price_1_cumulative: 0,
+                timestamp,
+            };
+            let mut window = TwapWindow::default();
+
+            // GREEN: Primeira observação sempre entra
+            assert!(DcaVaultContract::roll_window(&mut window, observation(1_000)));
+            // RED: Observação recente demais não avança a janela
+            assert!(!DcaVaultContract::roll_window(&mut window, observation(1_000 + period - 1)));
+            // GREEN: Após o período, a anterior é preservada
+            assert!(DcaVaultContract::roll_window(&mut window, observation(1_000 + period)));
+            assert_eq!(window.previous, Some(observation(1_000)));
+
+            // GREEN: Usa a mais recente com idade suficiente, senão a anterior
+            let now = 1_000 + period + 10;
+            assert_eq!(DcaVaultContract::usable_observation(&window, now), Some(observation(1_000)));
+            let later = 1_000 + period * 2;
+            assert_eq!(DcaVaultContract::usable_observation(&window, later), Some(observation(1_000 + period)));
+            assert_eq!(DcaVaultContract::usable_observation(&TwapWindow::default(), later), None);
+        }
+    }
+}
@@ -4,6 +4,9 @@
 pub mod factory {
     use ink::storage::Mapping;
     use ink::prelude::vec::Vec;
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::DefaultEnvironment;
+    use pair_contract::pair_contract::PairContractRef;
 
     // Eventos do contrato
     #[ink(event)]
@@ -16,6 +19,85 @@ pub mod factory {
         pub length: u64,
     }
 
+    /// Emitido quando a pausa global de emergência dos pares muda
+    #[ink(event)]
+    pub struct GlobalPauseChanged {
+        pub paused: bool,
+        #[ink(topic)]
+        pub by: AccountId,
+    }
+
+    /// Emitido quando o override de pausa de um par muda
+    #[ink(event)]
+    pub struct PairPauseOverrideChanged {
+        #[ink(topic)]
+        pub pair: AccountId,
+        /// `Some(true)` pausa, `Some(false)` libera mesmo em pausa global, `None` segue a global
+        pub paused: Option<bool>,
+        #[ink(topic)]
+        pub by: AccountId,
+    }
+
+    /// Emitido quando o guardian de emergência é alterado
+    #[ink(event)]
+    pub struct GuardianChanged {
+        pub old_guardian: AccountId,
+        pub new_guardian: AccountId,
+    }
+
+    /// Emitido quando a governança alterna o modo de listagem
+    #[ink(event)]
+    pub struct ListingModeChanged {
+        /// `true` = create_pair exige tokens aprovados no registry
+        pub permissioned: bool,
+        pub registry: Option<AccountId>,
+    }
+
+    /// Emitido quando um token entra ou sai da lista de isenção de listagem
+    #[ink(event)]
+    pub struct ListingExemptionChanged {
+        #[ink(topic)]
+        pub token: AccountId,
+        pub exempt: bool,
+    }
+
+    /// Emitido quando a governança altera a fee de criação de pares
+    #[ink(event)]
+    pub struct PairCreationFeeChanged {
+        pub old_fee: Balance,
+        pub new_fee: Balance,
+        /// Supply mínimo de LP mantido para reembolso
+        pub min_liquidity: Balance,
+        /// Prazo para o reembolso, em blocos a partir da criação
+        pub refund_window: BlockNumber,
+    }
+
+    /// Emitido quando a fee de criação é devolvida ao criador do par
+    #[ink(event)]
+    pub struct PairCreationFeeRefunded {
+        #[ink(topic)]
+        pub pair: AccountId,
+        #[ink(topic)]
+        pub creator: AccountId,
+        pub amount: Balance,
+    }
+
+    /// Emitido quando a fee de criação de um par sem liquidez vai para o treasury
+    #[ink(event)]
+    pub struct PairCreationFeeCollected {
+        #[ink(topic)]
+        pub pair: AccountId,
+        pub treasury: AccountId,
+        pub amount: Balance,
+    }
+
+    /// Emitido quando a governança troca o treasury das fees de criação
+    #[ink(event)]
+    pub struct TreasuryUpdated {
+        pub old_treasury: AccountId,
+        pub new_treasury: AccountId,
+    }
+
     // Erros personalizados com documentação detalhada
     /// Erros que podem ocorrer nas operações da Factory
     #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
@@ -29,12 +111,81 @@ pub mod factory {
         CallerIsNotFeeSetter,
         /// Endereço zero não é permitido para tokens
         ZeroAddress,
+        /// Par não foi criado por esta factory
+        PairNotFound,
+        /// Chamada ao contrato do par falhou
+        PairCallFailed,
+        /// Chamador não é o guardian nem o fee_to_setter
+        CallerIsNotGuardian,
+        /// Modo permissionado exige um listing registry configurado
+        ListingRegistryNotSet,
+        /// Token não aprovado no listing registry (modo permissionado)
+        TokenNotApproved,
+        /// Consulta ao listing registry falhou
+        ListingCheckFailed,
+        /// Valor enviado diferente da fee de criação vigente
+        InvalidCreationFee,
+        /// Par sem fee de criação em custódia
+        NoCreationDeposit,
+        /// Prazo de reembolso encerrado
+        RefundWindowClosed,
+        /// Prazo de reembolso ainda aberto
+        RefundWindowOpen,
+        /// Transferência de LUNES nativo falhou
+        TransferFailed,
+        /// Liquidez mínima não foi mantida pelo período exigido
+        LiquidityNotPersisted,
+        /// Instanciação do contrato de par falhou
+        PairDeployFailed,
     }
 
     /// Constantes do contrato
     mod constants {
         /// Endereço zero (usado para validações)
         pub const ZERO_ADDRESS: [u8; 32] = [0u8; 32];
+
+        /// Máximo de pares retornados por página nas queries paginadas
+        pub const MAX_PAGE_SIZE: u64 = 100;
+
+        /// Prazo padrão para reembolso da fee de criação (~7 dias com blocos de 6s)
+        pub const DEFAULT_REFUND_WINDOW_BLOCKS: u32 = 7 * 24 * 60 * 10;
+
+        /// Blocos que a liquidez mínima deve permanecer no par antes do reembolso
+        /// (~1 hora com blocos de 6s; impede liquidez relâmpago no mesmo bloco)
+        pub const CREATION_LIQUIDITY_HOLD_BLOCKS: u32 = 600;
+    }
+
+    /// Selectors das chamadas cross-contract aos pares e ao listing registry
+    mod selectors {
+        pub const PAIR_SET_CIRCUIT_BREAKER_THRESHOLD: [u8; 4] = ink::selector_bytes!("set_circuit_breaker_threshold");
+        pub const PAIR_SET_DYNAMIC_FEE: [u8; 4] = ink::selector_bytes!("set_dynamic_fee");
+        pub const PAIR_SET_FEE_DISCOUNT_ROUTER: [u8; 4] = ink::selector_bytes!("set_fee_discount_router");
+        pub const PAIR_SET_PAUSED: [u8; 4] = ink::selector_bytes!("set_paused");
+        pub const REGISTRY_IS_PROJECT_APPROVED: [u8; 4] = ink::selector_bytes!("is_project_approved");
+        pub const PAIR_MIN_TOTAL_SUPPLY_SINCE: [u8; 4] = ink::selector_bytes!("min_total_supply_since");
+    }
+
+    /// Fee de criação em custódia até o par atingir liquidez ou o prazo vencer
+    #[derive(Debug, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct CreationDeposit {
+        pub creator: AccountId,
+        pub amount: Balance,
+        /// Último bloco do prazo de reembolso
+        pub deadline: BlockNumber,
+        /// Supply mínimo de LP exigido para reembolso
+        pub min_liquidity: Balance,
+    }
+
+    impl CreationDeposit {
+        /// Valida que o supply de LP ficou no mínimo em todos os blocos da
+        /// retenção (`min_supply` = menor supply ao final de cada bloco)
+        fn ensure_refundable(&self, min_supply: Balance) -> Result<(), FactoryError> {
+            if min_supply < self.min_liquidity {
+                return Err(FactoryError::LiquidityNotPersisted);
+            }
+            Ok(())
+        }
     }
 
     /// Estrutura principal do contrato
@@ -46,10 +197,40 @@ pub mod factory {
         fee_to_setter: AccountId,
         /// Token pair mapping (frequently accessed - kept as direct Mapping)
         get_pair: Mapping<(AccountId, AccountId), AccountId>,
-        /// All pairs list (frequently accessed for indexing - kept as Vec)
-        all_pairs: Vec<AccountId>,
+        /// All pairs by creation index (Mapping keeps root storage constant-size)
+        all_pairs: Mapping<u64, AccountId>,
+        /// Number of pairs created
+        all_pairs_length: u64,
+        /// Reverse index: pair address → creation index
+        pair_ids: Mapping<AccountId, u64>,
+        /// Per-token pair index: (token, position) → pair
+        token_pairs: Mapping<(AccountId, u64), AccountId>,
+        /// Number of pairs listing each token
+        token_pairs_length: Mapping<AccountId, u64>,
         /// Pair contract code hash (rarely accessed - optimized with Lazy)
         pair_contract_code_hash: ink::storage::Lazy<Hash>,
+        /// Guardian de emergência (pode pausar, não pode despausar)
+        guardian: AccountId,
+        /// Pausa global de mint/swap em todos os pares (empurrada aos pares)
+        paused: bool,
+        /// Override de pausa por par (prevalece sobre a pausa global)
+        pair_pause_override: Mapping<AccountId, bool>,
+        /// Modo de listagem permissionado (create_pair exige tokens aprovados)
+        permissioned: bool,
+        /// Contrato com `is_project_approved(token)` (ex.: StakingContract)
+        listing_registry: Option<AccountId>,
+        /// Ativos base isentos da aprovação (ex.: WLUNES)
+        listing_exempt: Mapping<AccountId, bool>,
+        /// Fee anti-spam de criação de pares em LUNES nativo (0 = desativada)
+        pair_creation_fee: Balance,
+        /// Supply mínimo de LP para reembolso da fee
+        creation_min_liquidity: Balance,
+        /// Prazo de reembolso contado a partir da criação (blocos)
+        creation_refund_window: BlockNumber,
+        /// Destino das fees de criação não reembolsadas
+        treasury: AccountId,
+        /// Fees de criação em custódia por par
+        creation_deposits: Mapping<AccountId, CreationDeposit>,
     }
 
     impl Default for FactoryContract {
@@ -58,8 +239,23 @@ pub mod factory {
                 fee_to: ink::storage::Lazy::new(),
                 fee_to_setter: AccountId::from([0u8; 32]),
                 get_pair: Mapping::default(),
-                all_pairs: Vec::new(),
+                all_pairs: Mapping::default(),
+                all_pairs_length: 0,
+                pair_ids: Mapping::default(),
+                token_pairs: Mapping::default(),
+                token_pairs_length: Mapping::default(),
                 pair_contract_code_hash: ink::storage::Lazy::new(),
+                guardian: AccountId::from([0u8; 32]),
+                paused: false,
+                pair_pause_override: Mapping::default(),
+                permissioned: false,
+                listing_registry: None,
+                listing_exempt: Mapping::default(),
+                pair_creation_fee: 0,
+                creation_min_liquidity: 0,
+                creation_refund_window: constants::DEFAULT_REFUND_WINDOW_BLOCKS,
+                treasury: AccountId::from([0u8; 32]),
+                creation_deposits: Mapping::default(),
             }
         }
     }
@@ -85,8 +281,23 @@ pub mod factory {
                 fee_to: ink::storage::Lazy::new(),
                 fee_to_setter,
                 get_pair: Mapping::default(),
-                all_pairs: Vec::new(),
+                all_pairs: Mapping::default(),
+                all_pairs_length: 0,
+                pair_ids: Mapping::default(),
+                token_pairs: Mapping::default(),
+                token_pairs_length: Mapping::default(),
                 pair_contract_code_hash: ink::storage::Lazy::new(),
+                guardian: fee_to_setter,
+                paused: false,
+                pair_pause_override: Mapping::default(),
+                permissioned: false,
+                listing_registry: None,
+                listing_exempt: Mapping::default(),
+                pair_creation_fee: 0,
+                creation_min_liquidity: 0,
+                creation_refund_window: constants::DEFAULT_REFUND_WINDOW_BLOCKS,
+                treasury: fee_to_setter,
+                creation_deposits: Mapping::default(),
             };
             
             // Initialize Lazy fields for gas optimization
@@ -108,6 +319,15 @@ pub mod factory {
             Ok(())
         }
 
+        /// Valida que o chamador é o guardian ou o fee_to_setter
+        fn ensure_caller_is_guardian(&self) -> Result<(), FactoryError> {
+            let caller = self.env().caller();
+            if caller != self.guardian && caller != self.fee_to_setter {
+                return Err(FactoryError::CallerIsNotGuardian);
+            }
+            Ok(())
+        }
+
         /// Valida entrada para criação de par
         fn validate_pair_creation(&self, token_a: AccountId, token_b: AccountId) -> Result<(), FactoryError> {
             // Fail fast: tokens não podem ser endereço zero (verificar primeiro)
@@ -133,6 +353,30 @@ pub mod factory {
             }
         }
 
+        /// Instancia o contrato de par com o code hash configurado
+        ///
+        /// O salt são os tokens ordenados, de modo que o endereço é
+        /// determinístico. Sem code hash (testes off-chain) o endereço é
+        /// apenas derivado dos tokens, sem contrato implantado.
+        fn deploy_pair(&self, token_0: AccountId, token_1: AccountId) -> Result<AccountId, FactoryError> {
+            let code_hash = self.pair_contract_code_hash();
+            if code_hash == Hash::default() {
+                return Ok(self.generate_pair_address(token_0, token_1));
+            }
+
+            let mut salt = Vec::new();
+            salt.extend_from_slice(token_0.as_ref());
+            salt.extend_from_slice(token_1.as_ref());
+            let pair = PairContractRef::new(self.env().account_id(), token_0, token_1)
+                .code_hash(code_hash)
+                .endowment(0)
+                .salt_bytes(&salt)
+                .try_instantiate()
+                .map_err(|_| FactoryError::PairDeployFailed)?
+                .map_err(|_| FactoryError::PairDeployFailed)?;
+            Ok(ink::ToAccountId::to_account_id(&pair))
+        }
+
         /// Gera endereço determinístico do par usando hash dos tokens
         fn generate_pair_address(&self, token_0: AccountId, token_1: AccountId) -> AccountId {
             let mut salt_input = Vec::new();
@@ -144,11 +388,47 @@ pub mod factory {
             AccountId::from(output)
         }
 
-        /// Registra par nos mappings bidirecionais
+        /// Registra par nos mappings bidirecionais e nos índices de enumeração
         fn register_pair(&mut self, token_0: AccountId, token_1: AccountId, pair_address: AccountId) {
             self.get_pair.insert((token_0, token_1), &pair_address);
             self.get_pair.insert((token_1, token_0), &pair_address);
-            self.all_pairs.push(pair_address);
+
+            let pid = self.all_pairs_length;
+            self.all_pairs.insert(pid, &pair_address);
+            self.pair_ids.insert(pair_address, &pid);
+            self.all_pairs_length = pid.saturating_add(1);
+
+            for token in [token_0, token_1] {
+                let position = self.token_pairs_length.get(token).unwrap_or(0);
+                self.token_pairs.insert((token, position), &pair_address);
+                self.token_pairs_length.insert(token, &position.saturating_add(1));
+            }
+        }
+
+        /// Modo permissionado: exige que cada token seja isento ou aprovado no registry
+        fn ensure_tokens_listable(&self, token_0: AccountId, token_1: AccountId) -> Result<(), FactoryError> {
+            if !self.permissioned {
+                return Ok(());
+            }
+            for token in [token_0, token_1] {
+                if !self.is_token_listable(token)? {
+                    return Err(FactoryError::TokenNotApproved);
+                }
+            }
+            Ok(())
+        }
+
+        /// Verifica se o endereço é um par criado por esta factory
+        fn is_registered_pair(&self, pair: AccountId) -> bool {
+            self.pair_ids.contains(pair)
+        }
+
+        /// Intervalo `[start, start + limit)` limitado a `length` e a `MAX_PAGE_SIZE`
+        fn page_bounds(start: u64, limit: u64, length: u64) -> core::ops::Range<u64> {
+            let end = start
+                .saturating_add(limit.min(constants::MAX_PAGE_SIZE))
+                .min(length);
+            start..end.max(start)
         }
 
         // ========================================
@@ -165,9 +445,7 @@ pub mod factory {
         /// * `None` - Se índice inválido
         #[ink(message)]
         pub fn all_pairs(&self, pid: u64) -> Option<AccountId> {
-            usize::try_from(pid)
-                .ok()
-                .and_then(|index| self.all_pairs.get(index).copied())
+            self.all_pairs.get(pid)
         }
 
         /// Retorna quantidade total de pares criados
@@ -176,7 +454,42 @@ pub mod factory {
         /// * `u64` - Número total de pares registrados na factory
         #[ink(message)]
         pub fn all_pairs_length(&self) -> u64 {
-            self.all_pairs.len() as u64
+            self.all_pairs_length
+        }
+
+        /// Retorna uma página de pares em ordem de criação
+        /// 
+        /// # Parâmetros
+        /// * `start` - Índice do primeiro par (0-based)
+        /// * `limit` - Máximo de pares (limitado a `MAX_PAGE_SIZE`)
+        /// 
+        /// # Retorna
+        /// * `Vec<AccountId>` - Pares em `[start, start + limit)`; vazio se `start` além do fim
+        #[ink(message)]
+        pub fn pairs_range(&self, start: u64, limit: u64) -> Vec<AccountId> {
+            Self::page_bounds(start, limit, self.all_pairs_length)
+                .filter_map(|pid| self.all_pairs.get(pid))
+                .collect()
+        }
+
+        /// Retorna uma página dos pares que listam `token`, em ordem de criação
+        /// 
+        /// # Parâmetros
+        /// * `token` - Token cujos mercados serão listados
+        /// * `start` - Posição do primeiro par no índice do token (0-based)
+        /// * `limit` - Máximo de pares (limitado a `MAX_PAGE_SIZE`)
+        #[ink(message)]
+        pub fn pairs_for_token(&self, token: AccountId, start: u64, limit: u64) -> Vec<AccountId> {
+            let length = self.pairs_for_token_length(token);
+            Self::page_bounds(start, limit, length)
+                .filter_map(|position| self.token_pairs.get((token, position)))
+                .collect()
+        }
+
+        /// Retorna quantos pares listam `token`
+        #[ink(message)]
+        pub fn pairs_for_token_length(&self, token: AccountId) -> u64 {
+            self.token_pairs_length.get(token).unwrap_or(0)
         }
 
         /// Retorna endereço atual do fee_to (receptor de taxas)
@@ -234,7 +547,8 @@ pub mod factory {
         /// * Tokens não podem ser idênticos
         /// * Tokens não podem ser endereço zero
         /// * Par não pode já existir
-        #[ink(message)]
+        /// * Valor enviado deve ser exatamente a fee de criação vigente
+        #[ink(message, payable)]
         pub fn create_pair(
             &mut self,
             token_a: AccountId,
@@ -251,18 +565,42 @@ pub mod factory {
                 return Err(FactoryError::PairExists);
             }
 
-            // Gerar endereço determinístico do par
-            let pair_address = self.generate_pair_address(token_0, token_1);
+            // Modo permissionado: ambos os tokens devem estar listados
+            self.ensure_tokens_listable(token_0, token_1)?;
+
+            // Fee anti-spam (reembolsável se o par ganhar liquidez a tempo)
+            let fee_paid = self.env().transferred_value();
+            if fee_paid != self.pair_creation_fee {
+                return Err(FactoryError::InvalidCreationFee);
+            }
+
+            // Implantar o par (endereço determinístico pelos tokens)
+            let pair_address = self.deploy_pair(token_0, token_1)?;
 
             // Registrar o novo par
             self.register_pair(token_0, token_1, pair_address);
 
+            if fee_paid > 0 {
+                self.creation_deposits.insert(
+                    pair_address,
+                    &CreationDeposit {
+                        creator: self.env().caller(),
+                        amount: fee_paid,
+                        deadline: self
+                            .env()
+                            .block_number()
+                            .saturating_add(self.creation_refund_window),
+                        min_liquidity: self.creation_min_liquidity,
+                    },
+                );
+            }
+
             // Emitir evento para indexadores/UIs
             self.env().emit_event(PairCreated {
                 token_0,
                 token_1,
                 pair: pair_address,
-                length: self.all_pairs.len() as u64,
+                length: self.all_pairs_length,
             });
 
             Ok(pair_address)
@@ -307,6 +645,493 @@ pub mod factory {
             self.fee_to_setter = fee_to_setter;
             Ok(())
         }
+
+        // ========================================
+        // LISTAGEM DE TOKENS (PERMISSIONADA / PERMISSIONLESS)
+        // ========================================
+
+        /// Retorna se create_pair exige tokens aprovados
+        #[ink(message)]
+        pub fn is_permissioned(&self) -> bool {
+            self.permissioned
+        }
+
+        /// Retorna o listing registry consultado no modo permissionado
+        #[ink(message)]
+        pub fn listing_registry(&self) -> Option<AccountId> {
+            self.listing_registry
+        }
+
+        /// Retorna se o token é isento da aprovação de listagem
+        #[ink(message)]
+        pub fn is_listing_exempt(&self, token: AccountId) -> bool {
+            self.listing_exempt.get(token).unwrap_or(false)
+        }
+
+        /// Retorna se o token pode ser usado em create_pair no modo permissionado
+        /// 
+        /// Tokens isentos dispensam a consulta; os demais são verificados via
+        /// `is_project_approved` no listing registry.
+        #[ink(message)]
+        pub fn is_token_listable(&self, token: AccountId) -> Result<bool, FactoryError> {
+            if self.is_listing_exempt(token) {
+                return Ok(true);
+            }
+            let registry = self.listing_registry.ok_or(FactoryError::ListingRegistryNotSet)?;
+            if !self.env().is_contract(&registry) {
+                return Err(FactoryError::ListingCheckFailed);
+            }
+
+            build_call::<DefaultEnvironment>()
+                .call(registry)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selectors::REGISTRY_IS_PROJECT_APPROVED)).push_arg(token),
+                )
+                .returns::<bool>()
+                .try_invoke()
+                .map_err(|_| FactoryError::ListingCheckFailed)?
+                .map_err(|_| FactoryError::ListingCheckFailed)
+        }
+
+        /// Alterna entre listagem permissionada e permissionless
+        /// 
+        /// # Parâmetros
+        /// * `permissioned` - `true` exige aprovação no registry para criar pares
+        /// * `registry` - Contrato consultado (ex.: StakingContract)
+        /// 
+        /// # Controle de Acesso
+        /// * Apenas o fee_to_setter (governança) pode chamar esta função
+        #[ink(message)]
+        pub fn set_listing_mode(&mut self, permissioned: bool, registry: Option<AccountId>) -> Result<(), FactoryError> {
+            self.ensure_caller_is_fee_setter()?;
+
+            if permissioned && registry.is_none() {
+                return Err(FactoryError::ListingRegistryNotSet);
+            }
+
+            self.permissioned = permissioned;
+            self.listing_registry = registry;
+            self.env().emit_event(ListingModeChanged { permissioned, registry });
+            Ok(())
+        }
+
+        /// Adiciona ou remove um ativo base da lista de isenção (ex.: WLUNES)
+        /// 
+        /// # Controle de Acesso
+        /// * Apenas o fee_to_setter (governança) pode chamar esta função
+        #[ink(message)]
+        pub fn set_listing_exemption(&mut self, token: AccountId, exempt: bool) -> Result<(), FactoryError> {
+            self.ensure_caller_is_fee_setter()?;
+
+            if token == AccountId::from(constants::ZERO_ADDRESS) {
+                return Err(FactoryError::ZeroAddress);
+            }
+
+            if exempt {
+                self.listing_exempt.insert(token, &true);
+            } else {
+                self.listing_exempt.remove(token);
+            }
+            self.env().emit_event(ListingExemptionChanged { token, exempt });
+            Ok(())
+        }
+
+        // ========================================
+        // FEE DE CRIAÇÃO DE PARES (ANTI-SPAM)
+        // ========================================
+
+        /// Retorna a fee de criação de pares vigente
+        #[ink(message)]
+        pub fn pair_creation_fee(&self) -> Balance {
+            self.pair_creation_fee
+        }
+
+        /// Retorna o destino das fees de criação não reembolsadas
+        #[ink(message)]
+        pub fn treasury(&self) -> AccountId {
+            self.treasury
+        }
+
+        /// Retorna a fee de criação em custódia de um par
+        #[ink(message)]
+        pub fn creation_deposit(&self, pair: AccountId) -> Option<CreationDeposit> {
+            self.creation_deposits.get(pair)
+        }
+
+        /// Define a fee de criação e as condições de reembolso
+        /// 
+        /// # Parâmetros
+        /// * `fee` - Fee em LUNES nativo (0 desativa)
+        /// * `min_liquidity` - Supply de LP a manter durante a retenção para reembolso
+        /// * `refund_window` - Prazo em blocos, a partir da criação, para pedir o reembolso
+        /// 
+        /// # Controle de Acesso
+        /// * Apenas o fee_to_setter (governança) pode chamar esta função
+        #[ink(message)]
+        pub fn set_pair_creation_fee(
+            &mut self,
+            fee: Balance,
+            min_liquidity: Balance,
+            refund_window: BlockNumber,
+        ) -> Result<(), FactoryError> {
+            self.ensure_caller_is_fee_setter()?;
+
+            let old_fee = self.pair_creation_fee;
+            self.pair_creation_fee = fee;
+            self.creation_min_liquidity = min_liquidity;
+            self.creation_refund_window = refund_window;
+
+            self.env().emit_event(PairCreationFeeChanged {
+                old_fee,
+                new_fee: fee,
+                min_liquidity,
+                refund_window,
+            });
+            Ok(())
+        }
+
+        /// Define o treasury que recebe as fees de criação não reembolsadas
+        /// 
+        /// # Controle de Acesso
+        /// * Apenas o fee_to_setter (governança) pode chamar esta função
+        #[ink(message)]
+        pub fn set_treasury(&mut self, treasury: AccountId) -> Result<(), FactoryError> {
+            self.ensure_caller_is_fee_setter()?;
+
+            if treasury == AccountId::from(constants::ZERO_ADDRESS) {
+                return Err(FactoryError::ZeroAddress);
+            }
+
+            let old_treasury = self.treasury;
+            self.treasury = treasury;
+            self.env().emit_event(TreasuryUpdated {
+                old_treasury,
+                new_treasury: treasury,
+            });
+            Ok(())
+        }
+
+        /// Devolve a fee de criação ao criador se o par manteve a liquidez mínima
+        /// 
+        /// O supply de LP do par precisa ter ficado acima de `min_liquidity` ao
+        /// final de cada um dos últimos `CREATION_LIQUIDITY_HOLD_BLOCKS` blocos
+        /// (checkpoints `PairContract::min_total_supply_since`), dentro do prazo.
+        /// Qualquer conta pode chamar; o valor sempre vai para o criador.
+        #[ink(message)]
+        pub fn refund_creation_fee(&mut self, pair: AccountId) -> Result<Balance, FactoryError> {
+            let deposit = self.creation_deposits.get(pair).ok_or(FactoryError::NoCreationDeposit)?;
+            let block = self.env().block_number();
+            if block > deposit.deadline {
+                return Err(FactoryError::RefundWindowClosed);
+            }
+            let since = block.saturating_sub(constants::CREATION_LIQUIDITY_HOLD_BLOCKS);
+            let min_supply = self.pair_min_supply_since(pair, since)?;
+            self.settle_refund(pair, deposit, min_supply)
+        }
+
+        /// Valida a retenção da liquidez medida e paga o reembolso ao criador
+        fn settle_refund(
+            &mut self,
+            pair: AccountId,
+            deposit: CreationDeposit,
+            min_supply: Balance,
+        ) -> Result<Balance, FactoryError> {
+            deposit.ensure_refundable(min_supply)?;
+
+            self.creation_deposits.remove(pair);
+            self.env()
+                .transfer(deposit.creator, deposit.amount)
+                .map_err(|_| FactoryError::TransferFailed)?;
+
+            self.env().emit_event(PairCreationFeeRefunded {
+                pair,
+                creator: deposit.creator,
+                amount: deposit.amount,
+            });
+            Ok(deposit.amount)
+        }
+
+        /// Envia ao treasury a fee de criação de um par cujo prazo de reembolso venceu
+        /// 
+        /// Qualquer conta pode chamar após o prazo.
+        #[ink(message)]
+        pub fn collect_creation_fee(&mut self, pair: AccountId) -> Result<Balance, FactoryError> {
+            let deposit = self.creation_deposits.get(pair).ok_or(FactoryError::NoCreationDeposit)?;
+            if self.env().block_number() <= deposit.deadline {
+                return Err(FactoryError::RefundWindowOpen);
+            }
+
+            self.creation_deposits.remove(pair);
+            let treasury = self.treasury;
+            self.env()
+                .transfer(treasury, deposit.amount)
+                .map_err(|_| FactoryError::TransferFailed)?;
+
+            self.env().emit_event(PairCreationFeeCollected {
+                pair,
+                treasury,
+                amount: deposit.amount,
+            });
+            Ok(deposit.amount)
+        }
+
+        /// Menor supply de LP do par ao final de cada bloco desde `block`
+        fn pair_min_supply_since(&self, pair: AccountId, block: BlockNumber) -> Result<Balance, FactoryError> {
+            if !self.env().is_contract(&pair) {
+                return Err(FactoryError::PairCallFailed);
+            }
+
+            build_call::<DefaultEnvironment>()
+                .call(pair)
+                .exec_input(ExecutionInput::new(Selector::new(selectors::PAIR_MIN_TOTAL_SUPPLY_SINCE)).push_arg(block))
+                .returns::<Balance>()
+                .try_invoke()
+                .map_err(|_| FactoryError::PairCallFailed)?
+                .map_err(|_| FactoryError::PairCallFailed)
+        }
+
+        // ========================================
+        // PAUSA DE EMERGÊNCIA DOS PARES
+        // ========================================
+
+        /// Retorna o guardian de emergência
+        #[ink(message)]
+        pub fn guardian(&self) -> AccountId {
+            self.guardian
+        }
+
+        /// Retorna se a pausa global está ativa
+        #[ink(message)]
+        pub fn is_paused(&self) -> bool {
+            self.paused
+        }
+
+        /// Retorna o override de pausa de um par (`None` = segue a pausa global)
+        #[ink(message)]
+        pub fn pair_pause_override(&self, pair: AccountId) -> Option<bool> {
+            self.pair_pause_override.get(pair)
+        }
+
+        /// Retorna se mint/swap estão pausados em `pair`
+        ///
+        /// O override do par prevalece; sem override vale a pausa global.
+        /// Burn nunca é pausado, para que LPs possam sair. Os pares guardam
+        /// este estado localmente (`PairContract::set_paused`).
+        #[ink(message)]
+        pub fn is_pair_paused(&self, pair: AccountId) -> bool {
+            self.pair_pause_override.get(pair).unwrap_or(self.paused)
+        }
+
+        /// Pausa mint/swap em todos os pares
+        ///
+        /// Os pares só passam a rejeitar mint/swap quando `sync_pair_pauses`
+        /// empurra o novo estado para eles.
+        ///
+        /// # Controle de Acesso
+        /// * Guardian ou fee_to_setter
+        #[ink(message)]
+        pub fn pause_all(&mut self) -> Result<(), FactoryError> {
+            self.ensure_caller_is_guardian()?;
+            self.set_global_pause(true);
+            Ok(())
+        }
+
+        /// Encerra a pausa global (propagada aos pares por `sync_pair_pauses`)
+        ///
+        /// # Controle de Acesso
+        /// * Apenas o fee_to_setter (o guardian não pode despausar)
+        #[ink(message)]
+        pub fn unpause_all(&mut self) -> Result<(), FactoryError> {
+            self.ensure_caller_is_fee_setter()?;
+            self.set_global_pause(false);
+            Ok(())
+        }
+
+        /// Define o override de pausa de um par
+        ///
+        /// # Parâmetros
+        /// * `pair` - Endereço do par criado por esta factory
+        /// * `paused` - `Some(true)` pausa só este par, `Some(false)` o mantém
+        ///   operando durante a pausa global, `None` remove o override
+        ///
+        /// # Controle de Acesso
+        /// * Guardian pode apenas pausar (`Some(true)`); demais valores exigem o fee_to_setter
+        #[ink(message)]
+        pub fn set_pair_pause_override(
+            &mut self,
+            pair: AccountId,
+            paused: Option<bool>,
+        ) -> Result<(), FactoryError> {
+            if paused == Some(true) {
+                self.ensure_caller_is_guardian()?;
+            } else {
+                self.ensure_caller_is_fee_setter()?;
+            }
+            if !self.is_registered_pair(pair) {
+                return Err(FactoryError::PairNotFound);
+            }
+
+            match paused {
+                Some(value) => {
+                    self.pair_pause_override.insert(pair, &value);
+                }
+                None => self.pair_pause_override.remove(pair),
+            }
+            self.push_pair_pause(pair)?;
+
+            self.env().emit_event(PairPauseOverrideChanged {
+                pair,
+                paused,
+                by: self.env().caller(),
+            });
+            Ok(())
+        }
+
+        /// Define o guardian de emergência
+        ///
+        /// # Controle de Acesso
+        /// * Apenas o fee_to_setter pode chamar esta função
+        #[ink(message)]
+        pub fn set_guardian(&mut self, guardian: AccountId) -> Result<(), FactoryError> {
+            self.ensure_caller_is_fee_setter()?;
+
+            if guardian == AccountId::from(constants::ZERO_ADDRESS) {
+                return Err(FactoryError::ZeroAddress);
+            }
+
+            let old_guardian = self.guardian;
+            self.guardian = guardian;
+            self.env().emit_event(GuardianChanged {
+                old_guardian,
+                new_guardian: guardian,
+            });
+            Ok(())
+        }
+
+        /// Empurra o estado de pausa a uma página de pares `[start, start + limit)`
+        ///
+        /// Qualquer conta pode chamar: o estado enviado é sempre o do factory
+        /// (`is_pair_paused`). Após `pause_all`/`unpause_all` o guardian ou o
+        /// admin percorre as páginas para que os pares passem a segui-lo.
+        ///
+        /// # Retorna
+        /// * Número de pares percorridos
+        #[ink(message)]
+        pub fn sync_pair_pauses(&mut self, start: u64, limit: u64) -> Result<u64, FactoryError> {
+            let range = Self::page_bounds(start, limit, self.all_pairs_length);
+            let synced = range.end.saturating_sub(range.start);
+            for pid in range {
+                if let Some(pair) = self.all_pairs.get(pid) {
+                    self.push_pair_pause(pair)?;
+                }
+            }
+            Ok(synced)
+        }
+
+        /// Envia `is_pair_paused(pair)` ao par
+        ///
+        /// Endereços sem código (par ainda não implantado, testes off-chain)
+        /// são ignorados; o par recebe o estado no próximo sync.
+        fn push_pair_pause(&self, pair: AccountId) -> Result<(), FactoryError> {
+            if !self.env().is_contract(&pair) {
+                return Ok(());
+            }
+            self.call_pair(pair, selectors::PAIR_SET_PAUSED, self.is_pair_paused(pair))
+        }
+
+        /// Atualiza a pausa global e emite o evento
+        fn set_global_pause(&mut self, paused: bool) {
+            self.paused = paused;
+            self.env().emit_event(GlobalPauseChanged {
+                paused,
+                by: self.env().caller(),
+            });
+        }
+
+        /// Define o movimento máximo de preço por bloco de um par (circuit breaker)
+        /// 
+        /// # Parâmetros
+        /// * `pair` - Endereço do par criado por esta factory
+        /// * `threshold_bps` - Movimento máximo em basis points (0 desabilita)
+        /// 
+        /// # Controle de Acesso
+        /// * Apenas o fee_to_setter pode chamar esta função
+        #[ink(message)]
+        pub fn set_circuit_breaker_threshold(&mut self, pair: AccountId, threshold_bps: u32) -> Result<(), FactoryError> {
+            // Validação de acesso centralizada
+            self.ensure_caller_is_fee_setter()?;
+
+            self.call_pair(pair, selectors::PAIR_SET_CIRCUIT_BREAKER_THRESHOLD, threshold_bps)
+        }
+
+        /// Define o router que pode aplicar o desconto de staking nos swaps de um par
+        /// 
+        /// # Parâmetros
+        /// * `pair` - Endereço do par criado por esta factory
+        /// * `router` - Router autorizado (`None` remove o desconto)
+        /// 
+        /// # Controle de Acesso
+        /// * Apenas o fee_to_setter pode chamar esta função
+        #[ink(message)]
+        pub fn set_fee_discount_router(&mut self, pair: AccountId, router: Option<AccountId>) -> Result<(), FactoryError> {
+            self.ensure_caller_is_fee_setter()?;
+
+            self.call_pair(pair, selectors::PAIR_SET_FEE_DISCOUNT_ROUTER, router)
+        }
+
+        /// Ativa ou altera a fee dinâmica baseada em volatilidade de um par
+        /// 
+        /// # Parâmetros
+        /// * `pair` - Endereço do par criado por esta factory
+        /// * `min_fee_bps` / `max_fee_bps` - Faixa da fee de swap em basis points
+        /// * `window` - Janela de medição do TWAP (ms)
+        /// * `volatility_cap_bps` - Volatilidade por janela em que a fee atinge o máximo
+        /// 
+        /// # Controle de Acesso
+        /// * Apenas o fee_to_setter pode chamar esta função
+        #[ink(message)]
+        pub fn set_dynamic_fee(
+            &mut self,
+            pair: AccountId,
+            min_fee_bps: u16,
+            max_fee_bps: u16,
+            window: Timestamp,
+            volatility_cap_bps: u32,
+        ) -> Result<(), FactoryError> {
+            self.ensure_caller_is_fee_setter()?;
+
+            // Codificado como `Option<DynamicFeeConfig>` do Pair Contract
+            let config = Some((min_fee_bps, max_fee_bps, window, volatility_cap_bps));
+            self.call_pair(pair, selectors::PAIR_SET_DYNAMIC_FEE, config)
+        }
+
+        /// Desativa a fee dinâmica de um par (volta à fee estática)
+        /// 
+        /// # Controle de Acesso
+        /// * Apenas o fee_to_setter pode chamar esta função
+        #[ink(message)]
+        pub fn disable_dynamic_fee(&mut self, pair: AccountId) -> Result<(), FactoryError> {
+            self.ensure_caller_is_fee_setter()?;
+
+            let config: Option<(u16, u16, Timestamp, u32)> = None;
+            self.call_pair(pair, selectors::PAIR_SET_DYNAMIC_FEE, config)
+        }
+
+        /// Executa uma mensagem de configuração em um par criado por esta factory
+        fn call_pair<Args: scale::Encode>(&self, pair: AccountId, selector: [u8; 4], args: Args) -> Result<(), FactoryError> {
+            if !self.is_registered_pair(pair) {
+                return Err(FactoryError::PairNotFound);
+            }
+
+            build_call::<DefaultEnvironment>()
+                .call(pair)
+                .exec_input(ExecutionInput::new(Selector::new(selector)).push_arg(args))
+                .returns::<Result<(), u8>>()
+                .try_invoke()
+                .map_err(|_| FactoryError::PairCallFailed)?
+                .map_err(|_| FactoryError::PairCallFailed)?
+                .map_err(|_| FactoryError::PairCallFailed)
+        }
     }
 
     /// Testes unitários
@@ -490,6 +1315,407 @@ pub mod factory {
             let _factory = FactoryContract::new(zero_address, Hash::default());
         }
 
+        #[ink::test]
+        fn test_set_circuit_breaker_threshold_validations() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            let mut factory = FactoryContract::new(accounts.alice, Hash::default());
+            let pair = factory.create_pair(accounts.bob, accounts.charlie).unwrap();
+
+            // RED: Apenas o fee_to_setter configura o breaker
+            set_sender(accounts.bob);
+            let result = factory.set_circuit_breaker_threshold(pair, 500);
+            assert_eq!(result, Err(FactoryError::CallerIsNotFeeSetter));
+
+            // RED: Par desconhecido
+            set_sender(accounts.alice);
+            let result = factory.set_circuit_breaker_threshold(accounts.django, 500);
+            assert_eq!(result, Err(FactoryError::PairNotFound));
+        }
+
+        #[ink::test]
+        fn test_set_fee_discount_router_validations() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            let mut factory = FactoryContract::new(accounts.alice, Hash::default());
+            let pair = factory.create_pair(accounts.bob, accounts.charlie).unwrap();
+
+            // RED: Apenas o fee_to_setter define o router do desconto
+            set_sender(accounts.bob);
+            let result = factory.set_fee_discount_router(pair, Some(accounts.eve));
+            assert_eq!(result, Err(FactoryError::CallerIsNotFeeSetter));
+
+            // RED: Par desconhecido
+            set_sender(accounts.alice);
+            let result = factory.set_fee_discount_router(accounts.django, Some(accounts.eve));
+            assert_eq!(result, Err(FactoryError::PairNotFound));
+        }
+
+        #[ink::test]
+        fn test_dynamic_fee_config_validations() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            let mut factory = FactoryContract::new(accounts.alice, Hash::default());
+            let pair = factory.create_pair(accounts.bob, accounts.charlie).unwrap();
+
+            // RED: Apenas o fee_to_setter configura a fee dinâmica
+            set_sender(accounts.bob);
+            assert_eq!(factory.set_dynamic_fee(pair, 10, 100, 60_000, 1_000), Err(FactoryError::CallerIsNotFeeSetter));
+            assert_eq!(factory.disable_dynamic_fee(pair), Err(FactoryError::CallerIsNotFeeSetter));
+
+            // RED: Par desconhecido
+            set_sender(accounts.alice);
+            assert_eq!(factory.set_dynamic_fee(accounts.django, 10, 100, 60_000, 1_000), Err(FactoryError::PairNotFound));
+        }
+
+        #[ink::test]
+        fn test_global_pause_roles() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            let mut factory = FactoryContract::new(accounts.alice, Hash::default());
+            let pair = factory.create_pair(accounts.bob, accounts.charlie).unwrap();
+            assert_eq!(factory.guardian(), accounts.alice);
+            assert!(factory.set_guardian(accounts.django).is_ok());
+
+            // RED: Contas sem papel não pausam
+            set_sender(accounts.eve);
+            assert_eq!(factory.pause_all(), Err(FactoryError::CallerIsNotGuardian));
+
+            // GREEN: Guardian pausa todos os pares
+            set_sender(accounts.django);
+            assert!(factory.pause_all().is_ok());
+            assert!(factory.is_paused());
+            assert!(factory.is_pair_paused(pair));
+
+            // RED: Guardian não despausa
+            assert_eq!(
+                factory.unpause_all(),
+                Err(FactoryError::CallerIsNotFeeSetter)
+            );
+
+            // GREEN: Admin despausa
+            set_sender(accounts.alice);
+            assert!(factory.unpause_all().is_ok());
+            assert!(!factory.is_pair_paused(pair));
+
+            // GREEN: Qualquer conta propaga o estado, uma página por vez
+            factory.create_pair(accounts.bob, accounts.django).unwrap();
+            set_sender(accounts.eve);
+            assert_eq!(factory.sync_pair_pauses(0, 1), Ok(1));
+            assert_eq!(factory.sync_pair_pauses(1, 10), Ok(1));
+            assert_eq!(factory.sync_pair_pauses(2, 10), Ok(0));
+        }
+
+        #[ink::test]
+        fn test_pair_pause_override() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            let mut factory = FactoryContract::new(accounts.alice, Hash::default());
+            let pair_a = factory.create_pair(accounts.bob, accounts.charlie).unwrap();
+            let pair_b = factory.create_pair(accounts.bob, accounts.django).unwrap();
+            factory.set_guardian(accounts.eve).unwrap();
+
+            // GREEN: Guardian pausa apenas um par
+            set_sender(accounts.eve);
+            assert!(factory.set_pair_pause_override(pair_a, Some(true)).is_ok());
+            assert!(factory.is_pair_paused(pair_a));
+            assert!(!factory.is_pair_paused(pair_b));
+
+            // RED: Guardian não libera pares nem remove overrides
+            assert_eq!(
+                factory.set_pair_pause_override(pair_b, Some(false)),
+                Err(FactoryError::CallerIsNotFeeSetter)
+            );
+            assert_eq!(
+                factory.set_pair_pause_override(pair_a, None),
+                Err(FactoryError::CallerIsNotFeeSetter)
+            );
+
+            // GREEN: Admin mantém pair_b operando durante a pausa global
+            set_sender(accounts.alice);
+            factory
+                .set_pair_pause_override(pair_b, Some(false))
+                .unwrap();
+            factory.pause_all().unwrap();
+            assert!(factory.is_pair_paused(pair_a));
+            assert!(!factory.is_pair_paused(pair_b));
+
+            // GREEN: Remover override volta a seguir a pausa global
+            factory.set_pair_pause_override(pair_b, None).unwrap();
+            assert!(factory.is_pair_paused(pair_b));
+            assert_eq!(factory.pair_pause_override(pair_b), None);
+
+            // RED: Par desconhecido
+            assert_eq!(
+                factory.set_pair_pause_override(accounts.frank, Some(true)),
+                Err(FactoryError::PairNotFound)
+            );
+        }
+
+        #[ink::test]
+        fn test_pairs_range_pagination() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            let mut factory = FactoryContract::new(accounts.alice, Hash::default());
+            let pair_ab = factory.create_pair(accounts.alice, accounts.bob).unwrap();
+            let pair_ac = factory.create_pair(accounts.alice, accounts.charlie).unwrap();
+            let pair_bc = factory.create_pair(accounts.bob, accounts.charlie).unwrap();
+
+            // GREEN: Páginas em ordem de criação
+            assert_eq!(factory.pairs_range(0, 2), vec![pair_ab, pair_ac]);
+            assert_eq!(factory.pairs_range(2, 2), vec![pair_bc]);
+
+            // GREEN: Início além do fim ou limite zero retornam vazio
+            assert_eq!(factory.pairs_range(3, 10), Vec::<AccountId>::new());
+            assert_eq!(factory.pairs_range(0, 0), Vec::<AccountId>::new());
+            assert_eq!(factory.pairs_range(u64::MAX, u64::MAX), Vec::<AccountId>::new());
+        }
+
+        #[ink::test]
+        fn test_pairs_for_token_index() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            let mut factory = FactoryContract::new(accounts.alice, Hash::default());
+            let pair_ab = factory.create_pair(accounts.alice, accounts.bob).unwrap();
+            let pair_cd = factory.create_pair(accounts.charlie, accounts.django).unwrap();
+            let pair_bc = factory.create_pair(accounts.bob, accounts.charlie).unwrap();
+
+            // GREEN: Cada token lista apenas seus mercados
+            assert_eq!(factory.pairs_for_token_length(accounts.bob), 2);
+            assert_eq!(factory.pairs_for_token(accounts.bob, 0, 10), vec![pair_ab, pair_bc]);
+            assert_eq!(factory.pairs_for_token(accounts.charlie, 1, 1), vec![pair_bc]);
+            assert_eq!(factory.pairs_for_token(accounts.django, 0, 10), vec![pair_cd]);
+
+            // GREEN: Token sem pares
+            assert_eq!(factory.pairs_for_token_length(accounts.eve), 0);
+            assert_eq!(factory.pairs_for_token(accounts.eve, 0, 10), Vec::<AccountId>::new());
+        }
+
+        #[ink::test]
+        fn test_listing_mode_governance() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            let mut factory = FactoryContract::new(accounts.alice, Hash::default());
+            assert!(!factory.is_permissioned());
+
+            // RED: Apenas a governança altera o modo
+            set_sender(accounts.bob);
+            assert_eq!(factory.set_listing_mode(true, Some(accounts.eve)), Err(FactoryError::CallerIsNotFeeSetter));
+            assert_eq!(factory.set_listing_exemption(accounts.django, true), Err(FactoryError::CallerIsNotFeeSetter));
+
+            // RED: Modo permissionado exige registry
+            set_sender(accounts.alice);
+            assert_eq!(factory.set_listing_mode(true, None), Err(FactoryError::ListingRegistryNotSet));
+
+            // GREEN: Alternar para permissionado e de volta
+            assert!(factory.set_listing_mode(true, Some(accounts.eve)).is_ok());
+            assert!(factory.is_permissioned());
+            assert_eq!(factory.listing_registry(), Some(accounts.eve));
+            assert!(factory.set_listing_mode(false, None).is_ok());
+            assert!(!factory.is_permissioned());
+        }
+
+        #[ink::test]
+        fn test_permissioned_create_pair_checks_tokens() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            let mut factory = FactoryContract::new(accounts.alice, Hash::default());
+            let wlunes = accounts.django;
+            factory.set_listing_exemption(wlunes, true).unwrap();
+            factory.set_listing_exemption(accounts.charlie, true).unwrap();
+            factory.set_listing_mode(true, Some(accounts.eve)).unwrap();
+
+            // GREEN: Tokens isentos dispensam o registry
+            assert!(factory.is_listing_exempt(wlunes));
+            assert!(factory.create_pair(accounts.charlie, wlunes).is_ok());
+
+            // RED: Token não isento sem registry implantado não é listável
+            assert_eq!(factory.create_pair(accounts.bob, wlunes), Err(FactoryError::ListingCheckFailed));
+            assert_eq!(factory.all_pairs_length(), 1);
+
+            // GREEN: Modo permissionless volta a aceitar qualquer token
+            factory.set_listing_mode(false, Some(accounts.eve)).unwrap();
+            assert!(factory.create_pair(accounts.bob, wlunes).is_ok());
+
+            // GREEN: Remover isenção
+            factory.set_listing_exemption(wlunes, false).unwrap();
+            assert!(!factory.is_listing_exempt(wlunes));
+        }
+
+        #[ink::test]
+        fn test_pair_creation_fee_governance() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            let mut factory = FactoryContract::new(accounts.alice, Hash::default());
+            assert_eq!(factory.pair_creation_fee(), 0);
+            assert_eq!(factory.treasury(), accounts.alice);
+
+            // RED: Apenas a governança define fee e treasury
+            set_sender(accounts.bob);
+            assert_eq!(factory.set_pair_creation_fee(1_000, 500, 60_000), Err(FactoryError::CallerIsNotFeeSetter));
+            assert_eq!(factory.set_treasury(accounts.bob), Err(FactoryError::CallerIsNotFeeSetter));
+
+            // GREEN: Governança define fee e emite PairCreationFeeChanged
+            set_sender(accounts.alice);
+            assert!(factory.set_pair_creation_fee(1_000, 500, 60_000).is_ok());
+            assert_eq!(factory.pair_creation_fee(), 1_000);
+            assert_eq!(test::recorded_events().count(), 1);
+
+            // RED: Treasury não pode ser endereço zero
+            assert_eq!(factory.set_treasury(AccountId::from([0u8; 32])), Err(FactoryError::ZeroAddress));
+
+            // GREEN: Troca do treasury emite TreasuryUpdated
+            assert!(factory.set_treasury(accounts.frank).is_ok());
+            assert_eq!(factory.treasury(), accounts.frank);
+            assert_eq!(test::recorded_events().count(), 2);
+        }
+
+        #[ink::test]
+        fn test_creation_liquidity_must_persist() {
+            let deposit = CreationDeposit {
+                creator: default_accounts().bob,
+                amount: 1_000,
+                deadline: 1_000,
+                min_liquidity: 500,
+            };
+
+            // RED: Supply abaixo do mínimo em algum bloco da retenção
+            assert_eq!(deposit.ensure_refundable(0), Err(FactoryError::LiquidityNotPersisted));
+            assert_eq!(deposit.ensure_refundable(499), Err(FactoryError::LiquidityNotPersisted));
+
+            // GREEN: Supply no mínimo em todos os blocos
+            assert_eq!(deposit.ensure_refundable(500), Ok(()));
+        }
+
+        #[ink::test]
+        fn test_creation_fee_refund_success() {
+            const FEE: Balance = 10_000_000;
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            let mut factory = FactoryContract::new(accounts.alice, Hash::default());
+            factory.set_pair_creation_fee(FEE, 500, 1_000).unwrap();
+
+            set_sender(accounts.bob);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(FEE);
+            let pair = factory
+                .create_pair(accounts.charlie, accounts.django)
+                .unwrap();
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            let callee = test::callee::<ink::env::DefaultEnvironment>();
+            test::set_account_balance::<ink::env::DefaultEnvironment>(callee, FEE * 2);
+
+            // RED: Sem code hash o par não é implantado e não comprova liquidez
+            set_sender(accounts.eve);
+            assert_eq!(factory.refund_creation_fee(pair), Err(FactoryError::PairCallFailed));
+
+            // RED: Supply mínimo medido abaixo do exigido (o par não é implantado
+            // no ambiente off-chain, então o supply medido é injetado)
+            let deposit = factory.creation_deposit(pair).unwrap();
+            assert_eq!(
+                factory.settle_refund(pair, deposit, 499),
+                Err(FactoryError::LiquidityNotPersisted)
+            );
+
+            // GREEN: Liquidez mantida em toda a retenção → criador recebe a fee
+            let creator_before =
+                test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob).unwrap();
+            assert_eq!(factory.settle_refund(pair, deposit, 500), Ok(FEE));
+            let creator_after =
+                test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.bob).unwrap();
+            assert_eq!(creator_after - creator_before, FEE);
+            assert_eq!(factory.creation_deposit(pair), None);
+            assert_eq!(
+                factory.refund_creation_fee(pair),
+                Err(FactoryError::NoCreationDeposit)
+            );
+        }
+
+        #[ink::test]
+        fn test_create_pair_requires_exact_creation_fee() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            let mut factory = FactoryContract::new(accounts.alice, Hash::default());
+
+            // RED: Sem fee configurada, valor enviado é rejeitado
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(10);
+            assert_eq!(factory.create_pair(accounts.charlie, accounts.django), Err(FactoryError::InvalidCreationFee));
+
+            factory.set_pair_creation_fee(1_000, 500, 60_000).unwrap();
+
+            // RED: Valor diferente da fee
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(999);
+            assert_eq!(factory.create_pair(accounts.charlie, accounts.django), Err(FactoryError::InvalidCreationFee));
+            assert_eq!(factory.all_pairs_length(), 0);
+
+            // GREEN: Fee exata fica em custódia até o prazo (em blocos)
+            test::set_block_number::<ink::env::DefaultEnvironment>(1_000);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(1_000);
+            let pair = factory
+                .create_pair(accounts.charlie, accounts.django)
+                .unwrap();
+            assert_eq!(
+                factory.creation_deposit(pair),
+                Some(CreationDeposit {
+                    creator: accounts.alice,
+                    amount: 1_000,
+                    deadline: 61_000,
+                    min_liquidity: 500,
+                })
+            );
+        }
+
+        #[ink::test]
+        fn test_creation_fee_refund_and_collection() {
+            const FEE: Balance = 10_000_000;
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            let mut factory = FactoryContract::new(accounts.alice, Hash::default());
+            factory.set_pair_creation_fee(FEE, 500, 1_000).unwrap();
+            factory.set_treasury(accounts.frank).unwrap();
+
+            set_sender(accounts.bob);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(FEE);
+            let pair = factory.create_pair(accounts.charlie, accounts.django).unwrap();
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            let callee = test::callee::<ink::env::DefaultEnvironment>();
+            test::set_account_balance::<ink::env::DefaultEnvironment>(callee, FEE * 2);
+
+            // RED: Par sem contrato implantado não comprova liquidez
+            assert_eq!(
+                factory.refund_creation_fee(pair),
+                Err(FactoryError::PairCallFailed)
+            );
+
+            // RED: Coleta só após o prazo
+            assert_eq!(factory.collect_creation_fee(pair), Err(FactoryError::RefundWindowOpen));
+
+            // RED: Após o prazo não há mais reembolso
+            test::set_block_number::<ink::env::DefaultEnvironment>(1_001);
+            assert_eq!(factory.refund_creation_fee(pair), Err(FactoryError::RefundWindowClosed));
+
+            // GREEN: Qualquer conta envia a fee ao treasury
+            let treasury_before = test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.frank).unwrap();
+            set_sender(accounts.eve);
+            assert_eq!(factory.collect_creation_fee(pair), Ok(FEE));
+            let treasury_after = test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.frank).unwrap();
+            assert_eq!(treasury_after - treasury_before, FEE);
+            assert_eq!(factory.creation_deposit(pair), None);
+            assert_eq!(factory.collect_creation_fee(pair), Err(FactoryError::NoCreationDeposit));
+        }
+
         #[ink::test]
         fn test_pair_address_deterministic_and_token_order() {
             let accounts = default_accounts();
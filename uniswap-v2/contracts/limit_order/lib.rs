@@ -0,0 +1,1146 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![warn(clippy::arithmetic_side_effects)]
+#[ink::contract]
+pub mod limit_order_contract {
+    use psp22::PSP22Error;
+    use ink::prelude::vec::Vec;
+    use ink::prelude::vec;
+    use ink::storage::Mapping;
+    use primitive_types::U256;
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::DefaultEnvironment;
+    use router_contract::router_contract::RouterError;
+
+    // ========================================
+    // LIMIT ORDER CONTRACT - ORDENS LIMITADAS SEM MATCHING ENGINE
+    // ========================================
+    //
+    // Usuários depositam `token_in` em escrow com um preço alvo e validade.
+    // Keepers (qualquer conta) executam as ordens através do RouterContract
+    // quando as reserves do par atingem o preço alvo, recebendo uma pequena
+    // fee sobre o amount executado.
+    //
+    // ## Funcionalidades:
+    // - Criação de ordens com escrow do token de entrada
+    // - Execução parcial ou total por keepers permissionless
+    // - Cancelamento pelo dono com reembolso do saldo restante
+    // - Consultas por dono e por par (índice do par paginado, sem limite de ordens)
+    //
+    // ## Preço alvo:
+    // O preço é expresso como `target_amount_out` para o `amount_in` total.
+    // Uma execução parcial de `x` exige pelo menos `x * target_amount_out / amount_in`
+    // (arredondado para cima), já descontada a fee do keeper.
+
+    // ========================================
+    // EVENTOS (PARA INDEXADORES E UIS)
+    // ========================================
+
+    /// Emitido quando uma ordem é criada
+    #[ink(event)]
+    pub struct OrderPlaced {
+        #[ink(topic)]
+        pub order_id: u64,
+        #[ink(topic)]
+        pub owner: AccountId,
+        #[ink(topic)]
+        pub pair: AccountId,
+        pub token_in: AccountId,
+        pub token_out: AccountId,
+        pub amount_in: Balance,
+        pub target_amount_out: Balance,
+        pub expiry: Timestamp,
+    }
+
+    /// Emitido quando uma ordem é executada (total ou parcialmente)
+    #[ink(event)]
+    pub struct OrderFilled {
+        #[ink(topic)]
+        pub order_id: u64,
+        #[ink(topic)]
+        pub keeper: AccountId,
+        pub amount_in: Balance,
+        pub amount_out: Balance,
+        pub keeper_fee: Balance,
+        pub remaining_in: Balance,
+    }
+
+    /// Emitido quando o dono cancela uma ordem
+    #[ink(event)]
+    pub struct OrderCancelled {
+        #[ink(topic)]
+        pub order_id: u64,
+        #[ink(topic)]
+        pub owner: AccountId,
+        pub refunded: Balance,
+    }
+
+    /// Emitido quando a governança altera a fee dos keepers
+    #[ink(event)]
+    pub struct KeeperFeeChanged {
+        pub old_fee_bps: u16,
+        pub new_fee_bps: u16,
+    }
+
+    // ========================================
+    // ERROS ESPECÍFICOS DO LIMIT ORDER CONTRACT
+    // ========================================
+
+    /// Erros que podem ocorrer nas operações de ordens limitadas
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum LimitOrderError {
+        /// Endereço zero informado
+        ZeroAddress,
+        /// Amount zero informado
+        ZeroAmount,
+        /// Token de entrada e saída iguais
+        IdenticalAddresses,
+        /// Token não pertence ao par informado
+        TokenNotInPair,
+        /// Validade da ordem já passou ou é inválida
+        InvalidExpiry,
+        /// Ordem não encontrada
+        OrderNotFound,
+        /// Ordem não está aberta
+        OrderNotOpen,
+        /// Ordem expirada
+        OrderExpired,
+        /// Amount de execução maior que o saldo restante
+        InvalidFillAmount,
+        /// Reserves do par ainda não atingiram o preço alvo
+        PriceNotReached,
+        /// Limite de ordens abertas atingido
+        TooManyOrders,
+        /// Fee do keeper acima do limite
+        KeeperFeeTooHigh,
+        /// Caller não autorizado
+        Unauthorized,
+        /// Consulta ao par falhou
+        PairQueryFailed,
+        /// Chamada ao router falhou
+        RouterCallFailed,
+        /// Transferência de token falhou
+        TransferFailed,
+        /// Overflow aritmético
+        Overflow,
+        /// Erro no token PSP22 subjacente
+        PSP22(PSP22Error),
+        /// Amount da ordem abaixo do mínimo
+        OrderTooSmall,
+        /// Swap rejeitado pelo router
+        Router(RouterError),
+        /// Consulta aos metadados do token falhou
+        TokenQueryFailed,
+    }
+
+    impl From<PSP22Error> for LimitOrderError {
+        fn from(error: PSP22Error) -> Self {
+            LimitOrderError::PSP22(error)
+        }
+    }
+
+    // ========================================
+    // TIPOS AUXILIARES
+    // ========================================
+
+    /// Status de uma ordem limitada
+    #[derive(Debug, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum OrderStatus {
+        /// Aberta (pode estar parcialmente executada)
+        Open,
+        /// Totalmente executada
+        Filled,
+        /// Cancelada pelo dono
+        Cancelled,
+    }
+
+    /// Ordem limitada em escrow
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct LimitOrder {
+        /// Identificador único
+        pub id: u64,
+        /// Dono da ordem (recebe o output e reembolsos)
+        pub owner: AccountId,
+        /// Par usado na execução
+        pub pair: AccountId,
+        /// Token depositado em escrow
+        pub token_in: AccountId,
+        /// Token desejado
+        pub token_out: AccountId,
+        /// Amount original de entrada
+        pub amount_in: Balance,
+        /// Output mínimo desejado para o `amount_in` total (preço alvo)
+        pub target_amount_out: Balance,
+        /// Amount de entrada ainda não executado
+        pub remaining_in: Balance,
+        /// Output acumulado entregue ao dono
+        pub filled_out: Balance,
+        /// Timestamp de validade da ordem
+        pub expiry: Timestamp,
+        /// Status atual
+        pub status: OrderStatus,
+    }
+
+    /// Resultado da validação de uma execução contra as reserves do par
+    #[derive(Debug, PartialEq, Eq)]
+    struct FillPlan {
+        /// Output mínimo exigido pelo preço alvo
+        min_out: Balance,
+        /// Fee paga ao keeper
+        keeper_fee: Balance,
+        /// Amount efetivamente enviado ao router
+        swap_amount: Balance,
+    }
+
+    // ========================================
+    // CONSTANTES
+    // ========================================
+    mod constants {
+        /// Denominador das fees em basis points (100% = 10000)
+        pub const BPS_DENOMINATOR: u128 = 10_000;
+
+        /// Fee inicial do keeper (0.1%)
+        pub const DEFAULT_KEEPER_FEE_BPS: u16 = 10;
+
+        /// Fee máxima do keeper (1%)
+        pub const MAX_KEEPER_FEE_BPS: u16 = 100;
+
+        /// Fee estática do PairContract em bps (0.5% = 995/1000), usada quando
+        /// o par não expõe `current_fee_bps`
+        pub const PAIR_STATIC_FEE_BPS: u16 = 50;
+
+        /// Amount mínimo de entrada de uma ordem em frações do token:
+        /// `10^(decimals - 6)` unidades brutas, ou seja 0.000001 token
+        /// (evita ocupar o livro com poeira independentemente dos decimais)
+        pub const MIN_ORDER_SCALE_DECIMALS: u8 = 6;
+
+        /// Limite de ordens abertas por dono (mantém a consulta por dono limitada)
+        pub const MAX_OPEN_ORDERS_PER_OWNER: usize = 32;
+
+        /// Tamanho máximo de uma página da consulta por par
+        pub const MAX_PAGE_SIZE: u64 = 100;
+    }
+
+    /// Selectors das chamadas cross-contract
+    mod selectors {
+        pub const PAIR_TOKEN_0: [u8; 4] = ink::selector_bytes!("token_0");
+        pub const PAIR_TOKEN_1: [u8; 4] = ink::selector_bytes!("token_1");
+        pub const PAIR_GET_RESERVES: [u8; 4] = ink::selector_bytes!("get_reserves");
+        pub const PAIR_CURRENT_FEE_BPS: [u8; 4] = ink::selector_bytes!("current_fee_bps");
+        pub const ROUTER_SWAP_EXACT_TOKENS_FOR_TOKENS: [u8; 4] = ink::selector_bytes!("swap_exact_tokens_for_tokens");
+        pub const PSP22_TRANSFER: [u8; 4] = ink::selector_bytes!("PSP22::transfer");
+        pub const PSP22_TRANSFER_FROM: [u8; 4] = ink::selector_bytes!("PSP22::transfer_from");
+        pub const PSP22_APPROVE: [u8; 4] = ink::selector_bytes!("PSP22::approve");
+        pub const PSP22_TOKEN_DECIMALS: [u8; 4] = ink::selector_bytes!("PSP22Metadata::token_decimals");
+    }
+
+    // ========================================
+    // STORAGE DO LIMIT ORDER CONTRACT
+    // ========================================
+
+    #[ink(storage)]
+    pub struct LimitOrderContract {
+        /// Admin (governança)
+        admin: AccountId,
+        /// Router usado nas execuções
+        router: AccountId,
+        /// Fee paga ao keeper em basis points do amount executado
+        keeper_fee_bps: u16,
+        /// Próximo id de ordem
+        next_order_id: u64,
+        /// Ordens por id
+        orders: Mapping<u64, LimitOrder>,
+        /// Ids das ordens abertas por dono
+        owner_orders: Mapping<AccountId, Vec<u64>>,
+        /// Ids das ordens abertas por `(par, posição)`
+        pair_orders: Mapping<(AccountId, u64), u64>,
+        /// Quantidade de ordens abertas por par
+        pair_orders_length: Mapping<AccountId, u64>,
+        /// Posição de cada ordem aberta no índice do seu par
+        pair_order_position: Mapping<u64, u64>,
+    }
+
+    impl LimitOrderContract {
+        /// Construtor do Limit Order Contract
+        ///
+        /// O deployer se torna o admin (governança).
+        #[ink(constructor)]
+        pub fn new(router: AccountId) -> Self {
+            Self {
+                admin: Self::env().caller(),
+                router,
+                keeper_fee_bps: constants::DEFAULT_KEEPER_FEE_BPS,
+                next_order_id: 1,
+                orders: Mapping::default(),
+                owner_orders: Mapping::default(),
+                pair_orders: Mapping::default(),
+                pair_orders_length: Mapping::default(),
+                pair_order_position: Mapping::default(),
+            }
+        }
+
+        // ========================================
+        // QUERIES (READ-ONLY)
+        // ========================================
+
+        /// Retorna o admin
+        #[ink(message)]
+        pub fn admin(&self) -> AccountId {
+            self.admin
+        }
+
+        /// Retorna o router usado nas execuções
+        #[ink(message)]
+        pub fn router(&self) -> AccountId {
+            self.router
+        }
+
+        /// Retorna a fee atual do keeper em basis points
+        #[ink(message)]
+        pub fn keeper_fee_bps(&self) -> u16 {
+            self.keeper_fee_bps
+        }
+
+        /// Retorna uma ordem pelo id
+        #[ink(message)]
+        pub fn get_order(&self, order_id: u64) -> Option<LimitOrder> {
+            self.orders.get(order_id)
+        }
+
+        /// Retorna as ordens abertas de um dono
+        #[ink(message)]
+        pub fn get_orders_by_owner(&self, owner: AccountId) -> Vec<LimitOrder> {
+            self.collect_orders(self.owner_orders.get(owner).unwrap_or_default())
+        }
+
+        /// Retorna uma página das ordens abertas de um par
+        ///
+        /// A ordem dentro do índice não é estável: remover uma ordem move a
+        /// última para a sua posição. Ordens expiradas continuam listadas até
+        /// serem canceladas pelo dono.
+        ///
+        /// # Parâmetros
+        /// - `start`: Posição da primeira ordem no índice do par (0-based)
+        /// - `limit`: Máximo de ordens (limitado a `MAX_PAGE_SIZE`)
+        #[ink(message)]
+        pub fn get_orders_by_pair(&self, pair: AccountId, start: u64, limit: u64) -> Vec<LimitOrder> {
+            let end = start
+                .saturating_add(limit.min(constants::MAX_PAGE_SIZE))
+                .min(self.pair_orders_length(pair));
+            (start..end.max(start))
+                .filter_map(|position| self.pair_orders.get((pair, position)))
+                .filter_map(|id| self.orders.get(id))
+                .collect()
+        }
+
+        /// Retorna quantas ordens abertas o par possui
+        #[ink(message)]
+        pub fn pair_orders_length(&self, pair: AccountId) -> u64 {
+            self.pair_orders_length.get(pair).unwrap_or(0)
+        }
+
+        /// Output mínimo exigido para executar `fill_amount` de uma ordem
+        #[ink(message)]
+        pub fn required_amount_out(&self, order_id: u64, fill_amount: Balance) -> Result<Balance, LimitOrderError> {
+            let order = self.orders.get(order_id).ok_or(LimitOrderError::OrderNotFound)?;
+            Self::min_amount_out(&order, fill_amount)
+        }
+
+        // ========================================
+        // OPERAÇÕES DE ORDENS
+        // ========================================
+
+        /// Cria uma ordem limitada, depositando `amount_in` de `token_in` em escrow
+        ///
+        /// O caller precisa ter aprovado este contrato em `token_in`. Cada dono
+        /// tem no máximo `MAX_OPEN_ORDERS_PER_OWNER` ordens abertas; o índice do
+        /// par não tem limite, então nenhuma conta consegue bloquear um par.
+        /// `amount_in` precisa ser ao menos 0.000001 token (pelos decimais de `token_in`).
+        ///
+        /// # Parâmetros
+        /// - `pair`: Par `token_in`/`token_out` usado na execução
+        /// - `target_amount_out`: Output mínimo desejado para o `amount_in` total
+        /// - `expiry`: Timestamp após o qual a ordem não pode mais ser executada
+        ///
+        /// # Retorna
+        /// - Id da nova ordem
+        #[ink(message)]
+        pub fn place_order(
+            &mut self,
+            pair: AccountId,
+            token_in: AccountId,
+            token_out: AccountId,
+            amount_in: Balance,
+            target_amount_out: Balance,
+            expiry: Timestamp,
+        ) -> Result<u64, LimitOrderError> {
+            let owner = self.env().caller();
+            let zero_address = AccountId::from([0u8; 32]);
+            if pair == zero_address || token_in == zero_address || token_out == zero_address {
+                return Err(LimitOrderError::ZeroAddress);
+            }
+            if token_in == token_out {
+                return Err(LimitOrderError::IdenticalAddresses);
+            }
+            if amount_in == 0 || target_amount_out == 0 {
+                return Err(LimitOrderError::ZeroAmount);
+            }
+            if expiry <= self.env().block_timestamp() {
+                return Err(LimitOrderError::InvalidExpiry);
+            }
+            if self.owner_orders.get(owner).unwrap_or_default().len() >= constants::MAX_OPEN_ORDERS_PER_OWNER {
+                return Err(LimitOrderError::TooManyOrders);
+            }
+
+            // Garantir que o par negocia os tokens da ordem
+            self.pair_reserves(pair, token_in, token_out)?;
+            if amount_in < Self::min_order_amount(self.token_decimals(token_in)?) {
+                return Err(LimitOrderError::OrderTooSmall);
+            }
+
+            let order_id = self.next_order_id;
+            self.next_order_id = order_id.checked_add(1).ok_or(LimitOrderError::Overflow)?;
+
+            let order = LimitOrder {
+                id: order_id,
+                owner,
+                pair,
+                token_in,
+                token_out,
+                amount_in,
+                target_amount_out,
+                remaining_in: amount_in,
+                filled_out: 0,
+                expiry,
+                status: OrderStatus::Open,
+            };
+            self.orders.insert(order_id, &order);
+            self.add_to_indexes(&order)?;
+
+            // Escrow do token de entrada
+            self.transfer_from_token(token_in, owner, self.env().account_id(), amount_in)?;
+
+            self.env().emit_event(OrderPlaced {
+                order_id,
+                owner,
+                pair,
+                token_in,
+                token_out,
+                amount_in,
+                target_amount_out,
+                expiry,
+            });
+
+            Ok(order_id)
+        }
+
+        /// Executa `fill_amount` de uma ordem aberta (qualquer conta pode chamar)
+        ///
+        /// A fee do keeper é descontada do `fill_amount`; o restante é trocado
+        /// via router e o output vai direto para o dono da ordem, que recebe
+        /// ao menos o preço alvo sobre o `fill_amount` completo.
+        ///
+        /// # Retorna
+        /// - Output entregue ao dono da ordem
+        #[ink(message)]
+        pub fn fill_order(&mut self, order_id: u64, fill_amount: Balance) -> Result<Balance, LimitOrderError> {
+            let keeper = self.env().caller();
+            let mut order = self.orders.get(order_id).ok_or(LimitOrderError::OrderNotFound)?;
+            if order.status != OrderStatus::Open {
+                return Err(LimitOrderError::OrderNotOpen);
+            }
+            if self.env().block_timestamp() > order.expiry {
+                return Err(LimitOrderError::OrderExpired);
+            }
+            if fill_amount == 0 || fill_amount > order.remaining_in {
+                return Err(LimitOrderError::InvalidFillAmount);
+            }
+
+            // Verificar se as reserves atingiram o preço alvo
+            let (reserve_in, reserve_out) = self.pair_reserves(order.pair, order.token_in, order.token_out)?;
+            let pair_fee_bps = self.pair_fee_bps(order.pair);
+            let plan = Self::plan_fill(&order, fill_amount, self.keeper_fee_bps, pair_fee_bps, reserve_in, reserve_out)?;
+
+            // Efeitos antes das chamadas externas
+            self.record_fill(&mut order, fill_amount)?;
+
+            // Swap via router com o output direto para o dono
+            let amount_out = self.swap_via_router(&order, plan.swap_amount, plan.min_out)?;
+            if plan.keeper_fee > 0 {
+                self.transfer_token(order.token_in, keeper, plan.keeper_fee)?;
+            }
+
+            order.filled_out = order
+                .filled_out
+                .checked_add(amount_out)
+                .ok_or(LimitOrderError::Overflow)?;
+            self.orders.insert(order_id, &order);
+
+            self.env().emit_event(OrderFilled {
+                order_id,
+                keeper,
+                amount_in: fill_amount,
+                amount_out,
+                keeper_fee: plan.keeper_fee,
+                remaining_in: order.remaining_in,
+            });
+
+            Ok(amount_out)
+        }
+
+        /// Cancela uma ordem aberta e reembolsa o saldo restante (apenas o dono)
+        ///
+        /// Ordens expiradas também são canceladas por aqui.
+        ///
+        /// # Retorna
+        /// - Amount reembolsado
+        #[ink(message)]
+        pub fn cancel_order(&mut self, order_id: u64) -> Result<Balance, LimitOrderError> {
+            let mut order = self.orders.get(order_id).ok_or(LimitOrderError::OrderNotFound)?;
+            if order.owner != self.env().caller() {
+                return Err(LimitOrderError::Unauthorized);
+            }
+            if order.status != OrderStatus::Open {
+                return Err(LimitOrderError::OrderNotOpen);
+            }
+
+            let refunded = order.remaining_in;
+            order.remaining_in = 0;
+            order.status = OrderStatus::Cancelled;
+            self.remove_from_indexes(&order);
+            self.orders.insert(order_id, &order);
+
+            if refunded > 0 {
+                self.transfer_token(order.token_in, order.owner, refunded)?;
+            }
+
+            self.env().emit_event(OrderCancelled {
+                order_id,
+                owner: order.owner,
+                refunded,
+            });
+
+            Ok(refunded)
+        }
+
+        // ========================================
+        // GOVERNANÇA
+        // ========================================
+
+        /// Define a fee do keeper (apenas admin)
+        #[ink(message)]
+        pub fn set_keeper_fee_bps(&mut self, fee_bps: u16) -> Result<(), LimitOrderError> {
+            if self.env().caller() != self.admin {
+                return Err(LimitOrderError::Unauthorized);
+            }
+            if fee_bps > constants::MAX_KEEPER_FEE_BPS {
+                return Err(LimitOrderError::KeeperFeeTooHigh);
+            }
+
+            let old_fee_bps = self.keeper_fee_bps;
+            self.keeper_fee_bps = fee_bps;
+
+            self.env().emit_event(KeeperFeeChanged {
+                old_fee_bps,
+                new_fee_bps: fee_bps,
+            });
+
+            Ok(())
+        }
+
+        // ========================================
+        // FUNÇÕES INTERNAS (CÁLCULOS)
+        // ========================================
+
+        /// Output mínimo proporcional ao preço alvo (arredondado para cima)
+        fn min_amount_out(order: &LimitOrder, fill_amount: Balance) -> Result<Balance, LimitOrderError> {
+            if fill_amount == 0 || order.amount_in == 0 {
+                return Err(LimitOrderError::ZeroAmount);
+            }
+            let numerator = U256::from(fill_amount)
+                .checked_mul(U256::from(order.target_amount_out))
+                .ok_or(LimitOrderError::Overflow)?;
+            let amount_in = U256::from(order.amount_in);
+            let min_out = numerator
+                .checked_add(amount_in)
+                .and_then(|value| value.checked_sub(U256::one()))
+                .and_then(|value| value.checked_div(amount_in))
+                .ok_or(LimitOrderError::Overflow)?;
+            u128::try_from(min_out).map_err(|_| LimitOrderError::Overflow)
+        }
+
+        /// Amount mínimo de uma ordem para um token com `decimals` casas
+        fn min_order_amount(decimals: u8) -> Balance {
+            10u128
+                .checked_pow(u32::from(decimals.saturating_sub(constants::MIN_ORDER_SCALE_DECIMALS)))
+                .unwrap_or(Balance::MAX)
+        }
+
+        /// Calcula fee, amount de swap e output mínimo, exigindo que as reserves
+        /// atinjam o preço alvo na fee cobrada pelo par
+        fn plan_fill(
+            order: &LimitOrder,
+            fill_amount: Balance,
+            keeper_fee_bps: u16,
+            pair_fee_bps: u16,
+            reserve_in: Balance,
+            reserve_out: Balance,
+        ) -> Result<FillPlan, LimitOrderError> {
+            let min_out = Self::min_amount_out(order, fill_amount)?;
+            let keeper_fee = Self::keeper_fee_of(fill_amount, keeper_fee_bps)?;
+            let swap_amount = fill_amount.checked_sub(keeper_fee).ok_or(LimitOrderError::Overflow)?;
+
+            let expected_out = Self::quote_amount_out(swap_amount, reserve_in, reserve_out, pair_fee_bps)?;
+            if expected_out < min_out {
+                return Err(LimitOrderError::PriceNotReached);
+            }
+            Ok(FillPlan {
+                min_out,
+                keeper_fee,
+                swap_amount,
+            })
+        }
+
+        /// Fee do keeper sobre o amount executado (arredondada para baixo)
+        fn keeper_fee_of(amount: Balance, fee_bps: u16) -> Result<Balance, LimitOrderError> {
+            let fee = U256::from(amount)
+                .checked_mul(U256::from(fee_bps))
+                .and_then(|value| value.checked_div(U256::from(constants::BPS_DENOMINATOR)))
+                .ok_or(LimitOrderError::Overflow)?;
+            u128::try_from(fee).map_err(|_| LimitOrderError::Overflow)
+        }
+
+        /// Output de produto constante com a fee do par em bps
+        fn quote_amount_out(
+            amount_in: Balance,
+            reserve_in: Balance,
+            reserve_out: Balance,
+            fee_bps: u16,
+        ) -> Result<Balance, LimitOrderError> {
+            if reserve_in == 0 || reserve_out == 0 {
+                return Err(LimitOrderError::PriceNotReached);
+            }
+            let fee_numerator = constants::BPS_DENOMINATOR
+                .checked_sub(u128::from(fee_bps))
+                .ok_or(LimitOrderError::Overflow)?;
+            let amount_in_with_fee = U256::from(amount_in)
+                .checked_mul(U256::from(fee_numerator))
+                .ok_or(LimitOrderError::Overflow)?;
+            let numerator = amount_in_with_fee
+                .checked_mul(U256::from(reserve_out))
+                .ok_or(LimitOrderError::Overflow)?;
+            let denominator = U256::from(reserve_in)
+                .checked_mul(U256::from(constants::BPS_DENOMINATOR))
+                .and_then(|value| value.checked_add(amount_in_with_fee))
+                .ok_or(LimitOrderError::Overflow)?;
+            let amount_out = numerator.checked_div(denominator).ok_or(LimitOrderError::Overflow)?;
+            u128::try_from(amount_out).map_err(|_| LimitOrderError::Overflow)
+        }
+
+        /// Carrega as ordens de uma lista de ids
+        fn collect_orders(&self, ids: Vec<u64>) -> Vec<LimitOrder> {
+            ids.into_iter().filter_map(|id| self.orders.get(id)).collect()
+        }
+
+        /// Debita a execução da ordem e a fecha quando não resta saldo
+        fn record_fill(&mut self, order: &mut LimitOrder, fill_amount: Balance) -> Result<(), LimitOrderError> {
+            order.remaining_in = order
+                .remaining_in
+                .checked_sub(fill_amount)
+                .ok_or(LimitOrderError::Overflow)?;
+            if order.remaining_in == 0 {
+                order.status = OrderStatus::Filled;
+                self.remove_from_indexes(order);
+            }
+            self.orders.insert(order.id, order);
+            Ok(())
+        }
+
+        /// Adiciona uma ordem aberta aos índices de dono e par
+        fn add_to_indexes(&mut self, order: &LimitOrder) -> Result<(), LimitOrderError> {
+            let mut owner_ids = self.owner_orders.get(order.owner).unwrap_or_default();
+            owner_ids.push(order.id);
+            self.owner_orders.insert(order.owner, &owner_ids);
+
+            let position = self.pair_orders_length(order.pair);
+            let length = position.checked_add(1).ok_or(LimitOrderError::Overflow)?;
+            self.pair_orders.insert((order.pair, position), &order.id);
+            self.pair_order_position.insert(order.id, &position);
+            self.pair_orders_length.insert(order.pair, &length);
+            Ok(())
+        }
+
+        /// Remove uma ordem fechada dos índices de dono e par
+        ///
+        /// No índice do par a última ordem ocupa a posição liberada (O(1)).
+        fn remove_from_indexes(&mut self, order: &LimitOrder) {
+            let mut owner_ids = self.owner_orders.get(order.owner).unwrap_or_default();
+            owner_ids.retain(|id| *id != order.id);
+            self.owner_orders.insert(order.owner, &owner_ids);
+
+            let Some(position) = self.pair_order_position.take(order.id) else {
+                return;
+            };
+            let last = self.pair_orders_length(order.pair).saturating_sub(1);
+            if position != last {
+                if let Some(moved) = self.pair_orders.get((order.pair, last)) {
+                    self.pair_orders.insert((order.pair, position), &moved);
+                    self.pair_order_position.insert(moved, &position);
+                }
+            }
+            self.pair_orders.remove((order.pair, last));
+            self.pair_orders_length.insert(order.pair, &last);
+        }
+
+        // ========================================
+        // FUNÇÕES INTERNAS (CHAMADAS CROSS-CONTRACT)
+        // ========================================
+
+        /// Reserves do par orientadas como `(reserve_in, reserve_out)`
+        fn pair_reserves(
+            &self,
+            pair: AccountId,
+            token_in: AccountId,
+            token_out: AccountId,
+        ) -> Result<(Balance, Balance), LimitOrderError> {
+            let token_0: AccountId = self.query_pair(pair, selectors::PAIR_TOKEN_0)?;
+            let token_1: AccountId = self.query_pair(pair, selectors::PAIR_TOKEN_1)?;
+            let (reserve_0, reserve_1, _): (Balance, Balance, Timestamp) =
+                self.query_pair(pair, selectors::PAIR_GET_RESERVES)?;
+
+            if token_in == token_0 && token_out == token_1 {
+                Ok((reserve_0, reserve_1))
+            } else if token_in == token_1 && token_out == token_0 {
+                Ok((reserve_1, reserve_0))
+            } else {
+                Err(LimitOrderError::TokenNotInPair)
+            }
+        }
+
+        /// Fee de swap atual do par em bps (estática quando o par não expõe `current_fee_bps`)
+        fn pair_fee_bps(&self, pair: AccountId) -> u16 {
+            self.query_pair(pair, selectors::PAIR_CURRENT_FEE_BPS)
+                .unwrap_or(constants::PAIR_STATIC_FEE_BPS)
+        }
+
+        /// Decimais de `token` (`PSP22Metadata::token_decimals`)
+        fn token_decimals(&self, token: AccountId) -> Result<u8, LimitOrderError> {
+            build_call::<DefaultEnvironment>()
+                .call(token)
+                .exec_input(ExecutionInput::new(Selector::new(selectors::PSP22_TOKEN_DECIMALS)))
+                .returns::<u8>()
+                .try_invoke()
+                .map_err(|_| LimitOrderError::TokenQueryFailed)?
+                .map_err(|_| LimitOrderError::TokenQueryFailed)
+        }
+
+        /// Consulta uma mensagem sem argumentos do Pair Contract
+        fn query_pair<R: scale::Decode>(&self, pair: AccountId, selector: [u8; 4]) -> Result<R, LimitOrderError> {
+            build_call::<DefaultEnvironment>()
+                .call(pair)
+                .exec_input(ExecutionInput::new(Selector::new(selector)))
+                .returns::<R>()
+                .try_invoke()
+                .map_err(|_| LimitOrderError::PairQueryFailed)?
+                .map_err(|_| LimitOrderError::PairQueryFailed)
+        }
+
+        /// Aprova o router e executa o swap com output para o dono da ordem
+        fn swap_via_router(&self, order: &LimitOrder, amount_in: Balance, min_out: Balance) -> Result<Balance, LimitOrderError> {
+            build_call::<DefaultEnvironment>()
+                .call(order.token_in)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selectors::PSP22_APPROVE))
+                        .push_arg(self.router)
+                        .push_arg(amount_in),
+                )
+                .returns::<Result<(), PSP22Error>>()
+                .try_invoke()
+                .map_err(|_| LimitOrderError::TransferFailed)?
+                .map_err(|_| LimitOrderError::TransferFailed)??;
+
+            let amounts = build_call::<DefaultEnvironment>()
+                .call(self.router)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selectors::ROUTER_SWAP_EXACT_TOKENS_FOR_TOKENS))
+                        .push_arg(amount_in)
+                        .push_arg(min_out)
+                        .push_arg(vec![order.token_in, order.token_out])
+                        .push_arg(order.owner)
+                        .push_arg(self.env().block_timestamp()),
+                )
+                .returns::<Result<Vec<Balance>, RouterError>>()
+                .try_invoke()
+                .map_err(|_| LimitOrderError::RouterCallFailed)?
+                .map_err(|_| LimitOrderError::RouterCallFailed)?
+                .map_err(LimitOrderError::Router)?;
+
+            amounts.last().copied().ok_or(LimitOrderError::RouterCallFailed)
+        }
+
+        /// Transfere tokens PSP22 mantidos em escrow
+        fn transfer_token(&self, token: AccountId, to: AccountId, amount: Balance) -> Result<(), LimitOrderError> {
+            build_call::<DefaultEnvironment>()
+                .call(token)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selectors::PSP22_TRANSFER))
+                        .push_arg(to)
+                        .push_arg(amount)
+                        .push_arg(Vec::<u8>::new()),
+                )
+                .returns::<Result<(), PSP22Error>>()
+                .try_invoke()
+                .map_err(|_| LimitOrderError::TransferFailed)?
+                .map_err(|_| LimitOrderError::TransferFailed)??;
+            Ok(())
+        }
+
+        /// Puxa tokens PSP22 de `from` usando a allowance concedida
+        fn transfer_from_token(
+            &self,
+            token: AccountId,
+            from: AccountId,
+            to: AccountId,
+            amount: Balance,
+        ) -> Result<(), LimitOrderError> {
+            build_call::<DefaultEnvironment>()
+                .call(token)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selectors::PSP22_TRANSFER_FROM))
+                        .push_arg(from)
+                        .push_arg(to)
+                        .push_arg(amount)
+                        .push_arg(Vec::<u8>::new()),
+                )
+                .returns::<Result<(), PSP22Error>>()
+                .try_invoke()
+                .map_err(|_| LimitOrderError::TransferFailed)?
+                .map_err(|_| LimitOrderError::TransferFailed)??;
+            Ok(())
+        }
+    }
+
+    // ========================================
+    // TESTES UNITÁRIOS
+    // ========================================
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        fn default_accounts() -> ink::env::test::DefaultAccounts<DefaultEnvironment> {
+            ink::env::test::default_accounts::<DefaultEnvironment>()
+        }
+
+        fn set_sender(sender: AccountId) {
+            ink::env::test::set_caller::<DefaultEnvironment>(sender);
+        }
+
+        fn set_timestamp(timestamp: u64) {
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(timestamp);
+        }
+
+        /// Amount das ordens de teste: o mínimo para um token de 18 decimais
+        const ORDER_AMOUNT_IN: Balance = 1_000_000_000_000;
+
+        /// Registra uma ordem de 1 token_in por 2 token_out como `place_order`
+        /// faria após o escrow, que exige um PSP22 real
+        fn insert_open_order(contract: &mut LimitOrderContract, owner: AccountId, pair: AccountId) -> u64 {
+            let accounts = default_accounts();
+            let order_id = contract.next_order_id;
+            contract.next_order_id = order_id.checked_add(1).unwrap();
+            let order = LimitOrder {
+                id: order_id,
+                owner,
+                pair,
+                token_in: accounts.django,
+                token_out: accounts.eve,
+                amount_in: ORDER_AMOUNT_IN,
+                target_amount_out: ORDER_AMOUNT_IN.checked_mul(2).unwrap(),
+                remaining_in: ORDER_AMOUNT_IN,
+                filled_out: 0,
+                expiry: 5_000,
+                status: OrderStatus::Open,
+            };
+            contract.orders.insert(order_id, &order);
+            contract.add_to_indexes(&order).unwrap();
+            order_id
+        }
+
+        /// Ids de uma página do índice do par
+        fn pair_ids(contract: &LimitOrderContract, pair: AccountId) -> Vec<u64> {
+            contract.get_orders_by_pair(pair, 0, 100).iter().map(|o| o.id).collect()
+        }
+
+        #[ink::test]
+        fn test_new_limit_order_contract() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            let contract = LimitOrderContract::new(accounts.bob);
+
+            assert_eq!(contract.admin(), accounts.alice);
+            assert_eq!(contract.router(), accounts.bob);
+            assert_eq!(contract.keeper_fee_bps(), constants::DEFAULT_KEEPER_FEE_BPS);
+            assert_eq!(contract.get_order(1), None);
+        }
+
+        #[ink::test]
+        fn test_place_order_validations() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            set_timestamp(1_000);
+
+            let mut contract = LimitOrderContract::new(accounts.bob);
+            let zero = AccountId::from([0u8; 32]);
+
+            // RED: Endereço zero
+            assert_eq!(
+                contract.place_order(zero, accounts.django, accounts.eve, 100, 200, 2_000),
+                Err(LimitOrderError::ZeroAddress)
+            );
+            // RED: Tokens iguais
+            assert_eq!(
+                contract.place_order(accounts.charlie, accounts.django, accounts.django, 100, 200, 2_000),
+                Err(LimitOrderError::IdenticalAddresses)
+            );
+            // RED: Amounts zero
+            assert_eq!(
+                contract.place_order(accounts.charlie, accounts.django, accounts.eve, 0, 200, 2_000),
+                Err(LimitOrderError::ZeroAmount)
+            );
+            // RED: Validade no passado
+            assert_eq!(
+                contract.place_order(accounts.charlie, accounts.django, accounts.eve, 1_000_000, 200, 1_000),
+                Err(LimitOrderError::InvalidExpiry)
+            );
+        }
+
+        #[ink::test]
+        fn test_min_order_amount_follows_decimals() {
+            // GREEN: 0.000001 token em unidades brutas
+            assert_eq!(LimitOrderContract::min_order_amount(18), ORDER_AMOUNT_IN);
+            assert_eq!(LimitOrderContract::min_order_amount(12), 1_000_000);
+            assert_eq!(LimitOrderContract::min_order_amount(8), 100);
+
+            // GREEN: Tokens com poucos decimais aceitam 1 unidade bruta
+            assert_eq!(LimitOrderContract::min_order_amount(6), 1);
+            assert_eq!(LimitOrderContract::min_order_amount(0), 1);
+
+            // GREEN: Decimais absurdos não estouram (nenhuma ordem é aceita)
+            assert_eq!(LimitOrderContract::min_order_amount(255), Balance::MAX);
+        }
+
+        #[ink::test]
+        #[allow(clippy::arithmetic_side_effects)]
+        fn test_fill_plan_and_record() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            let mut contract = LimitOrderContract::new(accounts.bob);
+            let order_id = insert_open_order(&mut contract, accounts.alice, accounts.charlie);
+            let mut order = contract.get_order(order_id).unwrap();
+
+            let (reserve_in, fill) = (10_000 * ORDER_AMOUNT_IN, ORDER_AMOUNT_IN);
+
+            // RED: Reserves 1:1 não atingem o preço alvo de 2:1
+            assert_eq!(
+                LimitOrderContract::plan_fill(&order, fill, 10, 50, reserve_in, reserve_in),
+                Err(LimitOrderError::PriceNotReached)
+            );
+
+            // RED: 1:2.01 atingiria o alvo a 0.3%, mas não na fee de 0.5% do par
+            let reserve_out = reserve_in / 1_000 * 2_010;
+            assert!(LimitOrderContract::plan_fill(&order, fill, 0, 30, reserve_in, reserve_out).is_ok());
+            assert_eq!(
+                LimitOrderContract::plan_fill(&order, fill, 0, 50, reserve_in, reserve_out),
+                Err(LimitOrderError::PriceNotReached)
+            );
+
+            // GREEN: Reserves 1:3 atingem o alvo; fee de 0.1% vai para o keeper
+            assert_eq!(
+                LimitOrderContract::plan_fill(&order, fill, 10, 50, reserve_in, 3 * reserve_in),
+                Ok(FillPlan {
+                    min_out: 2 * ORDER_AMOUNT_IN,
+                    keeper_fee: ORDER_AMOUNT_IN / 1_000,
+                    swap_amount: ORDER_AMOUNT_IN / 1_000 * 999,
+                })
+            );
+
+            // GREEN: Execução parcial mantém a ordem aberta e indexada
+            assert!(contract.record_fill(&mut order, ORDER_AMOUNT_IN / 10 * 4).is_ok());
+            assert_eq!(contract.get_order(order_id).unwrap().remaining_in, ORDER_AMOUNT_IN / 10 * 6);
+            assert_eq!(contract.pair_orders_length(accounts.charlie), 1);
+
+            // GREEN: Execução do restante fecha a ordem e a remove dos índices
+            assert!(contract.record_fill(&mut order, ORDER_AMOUNT_IN / 10 * 6).is_ok());
+            assert_eq!(contract.get_order(order_id).unwrap().status, OrderStatus::Filled);
+            assert!(contract.get_orders_by_owner(accounts.alice).is_empty());
+            assert_eq!(contract.pair_orders_length(accounts.charlie), 0);
+        }
+
+        #[ink::test]
+        fn test_pair_index_is_paged_and_unbounded() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            let mut contract = LimitOrderContract::new(accounts.bob);
+
+            // GREEN: Muitos donos (cada um dentro do seu limite) enchem o par sem teto fixo
+            let ids: Vec<u64> = (0..300u16)
+                .map(|index| {
+                    let mut owner = [7u8; 32];
+                    owner[..2].copy_from_slice(&index.to_le_bytes());
+                    insert_open_order(&mut contract, AccountId::from(owner), accounts.charlie)
+                })
+                .collect();
+            assert_eq!(contract.pair_orders_length(accounts.charlie), 300);
+
+            // GREEN: Páginas limitadas a MAX_PAGE_SIZE
+            assert_eq!(contract.get_orders_by_pair(accounts.charlie, 0, 500).len(), 100);
+            assert_eq!(contract.get_orders_by_pair(accounts.charlie, 250, 100).len(), 50);
+            assert!(contract.get_orders_by_pair(accounts.charlie, 300, 10).is_empty());
+
+            // GREEN: Remover do meio move a última ordem para a posição liberada
+            let removed = contract.get_order(ids[1]).unwrap();
+            contract.remove_from_indexes(&removed);
+            assert_eq!(contract.pair_orders_length(accounts.charlie), 299);
+            let first_page: Vec<u64> = contract.get_orders_by_pair(accounts.charlie, 0, 3).iter().map(|o| o.id).collect();
+            assert_eq!(first_page, vec![ids[0], ids[299], ids[2]]);
+
+            // GREEN: A ordem movida continua removível pela nova posição
+            let moved = contract.get_order(ids[299]).unwrap();
+            contract.remove_from_indexes(&moved);
+            let first_page: Vec<u64> = contract.get_orders_by_pair(accounts.charlie, 0, 3).iter().map(|o| o.id).collect();
+            assert_eq!(first_page, vec![ids[0], ids[298], ids[2]]);
+        }
+
+        #[ink::test]
+        fn test_min_amount_out_rounds_up_for_partial_fills() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            let mut contract = LimitOrderContract::new(accounts.bob);
+            let order_id = insert_open_order(&mut contract, accounts.alice, accounts.charlie);
+
+            // GREEN: Preço alvo de 2 token_out por token_in
+            assert_eq!(contract.required_amount_out(order_id, ORDER_AMOUNT_IN), Ok(2 * ORDER_AMOUNT_IN));
+            assert_eq!(contract.required_amount_out(order_id, 250), Ok(500));
+
+            // GREEN: Frações arredondam a favor do dono
+            let mut order = contract.get_order(order_id).unwrap();
+            order.amount_in = 1_000;
+            order.target_amount_out = 1_001;
+            assert_eq!(LimitOrderContract::min_amount_out(&order, 500), Ok(501));
+
+            // RED: Ordem inexistente
+            assert_eq!(contract.required_amount_out(99, 1), Err(LimitOrderError::OrderNotFound));
+        }
+
+        #[ink::test]
+        fn test_quote_and_keeper_fee() {
+            // GREEN: 1000 * 9950 * 10000 / (10000 * 10000 + 1000 * 9950) = 904 (fee do par, 0.5%)
+            assert_eq!(LimitOrderContract::quote_amount_out(1_000, 10_000, 10_000, 50), Ok(904));
+            // GREEN: Fee dinâmica mais alta reduz a cotação
+            assert_eq!(LimitOrderContract::quote_amount_out(1_000, 10_000, 10_000, 100), Ok(900));
+            assert_eq!(
+                LimitOrderContract::quote_amount_out(1_000, 0, 10_000, 50),
+                Err(LimitOrderError::PriceNotReached)
+            );
+
+            // GREEN: 0.1% de 10000 = 10
+            assert_eq!(LimitOrderContract::keeper_fee_of(10_000, 10), Ok(10));
+            assert_eq!(LimitOrderContract::keeper_fee_of(999, 10), Ok(0));
+        }
+
+        #[ink::test]
+        fn test_fill_order_validations() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            set_timestamp(1_000);
+
+            let mut contract = LimitOrderContract::new(accounts.bob);
+            let order_id = insert_open_order(&mut contract, accounts.alice, accounts.charlie);
+
+            set_sender(accounts.frank);
+
+            // RED: Amount inválido
+            assert_eq!(contract.fill_order(order_id, 0), Err(LimitOrderError::InvalidFillAmount));
+            assert_eq!(
+                contract.fill_order(order_id, ORDER_AMOUNT_IN + 1),
+                Err(LimitOrderError::InvalidFillAmount)
+            );
+
+            // RED: Ordem inexistente
+            assert_eq!(contract.fill_order(99, 10), Err(LimitOrderError::OrderNotFound));
+
+            // RED: Ordem expirada
+            set_timestamp(5_001);
+            assert_eq!(contract.fill_order(order_id, 10), Err(LimitOrderError::OrderExpired));
+        }
+
+        #[ink::test]
+        fn test_cancel_order_only_owner_and_open() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            let mut contract = LimitOrderContract::new(accounts.bob);
+            let order_id = insert_open_order(&mut contract, accounts.alice, accounts.charlie);
+
+            // RED: Apenas o dono cancela
+            set_sender(accounts.frank);
+            assert_eq!(contract.cancel_order(order_id), Err(LimitOrderError::Unauthorized));
+
+            // RED: Ordem já fechada
+            let mut order = contract.get_order(order_id).unwrap();
+            order.status = OrderStatus::Filled;
+            contract.orders.insert(order_id, &order);
+            set_sender(accounts.alice);
+            assert_eq!(contract.cancel_order(order_id), Err(LimitOrderError::OrderNotOpen));
+        }
+
+        #[ink::test]
+        fn test_queries_by_owner_and_pair() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            let mut contract = LimitOrderContract::new(accounts.bob);
+            let first = insert_open_order(&mut contract, accounts.alice, accounts.charlie);
+            let second = insert_open_order(&mut contract, accounts.alice, accounts.frank);
+            let third = insert_open_order(&mut contract, accounts.eve, accounts.charlie);
+
+            let by_owner: Vec<u64> = contract.get_orders_by_owner(accounts.alice).iter().map(|o| o.id).collect();
+            assert_eq!(by_owner, vec![first, second]);
+            assert_eq!(pair_ids(&contract, accounts.charlie), vec![first, third]);
+
+            // GREEN: Ordens fechadas saem dos índices mas continuam consultáveis por id
+            let order = contract.get_order(first).unwrap();
+            contract.remove_from_indexes(&order);
+            assert_eq!(contract.get_orders_by_owner(accounts.alice).len(), 1);
+            assert_eq!(pair_ids(&contract, accounts.charlie), vec![third]);
+            assert!(contract.get_order(first).is_some());
+        }
+
+        #[ink::test]
+        fn test_set_keeper_fee_governance() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            let mut contract = LimitOrderContract::new(accounts.bob);
+
+            // RED: Apenas admin
+            set_sender(accounts.frank);
+            assert_eq!(contract.set_keeper_fee_bps(20), Err(LimitOrderError::Unauthorized));
+
+            // RED: Acima do limite
+            set_sender(accounts.alice);
+            assert_eq!(contract.set_keeper_fee_bps(101), Err(LimitOrderError::KeeperFeeTooHigh));
+
+            // GREEN: Fee atualizada
+            assert!(contract.set_keeper_fee_bps(20).is_ok());
+            assert_eq!(contract.keeper_fee_bps(), 20);
+        }
+    }
+}
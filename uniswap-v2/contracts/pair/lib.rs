@@ -2,7 +2,13 @@
 #![warn(clippy::arithmetic_side_effects)]
 #[ink::contract]
 pub mod pair_contract {
-    use psp22::PSP22Error;
+    use ink::prelude::vec::Vec;
+    use psp22::{PSP22, PSP22Error};
+    use primitive_types::U256;
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::DefaultEnvironment;
+    pub use permit::PermitSignature;
+    pub use checkpoints::Checkpoint;
 
     // ========================================
     // PAIR CONTRACT - AUTOMATED MARKET MAKER (AMM)
@@ -21,6 +27,15 @@ pub mod pair_contract {
     // - Aritmética segura com overflow protection
     // - K-invariant check para prevenir manipulação de preços
     // - Minimum liquidity lock para evitar divisão por zero
+    // - Pausa de emergência: mint/swap bloqueados pela flag do factory (burn sempre liberado)
+    // - Circuit breaker: o swap que levaria o preço além do threshold definido
+    //   pelo factory (medido desde o início do bloco) é rejeitado antes de
+    //   alterar as reserves
+    //
+    // ## Fee Dinâmica (opcional):
+    // A fee de swap varia entre um mínimo e um máximo conforme a volatilidade
+    // realizada, medida entre TWAPs consecutivos do preço das reserves. A fee
+    // vigente é cobrada sobre o input medido de cada swap.
     // 
     // ## Fórmula AMM:
     // `k = reserve_0 * reserve_1` (produto constante)
@@ -79,6 +94,52 @@ pub mod pair_contract {
         pub reserve_1: Balance,
     }
 
+    /// Emitido quando o factory altera o threshold do circuit breaker
+    #[ink(event)]
+    pub struct CircuitBreakerThresholdChanged {
+        pub old_threshold_bps: u32,
+        pub new_threshold_bps: u32,
+    }
+
+    /// Emitido quando o factory pausa ou libera mint/swap neste par
+    #[ink(event)]
+    pub struct PauseChanged {
+        pub paused: bool,
+    }
+
+    /// Emitido quando o factory define o router autorizado a aplicar desconto de fee
+    #[ink(event)]
+    pub struct FeeDiscountRouterChanged {
+        pub router: Option<AccountId>,
+    }
+
+    /// Emitido quando o factory altera o modo de fee dinâmica
+    #[ink(event)]
+    pub struct DynamicFeeConfigChanged {
+        /// Nova configuração (`None` = fee estática)
+        pub config: Option<DynamicFeeConfig>,
+    }
+
+    /// Emitido em transferências de LP token (PSP22)
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        pub from: Option<AccountId>,
+        #[ink(topic)]
+        pub to: Option<AccountId>,
+        pub value: Balance,
+    }
+
+    /// Emitido quando a allowance de LP token muda (PSP22)
+    #[ink(event)]
+    pub struct Approval {
+        #[ink(topic)]
+        pub owner: AccountId,
+        #[ink(topic)]
+        pub spender: AccountId,
+        pub value: Balance,
+    }
+
     // ========================================
     // ERROS ESPECÍFICOS DO PAIR CONTRACT
     // ========================================
@@ -106,6 +167,18 @@ pub mod pair_contract {
         Overflow,
         /// Contrato travado (proteção reentrância)
         Locked,
+        /// Circuit breaker disparado: preço se moveu além do threshold neste bloco
+        CircuitBreaker,
+        /// Configuração de fee dinâmica inválida
+        InvalidFeeConfig,
+        /// Mint/swap pausados pelo factory (burn continua liberado)
+        Paused,
+        /// Deadline do permit expirado
+        PermitExpired,
+        /// Assinatura do permit inválida
+        InvalidSignature,
+        /// Transferência ou consulta de token falhou
+        TransferFailed,
         /// Erro no token PSP22 subjacente
         PSP22(PSP22Error),
     }
@@ -116,6 +189,48 @@ pub mod pair_contract {
         }
     }
 
+
+    /// Estado do circuit breaker de preço por bloco
+    #[derive(Debug, Default, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct CircuitBreakerState {
+        /// Movimento máximo de preço por bloco em bps (0 = desabilitado)
+        pub threshold_bps: u32,
+        /// Bloco ao qual o snapshot de reserves se refere
+        pub block: BlockNumber,
+        /// Reserves no início do bloco
+        pub start_reserve_0: Balance,
+        pub start_reserve_1: Balance,
+    }
+
+    /// Configuração do modo de fee dinâmica
+    #[derive(Debug, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct DynamicFeeConfig {
+        /// Fee com volatilidade zero (bps)
+        pub min_fee_bps: u16,
+        /// Fee a partir de `volatility_cap_bps` (bps)
+        pub max_fee_bps: u16,
+        /// Janela de medição do TWAP (ms)
+        pub window: Timestamp,
+        /// Volatilidade por janela (bps) em que a fee atinge o máximo
+        pub volatility_cap_bps: u32,
+    }
+
+    /// Última observação usada para medir a volatilidade realizada
+    #[derive(Debug, Default, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct VolatilityObservation {
+        /// Início da janela atual
+        pub timestamp: Timestamp,
+        /// Soma de `preço × ms` na janela atual (preço em `VOLATILITY_PRICE_SCALE`)
+        pub weighted_price: u128,
+        /// TWAP (em `VOLATILITY_PRICE_SCALE`) da última janela completa
+        pub last_twap: u128,
+        /// Variação entre os TWAPs das duas últimas janelas (bps)
+        pub volatility_bps: u32,
+    }
+
     // ========================================
     // CONSTANTES DO PROTOCOLO AMM
     // ========================================
@@ -139,6 +254,24 @@ pub mod pair_contract {
         pub const PROTOCOL_FEE_SHARE: u128 = 200;  // 20% = 0.1%
         pub const REWARDS_FEE_SHARE: u128 = 200;   // 20% = 0.1%
         pub const TOTAL_FEE_SHARES: u128 = 1000;   // 100%
+
+        /// Denominador de basis points (circuit breaker e fee dinâmica)
+        pub const BPS_DENOMINATOR: u128 = 10_000;
+
+        /// Fee estática em bps (0.5% = 995/1000)
+        pub const STATIC_FEE_BPS: u16 = ((FEE_DENOMINATOR - FEE_NUMERATOR) * BPS_DENOMINATOR / FEE_DENOMINATOR) as u16;
+
+        /// Fee máxima permitida no modo dinâmico (10%)
+        pub const MAX_DYNAMIC_FEE_BPS: u16 = 1_000;
+
+        /// Escala do preço (token_1 por token_0) usado na medição de volatilidade
+        pub const VOLATILITY_PRICE_SCALE: u128 = 1_000_000_000_000;
+    }
+
+    /// Selectors das chamadas cross-contract
+    mod selectors {
+        pub const PSP22_TRANSFER: [u8; 4] = ink::selector_bytes!("PSP22::transfer");
+        pub const PSP22_BALANCE_OF: [u8; 4] = ink::selector_bytes!("PSP22::balance_of");
     }
 
     /// Storage principal do contrato otimizado para gas
@@ -160,6 +293,12 @@ pub mod pair_contract {
         
         // Reentrancy protection (frequentemente acessado)
         unlocked: bool,
+
+        // Circuit breaker (lido em todo swap)
+        circuit_breaker: CircuitBreakerState,
+
+        // Pausa de emergência empurrada pelo factory (lida em todo mint/swap)
+        paused: bool,
         
         // === CAMPOS RARAMENTE ACESSADOS (LAZY) ===
         
@@ -179,6 +318,23 @@ pub mod pair_contract {
         accumulated_protocol_fees_1: ink::storage::Lazy<Balance>,
         accumulated_rewards_fees_0: ink::storage::Lazy<Balance>,
         accumulated_rewards_fees_1: ink::storage::Lazy<Balance>,
+
+        // Fee dinâmica (configurada pelo factory; observação atualizada por janela)
+        dynamic_fee: ink::storage::Lazy<Option<DynamicFeeConfig>>,
+        volatility_observation: ink::storage::Lazy<VolatilityObservation>,
+
+        // Router que repassa o desconto de staking do trader (configurado pelo factory)
+        fee_discount_router: ink::storage::Lazy<Option<AccountId>>,
+
+        // Snapshots de LP para governança (escritos em mint/burn, lidos raramente)
+        balance_checkpoints: ink::storage::Mapping<(AccountId, u32), Checkpoint>,
+        balance_checkpoint_count: ink::storage::Mapping<AccountId, u32>,
+        supply_checkpoints: ink::storage::Mapping<u32, Checkpoint>,
+        supply_checkpoint_count: ink::storage::Lazy<u32>,
+
+        // Allowances e nonces de permit do LP token
+        allowances: ink::storage::Mapping<(AccountId, AccountId), Balance>,
+        permit_nonces: ink::storage::Mapping<AccountId, u64>,
     }
 
     /// Default implementation with safe defaults e Lazy optimization
@@ -195,6 +351,8 @@ pub mod pair_contract {
                 total_supply: 0,
                 balances: ink::storage::Mapping::default(),
                 unlocked: true,
+                circuit_breaker: CircuitBreakerState::default(),
+                paused: false,
                 
                 // Campos raramente acessados (Lazy)
                 price_0_cumulative_last: ink::storage::Lazy::new(),
@@ -206,6 +364,15 @@ pub mod pair_contract {
                 accumulated_protocol_fees_1: ink::storage::Lazy::new(),
                 accumulated_rewards_fees_0: ink::storage::Lazy::new(),
                 accumulated_rewards_fees_1: ink::storage::Lazy::new(),
+                dynamic_fee: ink::storage::Lazy::new(),
+                volatility_observation: ink::storage::Lazy::new(),
+                fee_discount_router: ink::storage::Lazy::new(),
+                balance_checkpoints: ink::storage::Mapping::default(),
+                balance_checkpoint_count: ink::storage::Mapping::default(),
+                supply_checkpoints: ink::storage::Mapping::default(),
+                supply_checkpoint_count: ink::storage::Lazy::new(),
+                allowances: ink::storage::Mapping::default(),
+                permit_nonces: ink::storage::Mapping::default(),
             }
         }
     }
@@ -231,6 +398,8 @@ pub mod pair_contract {
             instance.accumulated_protocol_fees_1.set(&0);
             instance.accumulated_rewards_fees_0.set(&0);
             instance.accumulated_rewards_fees_1.set(&0);
+            instance.dynamic_fee.set(&None);
+            instance.fee_discount_router.set(&None);
             
             instance
         }
@@ -252,33 +421,38 @@ pub mod pair_contract {
             self.unlocked = true;
         }
 
+        /// Rejeita mint/swap enquanto o factory mantiver este par pausado
+        fn ensure_not_paused(&self) -> Result<(), PairError> {
+            if self.paused {
+                return Err(PairError::Paused);
+            }
+            Ok(())
+        }
+
         /// Update reserves and cumulative prices
+        ///
+        /// Os preços cumulativos (UQ112) usam aritmética modular 2^128, como no
+        /// Uniswap V2: o overflow é esperado e consumidores devem usar a
+        /// diferença `wrapping_sub` entre duas leituras.
         fn update(&mut self, balance_0: Balance, balance_1: Balance) -> Result<(), PairError> {
+            self.snapshot_block_start();
             let block_timestamp = self.env().block_timestamp();
             let time_elapsed = block_timestamp.saturating_sub(self.block_timestamp_last);
 
             if time_elapsed > 0 && self.reserve_0 != 0 && self.reserve_1 != 0 {
-                // Overflow protection for price calculation
-                let price_0 = self.reserve_1.checked_mul(constants::UQ112)
-                    .and_then(|p| p.checked_div(self.reserve_0))
-                    .ok_or(PairError::Overflow)?;
-                let price_1 = self.reserve_0.checked_mul(constants::UQ112)
-                    .and_then(|p| p.checked_div(self.reserve_1))
-                    .ok_or(PairError::Overflow)?;
+                let price_0 = Self::uq112_price(self.reserve_0, self.reserve_1);
+                let price_1 = Self::uq112_price(self.reserve_1, self.reserve_0);
 
                 let current_price_0 = self.price_0_cumulative_last.get().unwrap_or(0);
-                let new_price_0 = current_price_0
-                    .checked_add(price_0.checked_mul(time_elapsed as u128).ok_or(PairError::Overflow)?)
-                    .ok_or(PairError::Overflow)?;
+                let new_price_0 = current_price_0.wrapping_add(price_0.wrapping_mul(u128::from(time_elapsed)));
                 self.price_0_cumulative_last.set(&new_price_0);
                 
                 let current_price_1 = self.price_1_cumulative_last.get().unwrap_or(0);
-                let new_price_1 = current_price_1
-                    .checked_add(price_1.checked_mul(time_elapsed as u128).ok_or(PairError::Overflow)?)
-                    .ok_or(PairError::Overflow)?;
+                let new_price_1 = current_price_1.wrapping_add(price_1.wrapping_mul(u128::from(time_elapsed)));
                 self.price_1_cumulative_last.set(&new_price_1);
             }
 
+            self.observe_volatility(time_elapsed, block_timestamp)?;
             self.reserve_0 = balance_0;
             self.reserve_1 = balance_1;
             self.block_timestamp_last = block_timestamp;
@@ -291,6 +465,178 @@ pub mod pair_contract {
             Ok(())
         }
 
+        /// Preço UQ112 de `reserve_out / reserve_in`, truncado a 128 bits
+        ///
+        /// O truncamento preserva a soma módulo 2^128 do acumulador.
+        fn uq112_price(reserve_in: Balance, reserve_out: Balance) -> u128 {
+            let price = U256::from(reserve_out)
+                .saturating_mul(U256::from(constants::UQ112))
+                .checked_div(U256::from(reserve_in))
+                .unwrap_or_default();
+            price.low_u128()
+        }
+
+        /// Preço token_1 por token_0 das reserves em `VOLATILITY_PRICE_SCALE`
+        fn volatility_price(reserve_0: Balance, reserve_1: Balance) -> u128 {
+            let price = U256::from(reserve_1)
+                .saturating_mul(U256::from(constants::VOLATILITY_PRICE_SCALE))
+                .checked_div(U256::from(reserve_0))
+                .unwrap_or_default();
+            u128::try_from(price).unwrap_or(u128::MAX)
+        }
+
+        /// Guarda as reserves do início do bloco na primeira atualização do bloco
+        fn snapshot_block_start(&mut self) {
+            let block = self.env().block_number();
+            if self.circuit_breaker.block != block {
+                self.circuit_breaker.block = block;
+                self.circuit_breaker.start_reserve_0 = self.reserve_0;
+                self.circuit_breaker.start_reserve_1 = self.reserve_1;
+            }
+        }
+
+        /// Movimento relativo do preço (token_1 por token_0) em bps
+        ///
+        /// `|r1'·r0 − r1·r0'| / (r1·r0')`, ou seja `|p' − p| / p`.
+        fn price_move_bps(
+            start_reserve_0: Balance,
+            start_reserve_1: Balance,
+            reserve_0: Balance,
+            reserve_1: Balance,
+        ) -> u128 {
+            let before = U256::from(start_reserve_1).saturating_mul(U256::from(reserve_0));
+            if before.is_zero() {
+                return 0;
+            }
+            let after = U256::from(reserve_1).saturating_mul(U256::from(start_reserve_0));
+            let diff = if after > before { after.saturating_sub(before) } else { before.saturating_sub(after) };
+            let bps = diff
+                .saturating_mul(U256::from(constants::BPS_DENOMINATOR))
+                .checked_div(before)
+                .unwrap_or_default();
+            u128::try_from(bps).unwrap_or(u128::MAX)
+        }
+
+        /// Rejeita reserves pós-swap cujo preço se moveu além do threshold
+        /// desde o início do bloco
+        ///
+        /// Chamado antes de `update`, de modo que o swap ofensor não altera o
+        /// estado que integradores leem via `get_reserves`.
+        fn ensure_within_circuit_breaker(&self, balance_0: Balance, balance_1: Balance) -> Result<(), PairError> {
+            let state = self.circuit_breaker_state();
+            if state.threshold_bps == 0 {
+                return Ok(());
+            }
+            let price_move_bps = Self::price_move_bps(
+                state.start_reserve_0,
+                state.start_reserve_1,
+                balance_0,
+                balance_1,
+            );
+            if price_move_bps > u128::from(state.threshold_bps) {
+                return Err(PairError::CircuitBreaker);
+            }
+            Ok(())
+        }
+
+        /// Acumula o preço das reserves atuais e fecha a janela de volatilidade
+        /// quando `window` ms se passaram
+        ///
+        /// Chamado por `update` antes de gravar as novas reserves. A medição usa
+        /// as reserves diretamente (não os acumuladores UQ112), então não
+        /// depende do tamanho das reserves. A volatilidade realizada é a
+        /// variação relativa entre o TWAP da janela recém-fechada e o da anterior.
+        fn observe_volatility(&mut self, time_elapsed: Timestamp, now: Timestamp) -> Result<(), PairError> {
+            let Some(config) = self.dynamic_fee.get().flatten() else {
+                return Ok(());
+            };
+            let mut observation = self.volatility_observation.get().unwrap_or_default();
+            if self.reserve_0 != 0 && self.reserve_1 != 0 {
+                let price = Self::volatility_price(self.reserve_0, self.reserve_1);
+                observation.weighted_price = observation
+                    .weighted_price
+                    .saturating_add(price.saturating_mul(u128::from(time_elapsed)));
+            }
+            let elapsed = now.saturating_sub(observation.timestamp);
+            if elapsed < config.window {
+                self.volatility_observation.set(&observation);
+                return Ok(());
+            }
+
+            let twap = observation
+                .weighted_price
+                .checked_div(u128::from(elapsed))
+                .ok_or(PairError::Overflow)?;
+            // Janela sem preço acumulado (pool vazio) não gera medição
+            if twap > 0 {
+                if observation.last_twap > 0 {
+                    let diff = twap.abs_diff(observation.last_twap);
+                    let volatility = U256::from(diff)
+                        .saturating_mul(U256::from(constants::BPS_DENOMINATOR))
+                        .checked_div(U256::from(observation.last_twap))
+                        .unwrap_or_default();
+                    observation.volatility_bps = u32::try_from(volatility).unwrap_or(u32::MAX);
+                }
+                observation.last_twap = twap;
+            }
+            observation.timestamp = now;
+            observation.weighted_price = 0;
+            self.volatility_observation.set(&observation);
+            Ok(())
+        }
+
+        /// Interpola a fee linearmente entre mínimo e máximo pela volatilidade
+        fn fee_for_volatility(config: &DynamicFeeConfig, volatility_bps: u32) -> u16 {
+            let capped = volatility_bps.min(config.volatility_cap_bps);
+            let range = u32::from(config.max_fee_bps.saturating_sub(config.min_fee_bps));
+            let extra = u64::from(range)
+                .saturating_mul(u64::from(capped))
+                .checked_div(u64::from(config.volatility_cap_bps))
+                .unwrap_or(0);
+            config
+                .min_fee_bps
+                .saturating_add(u16::try_from(extra).unwrap_or(u16::MAX))
+                .min(config.max_fee_bps)
+        }
+
+        /// Grava o balance de LP atual de `account` no checkpoint do bloco corrente
+        fn write_balance_checkpoint(&mut self, account: AccountId) {
+            let checkpoint = Checkpoint {
+                block: self.env().block_number(),
+                value: self.balances.get(account).unwrap_or(0),
+            };
+            let count = self.balance_checkpoint_count.get(account).unwrap_or(0);
+            let last_block = count
+                .checked_sub(1)
+                .and_then(|index| self.balance_checkpoints.get((account, index)))
+                .map(|c| c.block);
+
+            let (index, new_count) = checkpoints::slot(count, last_block, checkpoint.block);
+            self.balance_checkpoints.insert((account, index), &checkpoint);
+            if new_count != count {
+                self.balance_checkpoint_count.insert(account, &new_count);
+            }
+        }
+
+        /// Grava o total supply de LP atual no checkpoint do bloco corrente
+        fn write_supply_checkpoint(&mut self) {
+            let checkpoint = Checkpoint {
+                block: self.env().block_number(),
+                value: self.total_supply,
+            };
+            let count = self.supply_checkpoint_count.get().unwrap_or(0);
+            let last_block = count
+                .checked_sub(1)
+                .and_then(|index| self.supply_checkpoints.get(index))
+                .map(|c| c.block);
+
+            let (index, new_count) = checkpoints::slot(count, last_block, checkpoint.block);
+            self.supply_checkpoints.insert(index, &checkpoint);
+            if new_count != count {
+                self.supply_checkpoint_count.set(&new_count);
+            }
+        }
+
         /// Calculate square root using Babylonian method
         fn sqrt(y: u128) -> u128 {
             if y > 3 {
@@ -348,9 +694,165 @@ pub mod pair_contract {
             self.price_1_cumulative_last.get().unwrap_or(0)
         }
 
+        /// Balance de LP de `account` ao final do bloco `block`
+        ///
+        /// Para o bloco atual o valor ainda pode mudar; a governança deve
+        /// consultar um bloco já finalizado (ex.: o anterior à proposta).
+        #[ink(message)]
+        pub fn balance_of_at(&self, account: AccountId, block: BlockNumber) -> Balance {
+            let count = self.balance_checkpoint_count.get(account).unwrap_or(0);
+            checkpoints::lookup(count, block, |index| self.balance_checkpoints.get((account, index)))
+        }
+
+        /// Total supply de LP ao final do bloco `block`
+        #[ink(message)]
+        pub fn total_supply_at(&self, block: BlockNumber) -> Balance {
+            let count = self.supply_checkpoint_count.get().unwrap_or(0);
+            checkpoints::lookup(count, block, |index| self.supply_checkpoints.get(index))
+        }
+
+        /// Menor total supply de LP ao final de cada bloco desde `block`
+        ///
+        /// Mede presença contínua de liquidez (ex.: reembolso da fee de criação
+        /// no factory); o custo cresce com o número de blocos com mint/burn
+        /// desde `block`.
+        #[ink(message)]
+        pub fn min_total_supply_since(&self, block: BlockNumber) -> Balance {
+            let count = self.supply_checkpoint_count.get().unwrap_or(0);
+            checkpoints::min_since(count, block, |index| self.supply_checkpoints.get(index))
+        }
+
+        /// Retorna se mint/swap estão pausados pelo factory neste par
+        #[ink(message)]
+        pub fn is_paused(&self) -> bool {
+            self.paused
+        }
+
+        /// Pausa ou libera mint/swap (apenas factory)
+        ///
+        /// O factory empurra aqui o estado de `FactoryContract::is_pair_paused`
+        /// (pausa global ou override do par). Burn nunca é pausado.
+        #[ink(message)]
+        pub fn set_paused(&mut self, paused: bool) -> Result<(), PairError> {
+            if self.env().caller() != self.factory {
+                return Err(PairError::Unauthorized);
+            }
+            self.paused = paused;
+            self.env().emit_event(PauseChanged { paused });
+            Ok(())
+        }
+
+        /// Estado atual do circuit breaker
+        ///
+        /// Em um bloco ainda sem atualização, as reserves de início do bloco
+        /// são as reserves atuais.
+        #[ink(message)]
+        pub fn circuit_breaker_state(&self) -> CircuitBreakerState {
+            let mut state = self.circuit_breaker;
+            let block = self.env().block_number();
+            if state.block != block {
+                state.block = block;
+                state.start_reserve_0 = self.reserve_0;
+                state.start_reserve_1 = self.reserve_1;
+            }
+            state
+        }
+
+        /// Define o movimento máximo de preço por bloco (apenas factory)
+        ///
+        /// # Parâmetros
+        /// * `threshold_bps` - Movimento máximo em basis points (0 desabilita)
+        #[ink(message)]
+        pub fn set_circuit_breaker_threshold(&mut self, threshold_bps: u32) -> Result<(), PairError> {
+            if self.env().caller() != self.factory {
+                return Err(PairError::Unauthorized);
+            }
+            let old_threshold_bps = self.circuit_breaker.threshold_bps;
+            self.circuit_breaker.threshold_bps = threshold_bps;
+            self.env().emit_event(CircuitBreakerThresholdChanged {
+                old_threshold_bps,
+                new_threshold_bps: threshold_bps,
+            });
+            Ok(())
+        }
+
+        /// Define o router autorizado a chamar `swap_with_fee_discount` (apenas factory)
+        #[ink(message)]
+        pub fn set_fee_discount_router(&mut self, router: Option<AccountId>) -> Result<(), PairError> {
+            if self.env().caller() != self.factory {
+                return Err(PairError::Unauthorized);
+            }
+            self.fee_discount_router.set(&router);
+            self.env().emit_event(FeeDiscountRouterChanged { router });
+            Ok(())
+        }
+
+        /// Router autorizado a aplicar o desconto de staking (`None` = nenhum)
+        #[ink(message)]
+        pub fn fee_discount_router(&self) -> Option<AccountId> {
+            self.fee_discount_router.get().flatten()
+        }
+
+        /// Fee de swap atual em bps
+        ///
+        /// Estática (`STATIC_FEE_BPS`) ou, no modo dinâmico, interpolada pela
+        /// volatilidade realizada da última janela.
+        #[ink(message)]
+        pub fn current_fee_bps(&self) -> u16 {
+            match self.dynamic_fee.get().flatten() {
+                Some(config) => Self::fee_for_volatility(&config, self.realized_volatility_bps()),
+                None => constants::STATIC_FEE_BPS,
+            }
+        }
+
+        /// Configuração do modo de fee dinâmica (`None` = fee estática)
+        #[ink(message)]
+        pub fn dynamic_fee_config(&self) -> Option<DynamicFeeConfig> {
+            self.dynamic_fee.get().flatten()
+        }
+
+        /// Volatilidade realizada da última janela completa (bps)
+        #[ink(message)]
+        pub fn realized_volatility_bps(&self) -> u32 {
+            self.volatility_observation.get().unwrap_or_default().volatility_bps
+        }
+
+        /// Ativa, altera ou desativa (`None`) a fee dinâmica (apenas factory)
+        ///
+        /// # Validações
+        /// * `min_fee_bps <= max_fee_bps <= MAX_DYNAMIC_FEE_BPS`
+        /// * `window` e `volatility_cap_bps` maiores que zero
+        #[ink(message)]
+        pub fn set_dynamic_fee(&mut self, config: Option<DynamicFeeConfig>) -> Result<(), PairError> {
+            if self.env().caller() != self.factory {
+                return Err(PairError::Unauthorized);
+            }
+            if let Some(config) = config {
+                if config.min_fee_bps > config.max_fee_bps
+                    || config.max_fee_bps > constants::MAX_DYNAMIC_FEE_BPS
+                    || config.window == 0
+                    || config.volatility_cap_bps == 0
+                {
+                    return Err(PairError::InvalidFeeConfig);
+                }
+            }
+
+            // Reinicia a medição a partir do estado atual
+            self.dynamic_fee.set(&config);
+            self.volatility_observation.set(&VolatilityObservation {
+                timestamp: self.block_timestamp_last,
+                weighted_price: 0,
+                last_twap: 0,
+                volatility_bps: 0,
+            });
+            self.env().emit_event(DynamicFeeConfigChanged { config });
+            Ok(())
+        }
+
         /// Mint LP tokens (simplified version for TDD)
         #[ink(message)]
         pub fn mint(&mut self, to: AccountId) -> Result<Balance, PairError> {
+            self.ensure_not_paused()?;
             self.lock()?;
             
             // Use closure para garantir unlock em todos os caminhos
@@ -395,6 +897,7 @@ pub mod pair_contract {
             if total_supply == 0 {
                 self.total_supply = self.total_supply.checked_add(constants::MINIMUM_LIQUIDITY).ok_or(PairError::Overflow)?;
                 self.balances.insert(AccountId::from([0u8; 32]), &constants::MINIMUM_LIQUIDITY);
+                self.write_balance_checkpoint(AccountId::from([0u8; 32]));
             }
             
             // Mint LP tokens to user
@@ -402,6 +905,8 @@ pub mod pair_contract {
             let balance = self.balances.get(to).unwrap_or(0);
             let new_balance = balance.checked_add(liquidity).ok_or(PairError::Overflow)?;
             self.balances.insert(to, &new_balance);
+            self.write_balance_checkpoint(to);
+            self.write_supply_checkpoint();
             self.update(balance_0, balance_1)?;
             
             self.env().emit_event(Mint {
@@ -460,6 +965,8 @@ pub mod pair_contract {
                 .ok_or(PairError::InsufficientLiquidityBurned)?;
             let new_contract_balance = contract_balance.checked_sub(liquidity).ok_or(PairError::InsufficientLiquidityBurned)?;
             self.balances.insert(self.env().account_id(), &new_contract_balance);
+            self.write_balance_checkpoint(self.env().account_id());
+            self.write_supply_checkpoint();
             let new_balance_0 = balance_0.checked_sub(amount_0).ok_or(PairError::InsufficientLiquidity)?;
             let new_balance_1 = balance_1.checked_sub(amount_1).ok_or(PairError::InsufficientLiquidity)?;
             self.update(new_balance_0, new_balance_1)?;
@@ -482,34 +989,83 @@ pub mod pair_contract {
             amount_1_out: Balance,
             to: AccountId,
         ) -> Result<(), PairError> {
+            self.swap_internal(amount_0_out, amount_1_out, to, self.current_fee_bps())
+        }
+
+        /// Swap com o desconto de staking do trader aplicado à fee atual
+        ///
+        /// Apenas o router configurado pelo factory pode chamar; ele resolve o
+        /// tier do trader e informa o desconto em bps da fee (10000 = isenção).
+        #[ink(message)]
+        pub fn swap_with_fee_discount(
+            &mut self,
+            amount_0_out: Balance,
+            amount_1_out: Balance,
+            to: AccountId,
+            discount_bps: u16,
+        ) -> Result<(), PairError> {
+            if self.fee_discount_router() != Some(self.env().caller()) {
+                return Err(PairError::Unauthorized);
+            }
+            let fee_bps = Self::discounted_fee_bps(self.current_fee_bps(), discount_bps)?;
+            self.swap_internal(amount_0_out, amount_1_out, to, fee_bps)
+        }
+
+        /// Fee com `discount_bps` (bps da fee) descontado
+        fn discounted_fee_bps(fee_bps: u16, discount_bps: u16) -> Result<u16, PairError> {
+            let remaining_bps = constants::BPS_DENOMINATOR
+                .checked_sub(u128::from(discount_bps))
+                .ok_or(PairError::InvalidFeeConfig)?;
+            let fee_bps = u128::from(fee_bps)
+                .saturating_mul(remaining_bps)
+                .checked_div(constants::BPS_DENOMINATOR)
+                .unwrap_or(0);
+            u16::try_from(fee_bps).map_err(|_| PairError::Overflow)
+        }
+
+        /// Lógica comum de swap cobrando `fee_bps` sobre o input medido
+        fn swap_internal(
+            &mut self,
+            amount_0_out: Balance,
+            amount_1_out: Balance,
+            to: AccountId,
+            fee_bps: u16,
+        ) -> Result<(), PairError> {
+            self.ensure_not_paused()?;
             self.lock()?;
             
             if amount_0_out == 0 && amount_1_out == 0 {
                 self.unlock();
                 return Err(PairError::InsufficientOutputAmount);
             }
-            
+
             if amount_0_out >= self.reserve_0 || amount_1_out >= self.reserve_1 {
                 self.unlock();
                 return Err(PairError::InsufficientLiquidity);
             }
             
-            // Simplified swap logic for TDD
-            let balance_0 = self.reserve_0.checked_sub(amount_0_out).ok_or(PairError::InsufficientLiquidity)?;
-            let balance_1 = self.reserve_1.checked_sub(amount_1_out).ok_or(PairError::InsufficientLiquidity)?;
-            
-            // Check K invariant with fee adjustment
-            let balance_0_adjusted = balance_0.checked_mul(constants::FEE_DENOMINATOR).ok_or(PairError::Overflow)?;
-            let balance_1_adjusted = balance_1.checked_mul(constants::FEE_DENOMINATOR).ok_or(PairError::Overflow)?;
-            
-            let k_new = balance_0_adjusted.checked_mul(balance_1_adjusted).ok_or(PairError::Overflow)?;
-            let reserve_0_adjusted = self.reserve_0.checked_mul(constants::FEE_DENOMINATOR).ok_or(PairError::Overflow)?;
-            let reserve_1_adjusted = self.reserve_1.checked_mul(constants::FEE_DENOMINATOR).ok_or(PairError::Overflow)?;
-            let k_old = reserve_0_adjusted.checked_mul(reserve_1_adjusted).ok_or(PairError::Overflow)?;
-            
-            if k_new < k_old {
+            // Saldos após enviar o output; o input é o que excede as reserves
+            let (balance_0, balance_1) = match self.swap_balances(amount_0_out, amount_1_out, to) {
+                Ok(balances) => balances,
+                Err(error) => {
+                    self.unlock();
+                    return Err(error);
+                }
+            };
+
+            // Check K invariant with fee adjustment (fee atual, estática ou dinâmica)
+            let (amount_0_in, amount_1_in) =
+                match self.check_swap_invariant(balance_0, balance_1, amount_0_out, amount_1_out, fee_bps) {
+                    Ok(amounts_in) => amounts_in,
+                    Err(error) => {
+                        self.unlock();
+                        return Err(error);
+                    }
+                };
+
+            if let Err(error) = self.ensure_within_circuit_breaker(balance_0, balance_1) {
                 self.unlock();
-                return Err(PairError::KValueDecreased);
+                return Err(error);
             }
             
             self.update(balance_0, balance_1)?;
@@ -517,8 +1073,8 @@ pub mod pair_contract {
             self.env().emit_event(Swap {
                 sender: self.env().caller(),
                 to,
-                amount_0_in: 0, // Simplified
-                amount_1_in: 0, // Simplified
+                amount_0_in,
+                amount_1_in,
                 amount_0_out,
                 amount_1_out,
             });
@@ -527,6 +1083,97 @@ pub mod pair_contract {
             Ok(())
         }
 
+        /// Envia o output a `to` e lê os saldos de tokens do par
+        ///
+        /// Sem tokens implantados (testes off-chain) os saldos são as reserves
+        /// menos o output, como na versão simplificada.
+        fn swap_balances(
+            &self,
+            amount_0_out: Balance,
+            amount_1_out: Balance,
+            to: AccountId,
+        ) -> Result<(Balance, Balance), PairError> {
+            if !self.env().is_contract(&self.token_0) || !self.env().is_contract(&self.token_1) {
+                let balance_0 = self.reserve_0.checked_sub(amount_0_out).ok_or(PairError::InsufficientLiquidity)?;
+                let balance_1 = self.reserve_1.checked_sub(amount_1_out).ok_or(PairError::InsufficientLiquidity)?;
+                return Ok((balance_0, balance_1));
+            }
+
+            if amount_0_out > 0 {
+                self.transfer_token(self.token_0, to, amount_0_out)?;
+            }
+            if amount_1_out > 0 {
+                self.transfer_token(self.token_1, to, amount_1_out)?;
+            }
+            Ok((self.token_balance(self.token_0)?, self.token_balance(self.token_1)?))
+        }
+
+        /// Calcula os inputs do swap e verifica o invariante K com a fee `fee_bps`
+        ///
+        /// # Retorna
+        /// - `(amount_0_in, amount_1_in)` medidos contra as reserves
+        fn check_swap_invariant(
+            &self,
+            balance_0: Balance,
+            balance_1: Balance,
+            amount_0_out: Balance,
+            amount_1_out: Balance,
+            fee_bps: u16,
+        ) -> Result<(Balance, Balance), PairError> {
+            let amount_0_in = balance_0.saturating_sub(self.reserve_0.saturating_sub(amount_0_out));
+            let amount_1_in = balance_1.saturating_sub(self.reserve_1.saturating_sub(amount_1_out));
+            if amount_0_in == 0 && amount_1_in == 0 {
+                return Err(PairError::InsufficientInputAmount);
+            }
+
+            let fee_bps = u128::from(fee_bps);
+            let balance_0_adjusted = balance_0.checked_mul(constants::BPS_DENOMINATOR)
+                .and_then(|b| b.checked_sub(amount_0_in.checked_mul(fee_bps)?))
+                .ok_or(PairError::Overflow)?;
+            let balance_1_adjusted = balance_1.checked_mul(constants::BPS_DENOMINATOR)
+                .and_then(|b| b.checked_sub(amount_1_in.checked_mul(fee_bps)?))
+                .ok_or(PairError::Overflow)?;
+
+            let k_new = U256::from(balance_0_adjusted).saturating_mul(U256::from(balance_1_adjusted));
+            let k_old = U256::from(self.reserve_0)
+                .saturating_mul(U256::from(self.reserve_1))
+                .saturating_mul(U256::from(constants::BPS_DENOMINATOR.saturating_mul(constants::BPS_DENOMINATOR)));
+            if k_new < k_old {
+                return Err(PairError::KValueDecreased);
+            }
+            Ok((amount_0_in, amount_1_in))
+        }
+
+        /// Transfere tokens PSP22 mantidos pelo par
+        fn transfer_token(&self, token: AccountId, to: AccountId, amount: Balance) -> Result<(), PairError> {
+            build_call::<DefaultEnvironment>()
+                .call(token)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selectors::PSP22_TRANSFER))
+                        .push_arg(to)
+                        .push_arg(amount)
+                        .push_arg(Vec::<u8>::new()),
+                )
+                .returns::<Result<(), PSP22Error>>()
+                .try_invoke()
+                .map_err(|_| PairError::TransferFailed)?
+                .map_err(|_| PairError::TransferFailed)?
+                .map_err(PairError::PSP22)
+        }
+
+        /// Saldo PSP22 do par em `token`
+        fn token_balance(&self, token: AccountId) -> Result<Balance, PairError> {
+            build_call::<DefaultEnvironment>()
+                .call(token)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selectors::PSP22_BALANCE_OF)).push_arg(self.env().account_id()),
+                )
+                .returns::<Balance>()
+                .try_invoke()
+                .map_err(|_| PairError::TransferFailed)?
+                .map_err(|_| PairError::TransferFailed)
+        }
+
         /// Sync reserves with token balances
         #[ink(message)]
         pub fn sync(&mut self) -> Result<(), PairError> {
@@ -535,6 +1182,203 @@ pub mod pair_contract {
             let balance_1 = 1000; // Placeholder
             self.update(balance_0, balance_1)
         }
+
+        // ========================================
+        // PERMIT DO LP TOKEN (APROVAÇÃO POR ASSINATURA)
+        // ========================================
+
+        /// Próximo nonce de permit de `owner`
+        #[ink(message)]
+        pub fn nonces(&self, owner: AccountId) -> u64 {
+            self.permit_nonces.get(owner).unwrap_or(0)
+        }
+
+        /// Separador de domínio dos permits (tag de versão + rede + endereço deste par)
+        #[ink(message)]
+        pub fn domain_separator(&self) -> Hash {
+            permit::domain_separator(self.env().account_id())
+        }
+
+        /// Payload assinado de um permit
+        #[ink(message)]
+        pub fn permit_digest(
+            &self,
+            owner: AccountId,
+            spender: AccountId,
+            value: Balance,
+            nonce: u64,
+            deadline: Timestamp,
+        ) -> [u8; 32] {
+            permit::permit_digest(
+                self.domain_separator(),
+                owner,
+                spender,
+                value,
+                nonce,
+                deadline,
+            )
+        }
+
+        /// Aprova `spender` a gastar LP de `owner` a partir de uma assinatura off-chain
+        ///
+        /// Permite ao router remover liquidez sem um `approve` prévio. `deadline` em ms.
+        #[ink(message)]
+        pub fn permit(
+            &mut self,
+            owner: AccountId,
+            spender: AccountId,
+            value: Balance,
+            deadline: Timestamp,
+            signature: PermitSignature,
+        ) -> Result<(), PairError> {
+            if self.env().block_timestamp() > deadline {
+                return Err(PairError::PermitExpired);
+            }
+
+            let nonce = self.nonces(owner);
+            let digest = self.permit_digest(owner, spender, value, nonce, deadline);
+            if !permit::verify_permit_signature(&owner, &digest, &signature) {
+                return Err(PairError::InvalidSignature);
+            }
+
+            self.permit_nonces.insert(owner, &nonce.saturating_add(1));
+            self.approve_internal(owner, spender, value);
+            Ok(())
+        }
+
+        /// Grava a allowance de LP e emite `Approval`
+        fn approve_internal(&mut self, owner: AccountId, spender: AccountId, value: Balance) {
+            if value == 0 {
+                self.allowances.remove((owner, spender));
+            } else {
+                self.allowances.insert((owner, spender), &value);
+            }
+
+            self.env().emit_event(Approval {
+                owner,
+                spender,
+                value,
+            });
+        }
+
+        /// Transferência interna de LP (atualiza checkpoints)
+        ///
+        /// Os checkpoints de `from` e `to` custam até quatro escritas de storage
+        /// por transferência; no mesmo bloco apenas sobrescrevem o checkpoint.
+        fn transfer_internal(&mut self, from: AccountId, to: AccountId, value: Balance) -> Result<(), PSP22Error> {
+            if from == to || value == 0 {
+                return Ok(());
+            }
+
+            let from_balance = self.balances.get(from).unwrap_or(0);
+            let new_from_balance = from_balance
+                .checked_sub(value)
+                .ok_or(PSP22Error::InsufficientBalance)?;
+            let new_to_balance = self.balances.get(to).unwrap_or(0).saturating_add(value);
+            self.balances.insert(from, &new_from_balance);
+            self.balances.insert(to, &new_to_balance);
+            self.write_balance_checkpoint(from);
+            self.write_balance_checkpoint(to);
+
+            self.env().emit_event(Transfer {
+                from: Some(from),
+                to: Some(to),
+                value,
+            });
+            Ok(())
+        }
+    }
+
+    // ========================================
+    // LP TOKEN (PSP22)
+    // ========================================
+
+    impl PSP22 for PairContract {
+        /// Total supply de LP
+        #[ink(message)]
+        fn total_supply(&self) -> Balance {
+            self.total_supply
+        }
+
+        /// Balance de LP de `owner`
+        #[ink(message)]
+        fn balance_of(&self, owner: AccountId) -> Balance {
+            self.balances.get(owner).unwrap_or(0)
+        }
+
+        /// Allowance de LP entre owner e spender
+        #[ink(message)]
+        fn allowance(&self, owner: AccountId, spender: AccountId) -> Balance {
+            self.allowances.get((owner, spender)).unwrap_or(0)
+        }
+
+        /// Transfere LP do caller para `to`
+        #[ink(message)]
+        fn transfer(&mut self, to: AccountId, value: Balance, _data: Vec<u8>) -> Result<(), PSP22Error> {
+            let from = self.env().caller();
+            self.transfer_internal(from, to, value)
+        }
+
+        /// Transfere LP de `from` para `to` consumindo a allowance do caller
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let spender = self.env().caller();
+            if from == to || value == 0 {
+                return Ok(());
+            }
+            let new_allowance = self
+                .allowance(from, spender)
+                .checked_sub(value)
+                .ok_or(PSP22Error::InsufficientAllowance)?;
+            if self.balance_of(from) < value {
+                return Err(PSP22Error::InsufficientBalance);
+            }
+            self.approve_internal(from, spender, new_allowance);
+            self.transfer_internal(from, to, value)
+        }
+
+        /// Aprova `spender` a gastar LP do caller
+        #[ink(message)]
+        fn approve(&mut self, spender: AccountId, value: Balance) -> Result<(), PSP22Error> {
+            let owner = self.env().caller();
+            if owner != spender {
+                self.approve_internal(owner, spender, value);
+            }
+            Ok(())
+        }
+
+        /// Aumenta a allowance de LP de `spender`
+        #[ink(message)]
+        fn increase_allowance(&mut self, spender: AccountId, delta_value: Balance) -> Result<(), PSP22Error> {
+            let owner = self.env().caller();
+            if owner == spender || delta_value == 0 {
+                return Ok(());
+            }
+            let new_allowance = self.allowance(owner, spender).saturating_add(delta_value);
+            self.approve_internal(owner, spender, new_allowance);
+            Ok(())
+        }
+
+        /// Reduz a allowance de LP de `spender`
+        #[ink(message)]
+        fn decrease_allowance(&mut self, spender: AccountId, delta_value: Balance) -> Result<(), PSP22Error> {
+            let owner = self.env().caller();
+            if owner == spender || delta_value == 0 {
+                return Ok(());
+            }
+            let new_allowance = self
+                .allowance(owner, spender)
+                .checked_sub(delta_value)
+                .ok_or(PSP22Error::InsufficientAllowance)?;
+            self.approve_internal(owner, spender, new_allowance);
+            Ok(())
+        }
     }
 
     // ========================================
@@ -740,5 +1584,394 @@ pub mod pair_contract {
             // Total supply should be minimum + user liquidity
             assert!(pair.total_supply >= constants::MINIMUM_LIQUIDITY);
         }
+
+        #[ink::test]
+        fn test_lp_checkpoints_track_mint_and_burn() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            let mut pair = PairContract::new(accounts.bob, accounts.charlie, accounts.django);
+
+            // Bloco 0: primeiro mint
+            let liquidity = pair.mint(accounts.alice).unwrap();
+            let supply_after_mint = pair.total_supply;
+
+            // Bloco 2: LP devolvido ao par e queimado
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            let pair_account = ink::env::test::callee::<ink::env::DefaultEnvironment>();
+            pair.balances.insert(pair_account, &liquidity);
+            assert!(pair.burn(accounts.alice).is_ok());
+
+            // GREEN: Histórico de supply
+            assert_eq!(pair.total_supply_at(0), supply_after_mint);
+            assert_eq!(pair.total_supply_at(1), supply_after_mint);
+            assert_eq!(pair.total_supply_at(2), constants::MINIMUM_LIQUIDITY);
+
+            // GREEN: Histórico de balances (MINIMUM_LIQUIDITY travado no endereço zero)
+            assert_eq!(pair.balance_of_at(accounts.alice, 0), liquidity);
+            assert_eq!(pair.balance_of_at(AccountId::from([0u8; 32]), 5), constants::MINIMUM_LIQUIDITY);
+            assert_eq!(pair.balance_of_at(pair_account, 2), 0);
+            assert_eq!(pair.balance_of_at(accounts.eve, 2), 0);
+        }
+
+        #[ink::test]
+        fn test_min_total_supply_since_requires_continuous_presence() {
+            // GREEN: Supply do par ao longo de mint e burn
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            let mut pair = PairContract::new(accounts.bob, accounts.charlie, accounts.django);
+            assert_eq!(pair.min_total_supply_since(0), 0);
+            let liquidity = pair.mint(accounts.alice).unwrap();
+            assert_eq!(pair.min_total_supply_since(0), pair.total_supply);
+            ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
+            let pair_account = ink::env::test::callee::<ink::env::DefaultEnvironment>();
+            pair.balances.insert(pair_account, &liquidity);
+            pair.burn(accounts.alice).unwrap();
+            assert_eq!(pair.min_total_supply_since(0), constants::MINIMUM_LIQUIDITY);
+        }
+
+        #[ink::test]
+        fn test_lp_permit_with_sr25519_and_transfer_from() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            let mut pair = PairContract::new(accounts.bob, accounts.charlie, accounts.django);
+            let keypair = schnorrkel::MiniSecretKey::from_bytes(&[7u8; 32])
+                .unwrap()
+                .expand_to_keypair(schnorrkel::ExpansionMode::Ed25519);
+            let owner = AccountId::from(keypair.public.to_bytes());
+            let liquidity = pair.mint(owner).unwrap();
+
+            let digest = pair.permit_digest(owner, accounts.eve, liquidity, 0, 1_000);
+            let signature = PermitSignature::Sr25519(keypair.sign_simple(b"substrate", &digest).to_bytes());
+
+            // GREEN: Permit aprova o router (eve) sem transação do owner
+            assert!(pair.permit(owner, accounts.eve, liquidity, 1_000, signature.clone()).is_ok());
+            assert_eq!(pair.allowance(owner, accounts.eve), liquidity);
+            assert_eq!(pair.nonces(owner), 1);
+
+            // RED: Replay com nonce consumido
+            assert_eq!(pair.permit(owner, accounts.eve, liquidity, 1_000, signature), Err(PairError::InvalidSignature));
+
+            // GREEN: Spender move o LP para o par (fluxo de remove_liquidity)
+            set_sender(accounts.eve);
+            let pair_account = test::callee::<ink::env::DefaultEnvironment>();
+            assert!(pair.transfer_from(owner, pair_account, liquidity, Vec::new()).is_ok());
+            assert_eq!(pair.balance_of(pair_account), liquidity);
+            assert_eq!(pair.allowance(owner, accounts.eve), 0);
+
+            // RED: Allowance esgotada
+            assert_eq!(
+                pair.transfer_from(owner, accounts.eve, 1, Vec::new()),
+                Err(PSP22Error::InsufficientAllowance)
+            );
+
+            // RED: transfer_from sem allowance falha mesmo com caller == from
+            set_sender(pair_account);
+            assert_eq!(
+                pair.transfer_from(pair_account, accounts.eve, 1, Vec::new()),
+                Err(PSP22Error::InsufficientAllowance)
+            );
+            assert_eq!(pair.balance_of(pair_account), liquidity);
+        }
+
+        #[ink::test]
+        fn test_lp_permit_with_ecdsa_and_expiry() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            let mut pair = PairContract::new(accounts.bob, accounts.charlie, accounts.django);
+            let secret = secp256k1::SecretKey::from_slice(&[9u8; 32]).unwrap();
+            let public = secp256k1::PublicKey::from_secret_key(secp256k1::SECP256K1, &secret);
+            let mut owner_bytes = [0u8; 32];
+            ink::env::hash_bytes::<ink::env::hash::Blake2x256>(&public.serialize(), &mut owner_bytes);
+            let owner = AccountId::from(owner_bytes);
+
+            let digest = pair.permit_digest(owner, accounts.eve, 500, 0, 1_000);
+            let (recovery_id, compact) = secp256k1::SECP256K1
+                .sign_ecdsa_recoverable(&secp256k1::Message::from_digest(digest), &secret)
+                .serialize_compact();
+            let mut signature = [0u8; 65];
+            signature[..64].copy_from_slice(&compact);
+            signature[64] = recovery_id.to_i32() as u8;
+
+            // RED: Deadline expirado
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_001);
+            assert_eq!(
+                pair.permit(owner, accounts.eve, 500, 1_000, PermitSignature::Ecdsa(signature)),
+                Err(PairError::PermitExpired)
+            );
+
+            // RED: Assinatura válida para outro valor
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            assert_eq!(
+                pair.permit(owner, accounts.eve, 501, 1_000, PermitSignature::Ecdsa(signature)),
+                Err(PairError::InvalidSignature)
+            );
+
+            // GREEN: Conta derivada da chave ECDSA
+            assert!(pair.permit(owner, accounts.eve, 500, 1_000, PermitSignature::Ecdsa(signature)).is_ok());
+            assert_eq!(pair.allowance(owner, accounts.eve), 500);
+        }
+
+        #[ink::test]
+        fn test_circuit_breaker_threshold_only_by_factory() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            let mut pair = PairContract::new(accounts.bob, accounts.charlie, accounts.django);
+            assert_eq!(pair.circuit_breaker_state().threshold_bps, 0);
+
+            // RED: Apenas o factory define o threshold
+            let result = pair.set_circuit_breaker_threshold(500);
+            assert_eq!(result, Err(PairError::Unauthorized));
+
+            // GREEN: Factory define o threshold
+            set_sender(accounts.bob);
+            assert!(pair.set_circuit_breaker_threshold(500).is_ok());
+            assert_eq!(pair.circuit_breaker_state().threshold_bps, 500);
+        }
+
+        #[ink::test]
+        fn test_price_move_bps() {
+            // GREEN: Sem movimento
+            assert_eq!(PairContract::price_move_bps(1000, 1000, 2000, 2000), 0);
+            // GREEN: Preço dobra (+100%) ou cai pela metade (-50%)
+            assert_eq!(PairContract::price_move_bps(1000, 1000, 1000, 2000), 10_000);
+            assert_eq!(PairContract::price_move_bps(1000, 1000, 2000, 1000), 5_000);
+            // GREEN: Pool vazio no início do bloco não é medido
+            assert_eq!(PairContract::price_move_bps(0, 0, 1000, 1000), 0);
+        }
+
+        #[ink::test]
+        fn test_circuit_breaker_rejects_swap_before_commit() {
+            let accounts = default_accounts();
+            set_sender(accounts.bob);
+
+            let mut pair = PairContract::new(accounts.bob, accounts.charlie, accounts.django);
+            pair.set_circuit_breaker_threshold(500).unwrap();
+            pair.update(1000, 1000).unwrap();
+            test::advance_block::<ink::env::DefaultEnvironment>();
+
+            // GREEN: Movimento de ~4% no bloco é aceito (threshold 5%)
+            assert_eq!(pair.ensure_within_circuit_breaker(990, 1030), Ok(()));
+            pair.update(990, 1030).unwrap();
+
+            // RED: Movimento acumulado de ~12%, medido desde o início do bloco, é rejeitado
+            assert_eq!(pair.ensure_within_circuit_breaker(950, 1064), Err(PairError::CircuitBreaker));
+            let state = pair.circuit_breaker_state();
+            assert_eq!((state.start_reserve_0, state.start_reserve_1), (1000, 1000));
+
+            // GREEN: O swap rejeitado não alterou as reserves; swaps dentro do limite seguem
+            assert_eq!(pair.get_reserves().0, 990);
+            assert_eq!(pair.ensure_within_circuit_breaker(1000, 1000), Ok(()));
+        }
+
+        #[ink::test]
+        fn test_circuit_breaker_rearms_next_block() {
+            let accounts = default_accounts();
+            set_sender(accounts.bob);
+
+            let mut pair = PairContract::new(accounts.bob, accounts.charlie, accounts.django);
+            pair.set_circuit_breaker_threshold(100).unwrap();
+            pair.update(1000, 1000).unwrap();
+            test::advance_block::<ink::env::DefaultEnvironment>();
+            pair.update(990, 1010).unwrap();
+            assert_eq!(pair.ensure_within_circuit_breaker(980, 1020), Err(PairError::CircuitBreaker));
+
+            // GREEN: Novo bloco mede a partir das reserves atuais
+            test::advance_block::<ink::env::DefaultEnvironment>();
+            let state = pair.circuit_breaker_state();
+            assert_eq!((state.start_reserve_0, state.start_reserve_1), (990, 1010));
+            assert_eq!(pair.ensure_within_circuit_breaker(988, 1012), Ok(()));
+            assert_eq!(pair.ensure_within_circuit_breaker(980, 1020), Err(PairError::CircuitBreaker));
+        }
+
+        #[ink::test]
+        fn test_pause_flag_set_by_factory() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            // GREEN: Par nasce liberado
+            let mut pair = PairContract::new(accounts.bob, accounts.charlie, accounts.django);
+            assert!(!pair.is_paused());
+
+            // RED: Apenas o factory pausa
+            assert_eq!(pair.set_paused(true), Err(PairError::Unauthorized));
+
+            // RED: Pausado pelo factory, mint e swap são rejeitados
+            set_sender(accounts.bob);
+            pair.set_paused(true).unwrap();
+            assert!(pair.is_paused());
+            assert_eq!(pair.mint(accounts.alice), Err(PairError::Paused));
+            assert_eq!(pair.swap(0, 1, accounts.alice), Err(PairError::Paused));
+
+            // GREEN: Liberado de novo
+            pair.set_paused(false).unwrap();
+            assert!(pair.mint(accounts.alice).is_ok());
+        }
+
+        fn dynamic_config() -> DynamicFeeConfig {
+            DynamicFeeConfig {
+                min_fee_bps: 10,
+                max_fee_bps: 110,
+                window: 1_000,
+                volatility_cap_bps: 5_000,
+            }
+        }
+
+        #[ink::test]
+        fn test_set_dynamic_fee_validations() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+
+            let mut pair = PairContract::new(accounts.bob, accounts.charlie, accounts.django);
+            assert_eq!(pair.current_fee_bps(), constants::STATIC_FEE_BPS);
+
+            // RED: Apenas o factory configura a fee dinâmica
+            assert_eq!(pair.set_dynamic_fee(Some(dynamic_config())), Err(PairError::Unauthorized));
+
+            // RED: Mínimo acima do máximo ou máximo acima do teto
+            set_sender(accounts.bob);
+            let inverted = DynamicFeeConfig { min_fee_bps: 200, ..dynamic_config() };
+            assert_eq!(pair.set_dynamic_fee(Some(inverted)), Err(PairError::InvalidFeeConfig));
+            let too_high = DynamicFeeConfig { max_fee_bps: constants::MAX_DYNAMIC_FEE_BPS + 1, ..dynamic_config() };
+            assert_eq!(pair.set_dynamic_fee(Some(too_high)), Err(PairError::InvalidFeeConfig));
+
+            // GREEN: Sem volatilidade medida a fee é a mínima; None volta à fee estática
+            assert!(pair.set_dynamic_fee(Some(dynamic_config())).is_ok());
+            assert_eq!(pair.current_fee_bps(), 10);
+            assert!(pair.set_dynamic_fee(None).is_ok());
+            assert_eq!(pair.current_fee_bps(), constants::STATIC_FEE_BPS);
+        }
+
+        #[ink::test]
+        fn test_fee_for_volatility_interpolates() {
+            let config = dynamic_config();
+
+            // GREEN: Linear entre mínimo e máximo, limitada em volatility_cap_bps
+            assert_eq!(PairContract::fee_for_volatility(&config, 0), 10);
+            assert_eq!(PairContract::fee_for_volatility(&config, 2_500), 60);
+            assert_eq!(PairContract::fee_for_volatility(&config, 5_000), 110);
+            assert_eq!(PairContract::fee_for_volatility(&config, 50_000), 110);
+        }
+
+        #[ink::test]
+        fn test_swap_charges_current_fee_on_measured_input() {
+            let accounts = default_accounts();
+            set_sender(accounts.bob);
+
+            let mut pair = PairContract::new(accounts.bob, accounts.charlie, accounts.django);
+            pair.update(1_000_000, 1_000_000).unwrap();
+
+            // 1000 de token_0 entram e 990 de token_1 saem
+            let (balance_0, balance_1) = (1_001_000, 1_000_000 - 990);
+
+            // GREEN: O input medido paga a fee: aceito a 10 bps
+            assert_eq!(pair.check_swap_invariant(balance_0, balance_1, 0, 990, 10), Ok((1_000, 0)));
+            // RED: A mesma troca não paga 110 bps
+            assert_eq!(
+                pair.check_swap_invariant(balance_0, balance_1, 0, 990, 110),
+                Err(PairError::KValueDecreased)
+            );
+            // RED: Output sem input nenhum
+            assert_eq!(
+                pair.check_swap_invariant(1_000_000, 1_000_000 - 990, 0, 990, 10),
+                Err(PairError::InsufficientInputAmount)
+            );
+        }
+
+        #[ink::test]
+        fn test_swap_with_fee_discount_only_from_router() {
+            let accounts = default_accounts();
+            set_sender(accounts.bob);
+
+            let mut pair = PairContract::new(accounts.bob, accounts.charlie, accounts.django);
+            pair.update(1_000_000, 1_000_000).unwrap();
+
+            // RED: Sem router configurado ninguém aplica desconto
+            set_sender(accounts.eve);
+            assert_eq!(pair.swap_with_fee_discount(0, 990, accounts.eve, 3_000), Err(PairError::Unauthorized));
+
+            // RED: Apenas o factory configura o router
+            assert_eq!(pair.set_fee_discount_router(Some(accounts.eve)), Err(PairError::Unauthorized));
+            set_sender(accounts.bob);
+            pair.set_fee_discount_router(Some(accounts.eve)).unwrap();
+            assert_eq!(pair.fee_discount_router(), Some(accounts.eve));
+
+            // GREEN: O router passa da autorização (off-chain não há input medido)
+            set_sender(accounts.eve);
+            assert_eq!(
+                pair.swap_with_fee_discount(0, 990, accounts.eve, 3_000),
+                Err(PairError::InsufficientInputAmount)
+            );
+
+            // GREEN: 30% de desconto sobre 50 bps → 35 bps; desconto acima de 100% é inválido
+            assert_eq!(PairContract::discounted_fee_bps(50, 3_000), Ok(35));
+            assert_eq!(PairContract::discounted_fee_bps(50, 10_001), Err(PairError::InvalidFeeConfig));
+
+            // GREEN: A troca que não paga 50 bps passa com a fee descontada
+            let (balance_0, balance_1) = (1_001_000, 1_000_000 - 995);
+            assert_eq!(
+                pair.check_swap_invariant(balance_0, balance_1, 0, 995, 50),
+                Err(PairError::KValueDecreased)
+            );
+            assert_eq!(pair.check_swap_invariant(balance_0, balance_1, 0, 995, 35), Ok((1_000, 0)));
+        }
+
+        #[ink::test]
+        fn test_large_reserves_accumulate_and_measure_volatility() {
+            let accounts = default_accounts();
+            set_sender(accounts.bob);
+
+            // GREEN: Reserves acima de 2^16 não estouram os acumuladores
+            let (big, doubled): (Balance, Balance) = (1_000_000_000_000_000_000_000_000, 2_000_000_000_000_000_000_000_000);
+            let mut pair = PairContract::new(accounts.bob, accounts.charlie, accounts.django);
+            pair.update(big, big).unwrap();
+            pair.set_dynamic_fee(Some(dynamic_config())).unwrap();
+
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            pair.update(big, doubled).unwrap();
+            assert!(pair.price_0_cumulative_last() > 0);
+
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(2_000);
+            pair.update(big, doubled).unwrap();
+            // GREEN: TWAP dobra entre janelas → volatilidade de 100%, fee no máximo
+            assert_eq!(pair.realized_volatility_bps(), 10_000);
+            assert_eq!(pair.current_fee_bps(), 110);
+        }
+
+        #[ink::test]
+        fn test_dynamic_fee_tracks_realized_volatility() {
+            let accounts = default_accounts();
+            set_sender(accounts.bob);
+
+            let mut pair = PairContract::new(accounts.bob, accounts.charlie, accounts.django);
+            pair.update(1000, 1000).unwrap();
+            pair.set_dynamic_fee(Some(dynamic_config())).unwrap();
+
+            // Janela 1: preço estável em 1.0
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(1_000);
+            pair.update(1000, 1000).unwrap();
+            assert_eq!(pair.realized_volatility_bps(), 0);
+
+            // Janela 2: preço continua 1.0 (o acumulador usa as reserves anteriores)
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(2_000);
+            pair.update(1000, 1250).unwrap();
+            assert_eq!(pair.realized_volatility_bps(), 0);
+            assert_eq!(pair.current_fee_bps(), 10);
+
+            // Janela 3: TWAP sobe 25% → fee na metade da faixa
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(3_000);
+            pair.update(1000, 1250).unwrap();
+            assert_eq!(pair.realized_volatility_bps(), 2_500);
+            assert_eq!(pair.current_fee_bps(), 60);
+
+            // GREEN: Atualizações dentro da janela não alteram a medição
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(3_500);
+            pair.update(1000, 2000).unwrap();
+            assert_eq!(pair.realized_volatility_bps(), 2_500);
+        }
     }
 }
@@ -0,0 +1,124 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+
+//! Permit PSP22 (aprovação por assinatura) compartilhado pelos LP tokens
+//! dos pares e pelo WNATIVE.
+//!
+//! O digest assinado é `blake2_256(domain_separator, owner, spender, value, nonce, deadline)`,
+//! onde o domínio amarra a versão do formato, a rede e o endereço do contrato.
+
+use ink::primitives::{AccountId, Hash};
+
+/// Tag do domínio dos permits (versão do formato do payload)
+pub const PERMIT_DOMAIN_TAG: &[u8] = b"PSP22Permit:v1";
+
+/// Identidade da rede no domínio dos permits
+///
+/// Impede o replay de uma assinatura entre mainnet e testnet quando o
+/// contrato tem o mesmo endereço nas duas redes (mesmo deployer e salt).
+#[cfg(not(feature = "testnet"))]
+pub const PERMIT_CHAIN_ID: &[u8] = b"lunes:mainnet";
+#[cfg(feature = "testnet")]
+pub const PERMIT_CHAIN_ID: &[u8] = b"lunes:testnet";
+
+/// Assinatura de um permit
+///
+/// * `Sr25519` - assinatura da própria conta (chave pública = AccountId)
+/// * `Ecdsa` - assinatura recuperável; a conta é `blake2_256(chave pública comprimida)`
+#[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum PermitSignature {
+    Sr25519([u8; 64]),
+    Ecdsa([u8; 65]),
+}
+
+/// Separador de domínio (tag de versão + rede + endereço do contrato)
+pub fn domain_separator(contract: AccountId) -> Hash {
+    let mut output = [0u8; 32];
+    ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(
+        &(PERMIT_DOMAIN_TAG, PERMIT_CHAIN_ID, contract),
+        &mut output,
+    );
+    Hash::from(output)
+}
+
+/// Payload assinado de um permit (`deadline` em ms)
+pub fn permit_digest(
+    domain: Hash,
+    owner: AccountId,
+    spender: AccountId,
+    value: u128,
+    nonce: u64,
+    deadline: u64,
+) -> [u8; 32] {
+    let mut output = [0u8; 32];
+    ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(
+        &(domain, owner, spender, value, nonce, deadline),
+        &mut output,
+    );
+    output
+}
+
+/// Verifica a assinatura de um permit sobre `digest` para `owner`
+pub fn verify_permit_signature(
+    owner: &AccountId,
+    digest: &[u8; 32],
+    signature: &PermitSignature,
+) -> bool {
+    match signature {
+        PermitSignature::Sr25519(signature) => {
+            ink::env::sr25519_verify(signature, digest, owner.as_ref()).is_ok()
+        }
+        PermitSignature::Ecdsa(signature) => {
+            let mut public_key = [0u8; 33];
+            if ink::env::ecdsa_recover(signature, digest, &mut public_key).is_err() {
+                return false;
+            }
+            let mut account = [0u8; 32];
+            ink::env::hash_bytes::<ink::env::hash::Blake2x256>(&public_key, &mut account);
+            AccountId::from(account) == *owner
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_domain_separator_binds_chain_and_contract() {
+        let contract = AccountId::from([1u8; 32]);
+
+        // RED: Domínio sem a rede (formato antigo) não coincide
+        let mut legacy = [0u8; 32];
+        ink::env::hash_encoded::<ink::env::hash::Blake2x256, _>(
+            &(PERMIT_DOMAIN_TAG, contract),
+            &mut legacy,
+        );
+        assert_ne!(domain_separator(contract), Hash::from(legacy));
+
+        // GREEN: Determinístico por contrato e distinto entre contratos
+        assert_eq!(domain_separator(contract), domain_separator(contract));
+        assert_ne!(
+            domain_separator(contract),
+            domain_separator(AccountId::from([2u8; 32]))
+        );
+    }
+
+    #[test]
+    fn test_invalid_signature_rejected() {
+        let owner = AccountId::from([1u8; 32]);
+        let digest = permit_digest(domain_separator(owner), owner, owner, 1, 0, 0);
+
+        // RED: Assinaturas arbitrárias não verificam
+        assert!(!verify_permit_signature(
+            &owner,
+            &digest,
+            &PermitSignature::Sr25519([0u8; 64])
+        ));
+        assert!(!verify_permit_signature(
+            &owner,
+            &digest,
+            &PermitSignature::Ecdsa([1u8; 65])
+        ));
+    }
+}
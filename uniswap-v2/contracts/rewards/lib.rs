@@ -4,6 +4,9 @@
 #[ink::contract]
 pub mod trading_rewards {
     use ink::storage::Mapping;
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::DefaultEnvironment;
+    use staking_contract::staking_contract::StakingError;
 
 
 
@@ -102,6 +105,17 @@ pub mod trading_rewards {
         pub timestamp: Timestamp,
     }
 
+    /// Emitido quando o repasse ao staking falha; o valor fica no pool de trading
+    #[ink(event)]
+    pub struct StakingForwardFailed {
+        #[ink(topic)]
+        pub staking_contract: AccountId,
+        pub amount: Balance,
+        /// Erro retornado pelo staking (`None` se a chamada não chegou a executar)
+        pub error: Option<StakingError>,
+        pub timestamp: Timestamp,
+    }
+
     #[ink(event)]
     pub struct AntifraudParametersUpdated {
         pub min_trade_volume: Balance,
@@ -200,6 +214,12 @@ pub mod trading_rewards {
         epoch_active_traders: Mapping<u32, u32>,
     }
 
+    /// Selectors das chamadas cross-contract ao staking
+    mod selectors {
+        pub const STAKING_FUND_TRADING_REWARDS_POOL: [u8; 4] =
+            ink::selector_bytes!("fund_trading_rewards_pool");
+    }
+
     /// Constantes
     mod constants {
         use super::{Balance, Timestamp};
@@ -207,6 +227,13 @@ pub mod trading_rewards {
         /// Multiplicador para 8 casas decimais do LUNES
         pub const DECIMALS_8: Balance = 100_000_000;
         
+        /// Unidades de tempo em milissegundos (mesma base de `block_timestamp()`
+        /// usada pelo staking)
+        pub const ONE_SECOND: Timestamp = 1000;
+        pub const ONE_MINUTE: Timestamp = 60 * ONE_SECOND;
+        pub const ONE_HOUR: Timestamp = 60 * ONE_MINUTE;
+        pub const ONE_DAY: Timestamp = 24 * ONE_HOUR;
+        
         /// Thresholds para tiers (em LUNES com 8 decimais)
 
         pub const SILVER_THRESHOLD: Balance = 10_000 * DECIMALS_8;    // 10k LUNES
@@ -219,8 +246,8 @@ pub mod trading_rewards {
         pub const GOLD_MULTIPLIER: u32 = 150;     // 1.5x
         pub const PLATINUM_MULTIPLIER: u32 = 200; // 2.0x
         
-        /// Período de reset mensal (30 dias em segundos)
-        pub const MONTHLY_RESET_PERIOD: Timestamp = 30 * 24 * 60 * 60;
+        /// Período de reset mensal (30 dias)
+        pub const MONTHLY_RESET_PERIOD: Timestamp = 30 * ONE_DAY;
         
         /// === CONSTANTES ANTI-FRAUDE (valores padrão) ===
         
@@ -228,18 +255,24 @@ pub mod trading_rewards {
         pub const DEFAULT_MIN_TRADE_VOLUME: Balance = 100 * DECIMALS_8; // 100 LUNES
         
         /// Cooldown mínimo entre trades (anti-spam) - padrão
-        pub const DEFAULT_TRADE_COOLDOWN: Timestamp = 60; // 1 minuto
+        pub const DEFAULT_TRADE_COOLDOWN: Timestamp = ONE_MINUTE;
         
         /// Limite máximo de volume diário por trader - padrão
         pub const DEFAULT_MAX_DAILY_VOLUME: Balance = 1_000_000 * DECIMALS_8; // 1M LUNES
         
-        /// Período de reset diário (24 horas em segundos)
-        pub const DAILY_RESET_PERIOD: Timestamp = 24 * 60 * 60;
+        /// Período de reset diário (24 horas)
+        pub const DAILY_RESET_PERIOD: Timestamp = ONE_DAY;
         
         /// === CONSTANTES DE ÉPOCA ===
         
-        /// Duração padrão de uma época (7 dias em segundos)
-        pub const DEFAULT_EPOCH_DURATION: Timestamp = 7 * 24 * 60 * 60; // 1 semana
+        /// Duração padrão de uma época (7 dias)
+        pub const DEFAULT_EPOCH_DURATION: Timestamp = 7 * ONE_DAY; // 1 semana
+        
+        /// Duração mínima de uma época (1 hora)
+        pub const MIN_EPOCH_DURATION: Timestamp = ONE_HOUR;
+        
+        /// Validade do cache de peso total (5 minutos)
+        pub const WEIGHT_CACHE_VALIDITY: Timestamp = 5 * ONE_MINUTE;
         
         /// Flags de suspeita
         pub const SUSPICIOUS_FLAG_NONE: u8 = 0;
@@ -632,7 +665,7 @@ pub mod trading_rewards {
             Ok(())
         }
 
-        /// Define cooldown entre trades (apenas admin)
+        /// Define cooldown entre trades em milissegundos (apenas admin)
         #[ink(message)]
         pub fn set_trade_cooldown(&mut self, cooldown: Timestamp) -> Result<(), TradingRewardsError> {
             self.ensure_admin()?;
@@ -724,11 +757,11 @@ pub mod trading_rewards {
             Ok(())
         }
 
-        /// Define duração da época (apenas admin)
+        /// Define duração da época em milissegundos (apenas admin)
         #[ink(message)]
         pub fn set_epoch_duration(&mut self, duration: Timestamp) -> Result<(), TradingRewardsError> {
             self.ensure_admin()?;
-            if duration < 3600 { // Mínimo de 1 hora
+            if duration < constants::MIN_EPOCH_DURATION {
                 return Err(TradingRewardsError::VolumeTooSmall); // Reutilizando erro por simplicidade
             }
             self.epoch_duration = duration;
@@ -861,6 +894,10 @@ pub mod trading_rewards {
         }
 
         /// Recebe fee allocation do pair contract e distribui 10% para staking
+        ///
+        /// O repasse ao staking é best-effort: se a chamada falhar (ou não houver
+        /// staking configurado) a parcela fica no pool de trading e, em caso de
+        /// falha, `StakingForwardFailed` registra o erro decodificado.
         #[ink(message, payable)]
         pub fn receive_fee_allocation(&mut self) -> Result<(), TradingRewardsError> {
             self.ensure_not_paused()?;
@@ -882,24 +919,37 @@ pub mod trading_rewards {
                 .checked_sub(trading_rewards_amount)
                 .ok_or(TradingRewardsError::Overflow)?;
             
+            // Envia para o pool de trading rewards do staking se configurado
+            // (repartido pro-rata entre os stakers, fora do orçamento de APY)
+            let forwarded = match self.staking_contract {
+                Some(staking_address) if staking_rewards_amount > 0 => {
+                    match self.forward_to_staking(staking_address, staking_rewards_amount) {
+                        Ok(()) => true,
+                        Err(error) => {
+                            Self::env().emit_event(StakingForwardFailed {
+                                staking_contract: staking_address,
+                                amount: staking_rewards_amount,
+                                error,
+                                timestamp: Self::env().block_timestamp(),
+                            });
+                            false
+                        }
+                    }
+                }
+                _ => false,
+            };
+            // Parcela não repassada fica no pool de trading
+            let (trading_rewards_amount, staking_rewards_amount) = if forwarded {
+                (trading_rewards_amount, staking_rewards_amount)
+            } else {
+                (amount, 0)
+            };
+            
             // Adiciona ao pool de trading
             self.rewards_pool = self.rewards_pool
                 .checked_add(trading_rewards_amount)
                 .ok_or(TradingRewardsError::Overflow)?;
             
-            // Envia para staking se configurado
-            if let Some(staking_address) = self.staking_contract {
-                if staking_rewards_amount > 0 {
-                    // Chama fund_staking_rewards no contrato de staking
-                    if Self::env().transfer(staking_address, staking_rewards_amount).is_err() {
-                        return Err(TradingRewardsError::InsufficientBalance);
-                    }
-                    
-                    // Aqui deveria chamar o contrato de staking para notificar
-                    // mas por simplicidade vamos só transferir
-                }
-            }
-            
             Self::env().emit_event(RewardsPoolFunded {
                 total_amount: amount,
                 trading_amount: trading_rewards_amount,
@@ -912,6 +962,23 @@ pub mod trading_rewards {
 
         // === FUNÇÕES HELPER ===
 
+        /// Repassa a parcela dos stakers ao `fund_trading_rewards_pool` do staking
+        ///
+        /// Retorna o erro decodificado do staking, ou `None` se a chamada em si falhou.
+        fn forward_to_staking(&self, staking_address: AccountId, amount: Balance) -> Result<(), Option<StakingError>> {
+            build_call::<DefaultEnvironment>()
+                .call(staking_address)
+                .transferred_value(amount)
+                .exec_input(ExecutionInput::new(Selector::new(
+                    selectors::STAKING_FUND_TRADING_REWARDS_POOL,
+                )))
+                .returns::<Result<(), StakingError>>()
+                .try_invoke()
+                .map_err(|_| None)?
+                .map_err(|_| None)?
+                .map_err(Some)
+        }
+
         /// Calcula tier baseado no volume mensal
         fn calculate_tier(&self, monthly_volume: Balance) -> TradingTier {
             if monthly_volume >= constants::PLATINUM_THRESHOLD {
@@ -930,10 +997,9 @@ pub mod trading_rewards {
         /// Calcula peso total usando cache inteligente
         fn calculate_total_weight(&mut self) -> Result<Balance, TradingRewardsError> {
             let current_time = Self::env().block_timestamp();
-            const CACHE_VALIDITY_PERIOD: u64 = 300; // 5 minutos
             
             // Se cache é válido, usa valor cached
-            if current_time.checked_sub(self.weight_cache_timestamp).unwrap_or(0) < CACHE_VALIDITY_PERIOD {
+            if current_time.checked_sub(self.weight_cache_timestamp).unwrap_or(0) < constants::WEIGHT_CACHE_VALIDITY {
                 return Ok(self.cached_total_weight);
             }
             
@@ -1064,7 +1130,7 @@ pub mod trading_rewards {
         fn advance_time(seconds: u64) {
             TEST_TIME.with(|time| {
                 let current = time.get();
-                let new_time = current + seconds;
+                let new_time = current + seconds * constants::ONE_SECOND;
                 time.set(new_time);
                 ink::env::test::set_block_timestamp::<ink::env::DefaultEnvironment>(new_time);
             });
@@ -1340,13 +1406,13 @@ pub mod trading_rewards {
             // Atualiza parâmetros (apenas admin)
             set_next_caller(accounts.alice);
             contract.set_min_trade_volume(200 * constants::DECIMALS_8).unwrap(); // 200 LUNES
-            contract.set_trade_cooldown(120).unwrap(); // 2 minutos
+            contract.set_trade_cooldown(2 * constants::ONE_MINUTE).unwrap(); // 2 minutos
             contract.set_max_daily_volume(500_000 * constants::DECIMALS_8).unwrap(); // 500k LUNES
             
             // Verifica novos valores
             let (new_min_vol, new_cooldown, new_max_daily) = contract.get_antifraud_parameters();
             assert_eq!(new_min_vol, 200 * constants::DECIMALS_8);
-            assert_eq!(new_cooldown, 120);
+            assert_eq!(new_cooldown, 2 * constants::ONE_MINUTE);
             assert_eq!(new_max_daily, 500_000 * constants::DECIMALS_8);
             
             // Testa com novos parâmetros
@@ -1394,7 +1460,7 @@ pub mod trading_rewards {
             assert_eq!(epoch_id, 1);
             
             // Define nova duração
-            contract.set_epoch_duration(3600).unwrap(); // 1 hora
+            contract.set_epoch_duration(constants::ONE_HOUR).unwrap(); // 1 hora
             
             // Não-admin não pode iniciar época
             set_next_caller(accounts.bob);
@@ -1410,6 +1476,21 @@ pub mod trading_rewards {
             );
         }
 
+        #[ink::test]
+        fn test_fee_allocation_without_staking_stays_in_pool() {
+            let accounts = default_accounts();
+            let mut contract = TradingRewardsContract::new(accounts.alice, accounts.bob).unwrap();
+            
+            // RED: alocação vazia
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            assert_eq!(contract.receive_fee_allocation(), Err(TradingRewardsError::InsufficientBalance));
+            
+            // GREEN: sem staking configurado os 10% não ficam fora do pool
+            ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(1_000);
+            assert!(contract.receive_fee_allocation().is_ok());
+            assert_eq!(contract.get_stats().0, 1_000);
+        }
+
         #[ink::test]
         fn test_admin_events() {
             let accounts = default_accounts();
@@ -5,6 +5,13 @@ pub mod router_contract {
     use psp22::PSP22Error;
     use ink::prelude::vec::Vec;
     use ink::prelude::vec;
+    use primitive_types::U256;
+    use scale::{DecodeAll, Encode};
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::DefaultEnvironment;
+    pub use permit::PermitSignature;
+    use wnative_contract::wnative_contract::WnativeError;
+    use staking_contract::staking_contract::{StakePosition, StakingTier};
 
     // ========================================
     // ROUTER CONTRACT - DEX OPERATIONS COORDINATOR
@@ -15,6 +22,11 @@ pub mod router_contract {
     // - Swaps: Coordena trocas através de múltiplos pares
     // - Slippage Protection: Validações min/max amounts
     // - Multi-hop: Swaps através de múltiplos pares
+    // - Split-route: Divide um swap grande entre 2-3 rotas
+    // - Multicall: Executa várias operações do router atomicamente
+    // - Zap: Entrada/saída de liquidez com um único ativo (inclusive LUNES nativo)
+    // - Referral: Fee opcional de integrador, limitada pela governança
+    // - Desconto de staking: fee de swap reduzida para stakers Silver/Gold/Platinum
     // 
     // ## Segurança:
     // - Deadline verification para prevenir transações antigas
@@ -64,6 +76,99 @@ pub mod router_contract {
         pub path: Vec<AccountId>,
         #[ink(topic)]
         pub to: AccountId,
+        /// Integrador que recebeu a fee de referral (se houver)
+        pub referrer: Option<AccountId>,
+        /// Fee de referral descontada do input (já incluída em `amount_in`)
+        pub referral_fee: Balance,
+    }
+
+    /// Emitido quando um integrador resgata fees de referral acumuladas
+    #[ink(event)]
+    pub struct ReferralFeesClaimed {
+        #[ink(topic)]
+        pub referrer: AccountId,
+        #[ink(topic)]
+        pub token: AccountId,
+        pub amount: Balance,
+    }
+
+    /// Emitido quando a governança altera o teto da fee de referral
+    #[ink(event)]
+    pub struct MaxReferralFeeChanged {
+        pub old_max_bps: u16,
+        pub new_max_bps: u16,
+    }
+
+    /// Emitido quando a governança altera a tabela de descontos por tier de staking
+    #[ink(event)]
+    pub struct FeeDiscountsChanged {
+        pub staking_contract: Option<AccountId>,
+        pub discounts: FeeDiscounts,
+    }
+
+    /// Emitido quando o tier de fee em cache de uma conta é atualizado
+    #[ink(event)]
+    pub struct FeeTierRefreshed {
+        #[ink(topic)]
+        pub account: AccountId,
+        /// Tier em cache após a atualização (`None` = sem desconto)
+        pub tier: Option<StakingTier>,
+        /// Fim do lock da posição em cache
+        pub unlock_time: Option<Timestamp>,
+    }
+
+    /// Emitido quando a governança marca ou desmarca um agregador confiável
+    #[ink(event)]
+    pub struct TrustedAggregatorChanged {
+        #[ink(topic)]
+        pub aggregator: AccountId,
+        pub trusted: bool,
+    }
+
+    /// Emitido quando o admin do router é transferido
+    #[ink(event)]
+    pub struct AdminTransferred {
+        pub old_admin: AccountId,
+        pub new_admin: AccountId,
+    }
+
+    // ========================================
+    // TIPOS AUXILIARES
+    // ========================================
+
+    /// Estatísticas acumuladas de um integrador (referrer)
+    ///
+    /// Volumes e fees são somados nas unidades do token de entrada de cada swap.
+    #[derive(Debug, Default, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct ReferralStats {
+        /// Número de swaps referidos
+        pub swap_count: u64,
+        /// Volume de input (bruto) dos swaps referidos
+        pub total_volume: Balance,
+        /// Total de fees de referral geradas
+        pub total_fees: Balance,
+    }
+
+    /// Desconto sobre a fee de swap por tier de staking (bps da fee; 10000 = isenção)
+    #[derive(Debug, Default, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct FeeDiscounts {
+        pub silver_bps: u16,
+        pub gold_bps: u16,
+        pub platinum_bps: u16,
+    }
+
+    /// Tier de fee de uma posição de staking, em cache até o fim do lock
+    #[derive(Debug, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct CachedFeeTier {
+        /// Tier de fee da posição (`StakePosition::fee_tier`)
+        pub tier: StakingTier,
+        /// Posição de staking que garante o tier
+        pub position_id: u32,
+        /// Fim do lock da posição; depois disso o desconto deixa de valer
+        pub unlock_time: Timestamp,
     }
 
     // ========================================
@@ -95,8 +200,32 @@ pub mod router_contract {
         ExcessiveInputAmount,
         /// Pair não existe
         PairNotExists,
+        /// Divisão de rotas inválida (número de legs ou tokens de entrada/saída divergentes)
+        InvalidSplit,
+        /// Chamada de multicall com selector desconhecido ou argumentos inválidos
+        InvalidCall,
+        /// Token não pertence ao par informado
+        TokenNotInPair,
+        /// Wrap/unwrap ou transferência de LUNES nativo falhou
+        TransferFailed,
+        /// Fee de referral acima do máximo definido pela governança
+        ReferralFeeTooHigh,
+        /// Nenhuma fee de referral acumulada para resgate
+        NoReferralFees,
+        /// Caller não é o admin do router
+        Unauthorized,
+        /// Fee de swap acima de 100%
+        InvalidFee,
+        /// Tabela de descontos inválida (acima de 100% ou fora de ordem entre tiers)
+        InvalidFeeDiscount,
+        /// Permit de LP rejeitado pelo par (assinatura, nonce ou deadline)
+        PermitFailed,
+        /// Staking Contract não configurado ou consulta de posição falhou
+        StakingUnavailable,
         /// Erro no token PSP22 subjacente
         PSP22(PSP22Error),
+        /// Referrer não pode ser o próprio caller do swap
+        SelfReferral,
     }
 
     impl From<PSP22Error> for RouterError {
@@ -112,9 +241,65 @@ pub mod router_contract {
         /// Minimum liquidity para cálculos (mesmo valor do Pair)
         pub const MINIMUM_LIQUIDITY: u128 = 100;
         
-        /// Fee para swaps (0.3% = 997/1000)
-        pub const FEE_DENOMINATOR: u128 = 1000;
-        pub const FEE_NUMERATOR: u128 = 997;
+        /// Número máximo de rotas em um split-route swap
+        pub const MAX_SPLIT_LEGS: usize = 3;
+
+        /// Granularidade da busca de divisão ótima (cada passo aloca 1% do input)
+        pub const SPLIT_QUOTE_STEPS: u128 = 100;
+
+        /// Número máximo de chamadas em um multicall
+        pub const MAX_MULTICALL_CALLS: usize = 16;
+
+        /// Denominador das fees em basis points (100% = 10000)
+        pub const BPS_DENOMINATOR: u128 = 10_000;
+
+        /// Fee estática do PairContract em bps (0.5% = 995/1000), usada quando
+        /// o par não expõe `current_fee_bps`
+        pub const PAIR_STATIC_FEE_BPS: u16 = 50;
+
+        /// Fee cheia sobre a qual o desconto por tier é aplicado nas cotações
+        pub const BASE_SWAP_FEE_BPS: u16 = PAIR_STATIC_FEE_BPS;
+
+        /// Descontos iniciais sobre a fee por tier de staking (10%, 20%, 30%)
+        pub const DEFAULT_SILVER_DISCOUNT_BPS: u16 = 1_000;
+        pub const DEFAULT_GOLD_DISCOUNT_BPS: u16 = 2_000;
+        pub const DEFAULT_PLATINUM_DISCOUNT_BPS: u16 = 3_000;
+
+        /// Teto inicial da fee de referral (0.3%)
+        pub const DEFAULT_MAX_REFERRAL_FEE_BPS: u16 = 30;
+
+        /// Limite absoluto que a governança pode configurar (1%)
+        pub const REFERRAL_FEE_BPS_CEILING: u16 = 100;
+    }
+
+    /// Selectors das mensagens aceitas pelo multicall e das chamadas cross-contract
+    mod selectors {
+        pub const ADD_LIQUIDITY: [u8; 4] = ink::selector_bytes!("add_liquidity");
+        pub const REMOVE_LIQUIDITY: [u8; 4] = ink::selector_bytes!("remove_liquidity");
+        pub const SWAP_EXACT_TOKENS_FOR_TOKENS: [u8; 4] = ink::selector_bytes!("swap_exact_tokens_for_tokens");
+        pub const SWAP_TOKENS_FOR_EXACT_TOKENS: [u8; 4] = ink::selector_bytes!("swap_tokens_for_exact_tokens");
+        pub const SWAP_EXACT_TOKENS_FOR_TOKENS_SPLIT: [u8; 4] = ink::selector_bytes!("swap_exact_tokens_for_tokens_split");
+        pub const ZAP_IN: [u8; 4] = ink::selector_bytes!("zap_in");
+        pub const ZAP_OUT: [u8; 4] = ink::selector_bytes!("zap_out");
+        pub const SWAP_EXACT_TOKENS_FOR_TOKENS_WITH_REFERRAL: [u8; 4] =
+            ink::selector_bytes!("swap_exact_tokens_for_tokens_with_referral");
+        pub const SWAP_TOKENS_FOR_EXACT_TOKENS_WITH_REFERRAL: [u8; 4] =
+            ink::selector_bytes!("swap_tokens_for_exact_tokens_with_referral");
+
+        // Mensagens externas consultadas pelo router
+        pub const PAIR_TOKEN_0: [u8; 4] = ink::selector_bytes!("token_0");
+        pub const PAIR_TOKEN_1: [u8; 4] = ink::selector_bytes!("token_1");
+        pub const PAIR_GET_RESERVES: [u8; 4] = ink::selector_bytes!("get_reserves");
+        pub const PAIR_CURRENT_FEE_BPS: [u8; 4] = ink::selector_bytes!("current_fee_bps");
+        pub const PAIR_PERMIT: [u8; 4] = ink::selector_bytes!("permit");
+        pub const PAIR_BURN: [u8; 4] = ink::selector_bytes!("burn");
+        pub const FACTORY_GET_PAIR: [u8; 4] = ink::selector_bytes!("get_pair");
+        pub const STAKING_GET_STAKE: [u8; 4] = ink::selector_bytes!("get_stake");
+        pub const PSP22_TRANSFER: [u8; 4] = ink::selector_bytes!("PSP22::transfer");
+        pub const PSP22_TRANSFER_FROM: [u8; 4] = ink::selector_bytes!("PSP22::transfer_from");
+        pub const PSP22_BALANCE_OF: [u8; 4] = ink::selector_bytes!("PSP22::balance_of");
+        pub const WNATIVE_DEPOSIT: [u8; 4] = ink::selector_bytes!("deposit");
+        pub const WNATIVE_WITHDRAW: [u8; 4] = ink::selector_bytes!("withdraw");
     }
 
     // ========================================
@@ -128,13 +313,49 @@ pub mod router_contract {
         factory: AccountId,
         /// Endereço do WNative Contract
         wnative: AccountId,
+        /// Admin (governança) do router
+        admin: AccountId,
+        /// Teto da fee de referral em basis points
+        max_referral_fee_bps: u16,
+        /// Estatísticas acumuladas por referrer
+        referral_stats: ink::storage::Mapping<AccountId, ReferralStats>,
+        /// Fees de referral a resgatar por (referrer, token)
+        referral_fees: ink::storage::Mapping<(AccountId, AccountId), Balance>,
+        /// Fees de referral efetivamente recebidas e ainda não resgatadas, por token
+        referral_fees_collected: ink::storage::Mapping<AccountId, Balance>,
+        /// Staking Contract consultado para descontos (`None` = desativado)
+        staking_contract: Option<AccountId>,
+        /// Desconto sobre a fee de swap por tier de staking
+        fee_discounts: FeeDiscounts,
+        /// Tier de fee em cache por conta (atualizado por `refresh_fee_tier`)
+        fee_tiers: ink::storage::Mapping<AccountId, CachedFeeTier>,
+        /// Agregadores cujo desconto é calculado para o destinatário `to`
+        trusted_aggregators: ink::storage::Mapping<AccountId, bool>,
     }
 
     impl RouterContract {
         /// Construtor do Router Contract
+        ///
+        /// O deployer se torna o admin (governança) do router.
         #[ink(constructor)]
         pub fn new(factory: AccountId, wnative: AccountId) -> Self {
-            Self { factory, wnative }
+            Self {
+                factory,
+                wnative,
+                admin: Self::env().caller(),
+                max_referral_fee_bps: constants::DEFAULT_MAX_REFERRAL_FEE_BPS,
+                referral_stats: ink::storage::Mapping::default(),
+                referral_fees: ink::storage::Mapping::default(),
+                referral_fees_collected: ink::storage::Mapping::default(),
+                staking_contract: None,
+                fee_discounts: FeeDiscounts {
+                    silver_bps: constants::DEFAULT_SILVER_DISCOUNT_BPS,
+                    gold_bps: constants::DEFAULT_GOLD_DISCOUNT_BPS,
+                    platinum_bps: constants::DEFAULT_PLATINUM_DISCOUNT_BPS,
+                },
+                fee_tiers: ink::storage::Mapping::default(),
+                trusted_aggregators: ink::storage::Mapping::default(),
+            }
         }
 
         // ========================================
@@ -153,6 +374,79 @@ pub mod router_contract {
             self.wnative
         }
 
+        /// Retorna o admin (governança) do router
+        #[ink(message)]
+        pub fn admin(&self) -> AccountId {
+            self.admin
+        }
+
+        /// Retorna o teto atual da fee de referral em basis points
+        #[ink(message)]
+        pub fn max_referral_fee_bps(&self) -> u16 {
+            self.max_referral_fee_bps
+        }
+
+        /// Retorna as estatísticas acumuladas de um referrer
+        #[ink(message)]
+        pub fn get_referrer_stats(&self, referrer: AccountId) -> ReferralStats {
+            self.referral_stats.get(referrer).unwrap_or_default()
+        }
+
+        /// Retorna as fees de referral pendentes de resgate em um token
+        #[ink(message)]
+        pub fn referral_fees_of(&self, referrer: AccountId, token: AccountId) -> Balance {
+            self.referral_fees.get((referrer, token)).unwrap_or(0)
+        }
+
+        /// Retorna o total de fees de referral recebidas em `token` e ainda não resgatadas
+        #[ink(message)]
+        pub fn referral_fees_collected(&self, token: AccountId) -> Balance {
+            self.referral_fees_collected.get(token).unwrap_or(0)
+        }
+
+        /// Retorna o Staking Contract usado para descontos de fee
+        #[ink(message)]
+        pub fn staking_contract(&self) -> Option<AccountId> {
+            self.staking_contract
+        }
+
+        /// Retorna a tabela de descontos por tier de staking
+        #[ink(message)]
+        pub fn fee_discounts(&self) -> FeeDiscounts {
+            self.fee_discounts
+        }
+
+        /// Indica se `aggregator` repassa o desconto ao destinatário do swap
+        #[ink(message)]
+        pub fn is_trusted_aggregator(&self, aggregator: AccountId) -> bool {
+            self.trusted_aggregators.get(aggregator).unwrap_or(false)
+        }
+
+        /// Fee de swap em bps aplicada a `trader`, já com o desconto do seu tier
+        ///
+        /// Lê o tier em cache (`refresh_fee_tier`), sem chamada cross-contract;
+        /// sem cache, com o lock vencido ou sem staking configurado retorna a
+        /// fee cheia.
+        #[ink(message)]
+        pub fn get_trader_fee_bps(&self, trader: AccountId) -> u16 {
+            Self::discounted_fee_bps(&self.fee_discounts, self.cached_fee_tier(trader))
+        }
+
+        /// Desconto em bps da fee aplicado a `trader` (10000 = isenção)
+        ///
+        /// É o valor repassado a `PairContract::swap_with_fee_discount`, que
+        /// cobra o desconto no swap executado quando o factory autoriza o router.
+        #[ink(message)]
+        pub fn get_trader_discount_bps(&self, trader: AccountId) -> u16 {
+            Self::discount_bps(&self.fee_discounts, self.cached_fee_tier(trader))
+        }
+
+        /// Tier de fee em cache de `account`, com validade até o fim do lock
+        #[ink(message)]
+        pub fn fee_tier_of(&self, account: AccountId) -> Option<CachedFeeTier> {
+            self.fee_tiers.get(account)
+        }
+
         // ========================================
         // OPERAÇÕES DE LIQUIDEZ
         // ========================================
@@ -259,6 +553,53 @@ pub mod router_contract {
             Ok((amount_a, amount_b))
         }
 
+        /// Remove liquidez aprovando o LP via permit assinado (sem `approve` prévio)
+        /// 
+        /// O caller assina um permit do LP token do par para o router com
+        /// `value = liquidity` e o mesmo `deadline` da operação. O router move os
+        /// `liquidity` LP do caller para o par e chama `burn(to)`, consumindo todo
+        /// o permit: nenhuma allowance de LP sobra para o router.
+        #[ink(message)]
+        #[allow(clippy::too_many_arguments)]
+        pub fn remove_liquidity_with_permit(
+            &mut self,
+            token_a: AccountId,
+            token_b: AccountId,
+            liquidity: Balance,
+            amount_a_min: Balance,
+            amount_b_min: Balance,
+            to: AccountId,
+            deadline: u64,
+            signature: PermitSignature,
+        ) -> Result<(Balance, Balance), RouterError> {
+            // Validações antes de qualquer chamada externa
+            self.ensure_deadline(deadline)?;
+            self.validate_addresses(token_a, token_b)?;
+            if liquidity == 0 {
+                return Err(RouterError::InsufficientLiquidity);
+            }
+
+            let pair = self.resolve_pair(token_a, token_b)?;
+            self.permit_lp(pair, liquidity, deadline, signature)?;
+            self.transfer_lp_to_pair(pair, liquidity)?;
+            let (amount_0, amount_1) = self.burn_lp(pair, to)?;
+
+            let token_0: AccountId = self.query_pair(pair, selectors::PAIR_TOKEN_0)?;
+            let (amount_a, amount_b) = Self::burned_amounts(token_a, token_0, amount_0, amount_1);
+            Self::ensure_min_amounts(amount_a, amount_b, amount_a_min, amount_b_min)?;
+
+            self.env().emit_event(LiquidityRemoved {
+                token_a,
+                token_b,
+                amount_a,
+                amount_b,
+                liquidity,
+                to,
+            });
+
+            Ok((amount_a, amount_b))
+        }
+
         // ========================================
         // OPERAÇÕES DE SWAP
         // ========================================
@@ -273,36 +614,7 @@ pub mod router_contract {
             to: AccountId,
             deadline: u64,
         ) -> Result<Vec<Balance>, RouterError> {
-            // Validações iniciais
-            self.ensure_deadline(deadline)?;
-            self.validate_path(&path)?;
-            
-            if amount_in == 0 {
-                return Err(RouterError::InsufficientOutputAmount);
-            }
-            
-            // Para TDD, implementação simplificada do swap
-            // Em produção, calcularia através de múltiplos pares
-            let amount_out = self.calculate_output_amount(amount_in, &path)?;
-            
-            // Validar slippage protection
-            if amount_out < amount_out_min {
-                return Err(RouterError::InsufficientOutputAmount);
-            }
-            
-            // Retornar amounts array (input + output)
-            let amounts = vec![amount_in, amount_out];
-            
-            // Emitir evento
-            self.env().emit_event(Swap {
-                sender: self.env().caller(),
-                amount_in,
-                amount_out,
-                path: path.clone(),
-                to,
-            });
-            
-            Ok(amounts)
+            self.swap_exact_internal(amount_in, amount_out_min, path, to, deadline, None)
         }
 
         /// Swap com output amount exato
@@ -315,584 +627,2689 @@ pub mod router_contract {
             to: AccountId,
             deadline: u64,
         ) -> Result<Vec<Balance>, RouterError> {
-            // Validações iniciais
-            self.ensure_deadline(deadline)?;
-            self.validate_path(&path)?;
-            
-            if amount_out == 0 {
-                return Err(RouterError::InsufficientOutputAmount);
-            }
-            
-            // Para TDD, implementação simplificada do swap reverso
-            let amount_in = self.calculate_input_amount(amount_out, &path)?;
-            
-            // Validar slippage protection
-            if amount_in > amount_in_max {
-                return Err(RouterError::ExcessiveInputAmount);
-            }
-            
-            // Retornar amounts array (input + output)
-            let amounts = vec![amount_in, amount_out];
-            
-            // Emitir evento
-            self.env().emit_event(Swap {
-                sender: self.env().caller(),
-                amount_in,
-                amount_out,
-                path: path.clone(),
-                to,
-            });
-            
-            Ok(amounts)
+            self.swap_for_exact_internal(amount_out, amount_in_max, path, to, deadline, None)
         }
 
         // ========================================
-        // FUNÇÕES INTERNAS (VALIDAÇÕES E CÁLCULOS)
+        // SWAPS COM REFERRAL (INTEGRADORES)
         // ========================================
 
-        /// Valida se o deadline não expirou
-        fn ensure_deadline(&self, deadline: u64) -> Result<(), RouterError> {
-            let current_time = self.env().block_timestamp();
-            if current_time > deadline {
-                return Err(RouterError::Expired);
-            }
-            Ok(())
+        /// Swap com input exato pagando uma fee de referral ao integrador
+        ///
+        /// # Parâmetros
+        /// - `referral`: `(referrer, referral_fee_bps)` opcional. A fee é descontada
+        ///   de `amount_in` antes do swap e acumulada para o referrer resgatar
+        ///   via `claim_referral_fees`
+        ///
+        /// # Validações
+        /// - `referral_fee_bps` não pode exceder `max_referral_fee_bps`
+        /// - `amount_out_min` é aplicado sobre o output após a fee
+        #[ink(message)]
+        pub fn swap_exact_tokens_for_tokens_with_referral(
+            &mut self,
+            amount_in: Balance,
+            amount_out_min: Balance,
+            path: Vec<AccountId>,
+            to: AccountId,
+            deadline: u64,
+            referral: Option<(AccountId, u16)>,
+        ) -> Result<Vec<Balance>, RouterError> {
+            self.swap_exact_internal(amount_in, amount_out_min, path, to, deadline, referral)
         }
 
-        /// Valida endereços dos tokens
-        fn validate_addresses(&self, token_a: AccountId, token_b: AccountId) -> Result<(), RouterError> {
-            let zero_address = AccountId::from([0u8; 32]);
-            if token_a == zero_address || token_b == zero_address {
-                return Err(RouterError::ZeroAddress);
+        /// Swap com output exato pagando uma fee de referral ao integrador
+        ///
+        /// A fee é cobrada sobre o input bruto, de modo que `amount_in_max`
+        /// limita o total pago (swap + fee).
+        #[ink(message)]
+        pub fn swap_tokens_for_exact_tokens_with_referral(
+            &mut self,
+            amount_out: Balance,
+            amount_in_max: Balance,
+            path: Vec<AccountId>,
+            to: AccountId,
+            deadline: u64,
+            referral: Option<(AccountId, u16)>,
+        ) -> Result<Vec<Balance>, RouterError> {
+            self.swap_for_exact_internal(amount_out, amount_in_max, path, to, deadline, referral)
+        }
+
+        /// Resgata as fees de referral acumuladas pelo caller em `token`
+        ///
+        /// O resgate nunca excede o que o router efetivamente recebeu em `token`.
+        ///
+        /// # Retorna
+        /// - Amount transferido ao referrer
+        #[ink(message)]
+        pub fn claim_referral_fees(&mut self, token: AccountId) -> Result<Balance, RouterError> {
+            let referrer = self.env().caller();
+            let amount = self.referral_fees_of(referrer, token);
+            if amount == 0 {
+                return Err(RouterError::NoReferralFees);
             }
-            if token_a == token_b {
-                return Err(RouterError::IdenticalAddresses);
+
+            let collected = self
+                .referral_fees_collected(token)
+                .checked_sub(amount)
+                .ok_or(RouterError::NoReferralFees)?;
+
+            // Zerar antes da chamada externa (checks-effects-interactions)
+            self.referral_fees.remove((referrer, token));
+            self.referral_fees_collected.insert(token, &collected);
+            self.transfer_token(token, referrer, amount)?;
+
+            self.env().emit_event(ReferralFeesClaimed {
+                referrer,
+                token,
+                amount,
+            });
+
+            Ok(amount)
+        }
+
+        // ========================================
+        // GOVERNANÇA
+        // ========================================
+
+        /// Define o teto da fee de referral (apenas admin)
+        ///
+        /// # Validações
+        /// - Não pode exceder `REFERRAL_FEE_BPS_CEILING` (1%)
+        #[ink(message)]
+        pub fn set_max_referral_fee_bps(&mut self, max_bps: u16) -> Result<(), RouterError> {
+            self.ensure_admin()?;
+            if max_bps > constants::REFERRAL_FEE_BPS_CEILING {
+                return Err(RouterError::ReferralFeeTooHigh);
             }
+
+            let old_max_bps = self.max_referral_fee_bps;
+            self.max_referral_fee_bps = max_bps;
+
+            self.env().emit_event(MaxReferralFeeChanged {
+                old_max_bps,
+                new_max_bps: max_bps,
+            });
+
             Ok(())
         }
 
-        /// Valida path de swap
-        fn validate_path(&self, path: &Vec<AccountId>) -> Result<(), RouterError> {
-            if path.len() < 2 {
-                return Err(RouterError::InvalidPath);
-            }
-            
-            let zero_address = AccountId::from([0u8; 32]);
-            for token in path {
-                if *token == zero_address {
-                    return Err(RouterError::ZeroAddress);
+        /// Atualiza o tier de fee em cache de `account` a partir de `StakingContract::get_stake`
+        ///
+        /// Qualquer um pode chamar. A posição só entra no cache se estiver ativa
+        /// e com o lock em vigor, e substitui a atual se for a mesma posição, se
+        /// o cache tiver vencido ou se o tier for maior ou igual. Uma posição em
+        /// cache que foi encerrada (unstake antecipado) sai do cache, de modo que
+        /// qualquer conta pode revogar o desconto de quem saiu antes do lock.
+        ///
+        /// # Retorna
+        /// - Tier em cache após a atualização
+        #[ink(message)]
+        pub fn refresh_fee_tier(&mut self, account: AccountId, position_id: u32) -> Result<Option<StakingTier>, RouterError> {
+            let staking = self.staking_contract.ok_or(RouterError::StakingUnavailable)?;
+            let position = build_call::<DefaultEnvironment>()
+                .call(staking)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selectors::STAKING_GET_STAKE))
+                        .push_arg(account)
+                        .push_arg(position_id),
+                )
+                .returns::<Option<StakePosition>>()
+                .try_invoke()
+                .map_err(|_| RouterError::StakingUnavailable)?
+                .map_err(|_| RouterError::StakingUnavailable)?;
+
+            let now = self.env().block_timestamp();
+            let cached = Self::next_fee_tier(self.fee_tiers.get(account), position_id, position.as_ref(), now);
+            match cached {
+                Some(entry) => self.fee_tiers.insert(account, &entry),
+                None => {
+                    self.fee_tiers.remove(account);
+                    None
                 }
+            };
+
+            self.env().emit_event(FeeTierRefreshed {
+                account,
+                tier: cached.map(|entry| entry.tier),
+                unlock_time: cached.map(|entry| entry.unlock_time),
+            });
+            Ok(cached.map(|entry| entry.tier))
+        }
+
+        /// Define o Staking Contract e a tabela de descontos por tier (apenas admin)
+        ///
+        /// # Validações
+        /// - Cada desconto no máximo 10000 bps (isenção total)
+        /// - Silver <= Gold <= Platinum
+        #[ink(message)]
+        pub fn set_fee_discounts(
+            &mut self,
+            staking_contract: Option<AccountId>,
+            discounts: FeeDiscounts,
+        ) -> Result<(), RouterError> {
+            self.ensure_admin()?;
+            if u128::from(discounts.platinum_bps) > constants::BPS_DENOMINATOR
+                || discounts.silver_bps > discounts.gold_bps
+                || discounts.gold_bps > discounts.platinum_bps
+            {
+                return Err(RouterError::InvalidFeeDiscount);
             }
-            
+
+            self.staking_contract = staking_contract;
+            self.fee_discounts = discounts;
+
+            self.env().emit_event(FeeDiscountsChanged {
+                staking_contract,
+                discounts,
+            });
+
             Ok(())
         }
 
-        /// Calcula liquidez para add_liquidity (implementação simplificada para TDD)
-        fn calculate_liquidity(&self, amount_a: Balance, amount_b: Balance) -> Result<Balance, RouterError> {
-            if amount_a == 0 || amount_b == 0 {
-                return Err(RouterError::InsufficientLiquidity);
+        /// Marca `aggregator` como confiável (apenas admin)
+        ///
+        /// Swaps vindos de um agregador confiável usam o tier do destinatário
+        /// `to` em vez do caller, preservando o desconto do usuário final.
+        #[ink(message)]
+        pub fn set_trusted_aggregator(&mut self, aggregator: AccountId, trusted: bool) -> Result<(), RouterError> {
+            self.ensure_admin()?;
+            if aggregator == AccountId::from([0u8; 32]) {
+                return Err(RouterError::ZeroAddress);
             }
-            
-            // Fórmula simplificada para TDD: geometric mean
-            let liquidity = self.sqrt(amount_a.checked_mul(amount_b).ok_or(RouterError::InsufficientLiquidity)?);
-            
-            if liquidity <= constants::MINIMUM_LIQUIDITY {
-                return Err(RouterError::InsufficientLiquidity);
+
+            if trusted {
+                self.trusted_aggregators.insert(aggregator, &true);
+            } else {
+                self.trusted_aggregators.remove(aggregator);
             }
-            
-            Ok(liquidity)
+
+            self.env().emit_event(TrustedAggregatorChanged { aggregator, trusted });
+
+            Ok(())
         }
 
-        /// Calcula amount de output para swap (implementação simplificada para TDD)
-        fn calculate_output_amount(&self, amount_in: Balance, path: &Vec<AccountId>) -> Result<Balance, RouterError> {
-            if amount_in == 0 || path.len() < 2 {
-                return Err(RouterError::InsufficientOutputAmount);
+        /// Transfere o admin do router (apenas admin atual)
+        #[ink(message)]
+        pub fn transfer_admin(&mut self, new_admin: AccountId) -> Result<(), RouterError> {
+            self.ensure_admin()?;
+            if new_admin == AccountId::from([0u8; 32]) {
+                return Err(RouterError::ZeroAddress);
             }
+
+            let old_admin = self.admin;
+            self.admin = new_admin;
+
+            self.env().emit_event(AdminTransferred {
+                old_admin,
+                new_admin,
+            });
+
+            Ok(())
+        }
+
+        // ========================================
+        // SPLIT-ROUTE SWAPS
+        // ========================================
+
+        /// Swap com input exato dividido entre múltiplas rotas
+        ///
+        /// Cada leg é um par `(path, amount_in_share)`. Todas as rotas devem
+        /// começar no mesmo token de entrada e terminar no mesmo token de saída.
+        /// O `amount_out_min` é aplicado sobre a soma dos outputs de todas as legs;
+        /// se qualquer leg falhar, nenhuma é executada.
+        ///
+        /// # Retorna
+        /// - Output de cada leg, na mesma ordem de `legs`
+        #[ink(message)]
+        pub fn swap_exact_tokens_for_tokens_split(
+            &mut self,
+            legs: Vec<(Vec<AccountId>, Balance)>,
+            amount_out_min: Balance,
+            to: AccountId,
+            deadline: u64,
+        ) -> Result<Vec<Balance>, RouterError> {
+            // Validações iniciais
+            self.ensure_deadline(deadline)?;
+            self.validate_split_legs(&legs)?;
+
+            // Calcular todas as legs antes de qualquer efeito (atomicidade)
+            let fee_bps = self.get_trader_fee_bps(self.fee_trader(to));
+            let mut amounts_out = Vec::with_capacity(legs.len());
+            let mut total_out: Balance = 0;
+            for (path, amount_in) in legs.iter() {
+                if *amount_in == 0 {
+                    return Err(RouterError::InsufficientOutputAmount);
+                }
+                let amount_out = self.calculate_output_amount(*amount_in, path, fee_bps)?;
+                total_out = total_out
+                    .checked_add(amount_out)
+                    .ok_or(RouterError::InsufficientOutputAmount)?;
+                amounts_out.push(amount_out);
+            }
+
+            // Slippage protection sobre o output agregado
+            if total_out < amount_out_min {
+                return Err(RouterError::InsufficientOutputAmount);
+            }
+
+            // Emitir um evento por leg para indexadores
+            let sender = self.env().caller();
+            for ((path, amount_in), amount_out) in legs.into_iter().zip(amounts_out.iter()) {
+                self.env().emit_event(Swap {
+                    sender,
+                    amount_in,
+                    amount_out: *amount_out,
+                    path,
+                    to,
+                    referrer: None,
+                    referral_fee: 0,
+                });
+            }
+
+            Ok(amounts_out)
+        }
+
+        /// Sugere uma divisão quase ótima de `amount_in` entre 2 ou 3 rotas
+        ///
+        /// # Parâmetros
+        /// - `amount_in`: Amount total a ser trocado
+        /// - `routes`: Path de cada rota; reserves e fee de cada hop são lidas
+        ///   dos pares (`get_reserves` e `current_fee_bps`)
+        ///
+        /// # Retorna
+        /// - `(shares, total_out)`: amount sugerido para cada rota e output total esperado
+        #[ink(message)]
+        pub fn quote_split(
+            &self,
+            amount_in: Balance,
+            routes: Vec<Vec<AccountId>>,
+        ) -> Result<(Vec<Balance>, Balance), RouterError> {
+            if routes.len() < 2 || routes.len() > constants::MAX_SPLIT_LEGS {
+                return Err(RouterError::InvalidSplit);
+            }
+            if amount_in == 0 {
+                return Err(RouterError::InsufficientOutputAmount);
+            }
+
+            let mut route_hops = Vec::with_capacity(routes.len());
+            for path in routes.iter() {
+                self.validate_path(path)?;
+                route_hops.push(self.route_hops(path)?);
+            }
+            Self::split_quote(amount_in, &route_hops)
+        }
+
+        /// Output de um hop em `pair` com as reserves e a fee atuais do par
+        #[ink(message)]
+        pub fn get_amount_out(
+            &self,
+            amount_in: Balance,
+            pair: AccountId,
+            token_in: AccountId,
+        ) -> Result<Balance, RouterError> {
+            let (_, reserve_in, reserve_out) = self.pair_side(pair, token_in)?;
+            let fee_bps = self.pair_fee_bps(pair)?;
+            self.get_amount_out_with_fee(amount_in, reserve_in, reserve_out, fee_bps)
+        }
+
+        /// Calcula o output de um hop com uma fee explícita em bps
+        ///
+        /// A fee deve ser a do par (`PairContract::current_fee_bps`): 50 bps no
+        /// modo estático (995/1000) ou a interpolada no modo dinâmico.
+        #[ink(message)]
+        pub fn get_amount_out_with_fee(
+            &self,
+            amount_in: Balance,
+            reserve_in: Balance,
+            reserve_out: Balance,
+            fee_bps: u16,
+        ) -> Result<Balance, RouterError> {
+            let fee_numerator = constants::BPS_DENOMINATOR
+                .checked_sub(u128::from(fee_bps))
+                .ok_or(RouterError::InvalidFee)?;
+            Self::amount_out_for_fee(amount_in, reserve_in, reserve_out, fee_numerator, constants::BPS_DENOMINATOR)
+        }
+
+        /// Outputs de cada hop de `path` com as reserves e a fee atual de cada par
+        ///
+        /// Consulta o factory e os pares, então reflete a fee dinâmica vigente.
+        #[ink(message)]
+        pub fn get_amounts_out(&self, amount_in: Balance, path: Vec<AccountId>) -> Result<Vec<Balance>, RouterError> {
+            self.validate_path(&path)?;
+
+            let mut amounts = Vec::with_capacity(path.len());
+            amounts.push(amount_in);
+            let mut amount = amount_in;
+            for hop in path.windows(2) {
+                let pair = self.resolve_pair(hop[0], hop[1])?;
+                let (_, reserve_in, reserve_out) = self.pair_side(pair, hop[0])?;
+                let fee_bps = self.pair_fee_bps(pair)?;
+                amount = self.get_amount_out_with_fee(amount, reserve_in, reserve_out, fee_bps)?;
+                amounts.push(amount);
+            }
+
+            Ok(amounts)
+        }
+
+        // ========================================
+        // ZAP (LIQUIDEZ COM UM ÚNICO ATIVO)
+        // ========================================
+
+        /// Adiciona liquidez a `pair` usando apenas `token_in`
+        ///
+        /// Troca a fração ótima do input pelo outro token do par (solução
+        /// fechada que considera a fee) e adiciona o restante como liquidez.
+        ///
+        /// # Parâmetros
+        /// - `token_in`: Token de entrada (deve pertencer ao par)
+        /// - `amount_in`: Amount total de `token_in`
+        /// - `pair`: Endereço do Pair Contract
+        /// - `min_lp`: Quantidade mínima de LP tokens (slippage protection)
+        /// - `to`: Destinatário dos LP tokens
+        /// - `deadline`: Timestamp limite para execução
+        #[ink(message)]
+        pub fn zap_in(
+            &mut self,
+            token_in: AccountId,
+            amount_in: Balance,
+            pair: AccountId,
+            min_lp: Balance,
+            to: AccountId,
+            deadline: u64,
+        ) -> Result<Balance, RouterError> {
+            self.ensure_deadline(deadline)?;
+            if amount_in == 0 {
+                return Err(RouterError::InsufficientLiquidity);
+            }
+            self.zap_in_internal(token_in, amount_in, pair, min_lp, to, deadline)
+        }
+
+        /// Versão de `zap_in` que recebe LUNES nativo e o converte via WNative
+        ///
+        /// O zap usa o WNative efetivamente creditado ao router pelo `deposit`
+        /// (variação de saldo), não o valor transferido.
+        #[ink(message, payable)]
+        pub fn zap_in_native(
+            &mut self,
+            pair: AccountId,
+            min_lp: Balance,
+            to: AccountId,
+            deadline: u64,
+        ) -> Result<Balance, RouterError> {
+            self.ensure_deadline(deadline)?;
+            let amount_in = self.env().transferred_value();
+            if amount_in == 0 {
+                return Err(RouterError::InsufficientLiquidity);
+            }
+
+            let router = self.env().account_id();
+            let before = self.token_balance(self.wnative, router)?;
+            self.wrap_native(amount_in)?;
+            let wrapped = Self::received_amount(before, self.token_balance(self.wnative, router)?)?;
+
+            self.zap_in_internal(self.wnative, wrapped, pair, min_lp, to, deadline)
+        }
+
+        /// Remove liquidez de `pair` recebendo apenas `token_out`
+        ///
+        /// Queima os LP tokens e troca o outro lado do par por `token_out`.
+        ///
+        /// # Parâmetros
+        /// - `pair`: Endereço do Pair Contract
+        /// - `liquidity`: Quantidade de LP tokens a queimar
+        /// - `token_out`: Token de saída (deve pertencer ao par)
+        /// - `min_out`: Amount mínimo de `token_out` (slippage protection)
+        /// - `to`: Destinatário de `token_out`
+        /// - `deadline`: Timestamp limite para execução
+        #[ink(message)]
+        pub fn zap_out(
+            &mut self,
+            pair: AccountId,
+            liquidity: Balance,
+            token_out: AccountId,
+            min_out: Balance,
+            to: AccountId,
+            deadline: u64,
+        ) -> Result<Balance, RouterError> {
+            self.ensure_deadline(deadline)?;
+            if liquidity == 0 {
+                return Err(RouterError::InsufficientLiquidity);
+            }
+            self.zap_out_internal(pair, liquidity, token_out, min_out, to, deadline)
+        }
+
+        /// Versão de `zap_out` que entrega LUNES nativo (unwrap via WNative)
+        ///
+        /// Só é repassado o LUNES que o unwrap efetivamente creditou ao router
+        /// (variação de saldo nativo), de modo que o saldo próprio do router
+        /// nunca é usado para completar o pagamento. `min_out` é aplicado
+        /// sobre esse valor medido.
+        #[ink(message)]
+        pub fn zap_out_native(
+            &mut self,
+            pair: AccountId,
+            liquidity: Balance,
+            min_out: Balance,
+            to: AccountId,
+            deadline: u64,
+        ) -> Result<Balance, RouterError> {
+            self.ensure_deadline(deadline)?;
+            if liquidity == 0 {
+                return Err(RouterError::InsufficientLiquidity);
+            }
+
+            // WNative recebido pelo router no burn + swap
+            let router = self.env().account_id();
+            let wnative_before = self.token_balance(self.wnative, router)?;
+            self.zap_out_internal(pair, liquidity, self.wnative, 0, router, deadline)?;
+            let wrapped = Self::received_amount(wnative_before, self.token_balance(self.wnative, router)?)?;
+
+            // LUNES efetivamente liberado pelo unwrap
+            let native_before = self.env().balance();
+            self.unwrap_native(wrapped)?;
+            let amount_out = Self::received_amount(native_before, self.env().balance())?;
+            if amount_out < min_out {
+                return Err(RouterError::InsufficientOutputAmount);
+            }
+
+            self.env()
+                .transfer(to, amount_out)
+                .map_err(|_| RouterError::TransferFailed)?;
+
+            Ok(amount_out)
+        }
+
+        /// Calcula quanto de `amount_in` deve ser trocado antes de adicionar liquidez
+        ///
+        /// Solução fechada para uma fee `f`, com `r` = reserve do token de entrada:
+        /// `s = (sqrt(r² (2 - f)² + 4 (1 - f) a r) - r (2 - f)) / (2 (1 - f))`
+        ///
+        /// `fee_bps` deve ser a fee do par (`PairContract::current_fee_bps`).
+        #[ink(message)]
+        pub fn get_zap_swap_amount(
+            &self,
+            amount_in: Balance,
+            reserve_in: Balance,
+            fee_bps: u16,
+        ) -> Result<Balance, RouterError> {
+            if reserve_in == 0 {
+                return Err(RouterError::InsufficientLiquidity);
+            }
+
+            // Com d = BPS_DENOMINATOR e n = d - fee_bps: (2 - f) d = d + n e (1 - f) d = n
+            let n = constants::BPS_DENOMINATOR
+                .checked_sub(u128::from(fee_bps))
+                .filter(|n| *n > 0)
+                .ok_or(RouterError::InvalidFee)?;
+            let d = U256::from(constants::BPS_DENOMINATOR);
+            let n = U256::from(n);
+            let r = U256::from(reserve_in);
+            let a = U256::from(amount_in);
+            let two_minus_f = d.saturating_add(n);
+
+            let r_term = r.saturating_mul(two_minus_f);
+            let discriminant = r_term
+                .saturating_mul(r_term)
+                .saturating_add(U256::from(4u8).saturating_mul(n).saturating_mul(d).saturating_mul(a).saturating_mul(r));
+            let swap_amount = discriminant
+                .integer_sqrt()
+                .saturating_sub(r_term)
+                .checked_div(U256::from(2u8).saturating_mul(n))
+                .ok_or(RouterError::InsufficientLiquidity)?;
+
+            u128::try_from(swap_amount).map_err(|_| RouterError::InsufficientLiquidity)
+        }
+
+        // ========================================
+        // MULTICALL
+        // ========================================
+
+        /// Executa várias mensagens do router em uma única transação
+        ///
+        /// Cada item de `calls` é uma mensagem codificada: selector de 4 bytes
+        /// seguido dos argumentos em SCALE. As mensagens são despachadas
+        /// diretamente contra `self`, então o caller e as verificações de
+        /// deadline de cada mensagem são preservados. Se qualquer chamada falhar,
+        /// o erro é retornado e a transação inteira é revertida.
+        ///
+        /// # Retorna
+        /// - O valor de retorno de cada chamada, codificado em SCALE
+        #[ink(message)]
+        pub fn multicall(&mut self, calls: Vec<Vec<u8>>) -> Result<Vec<Vec<u8>>, RouterError> {
+            if calls.is_empty() || calls.len() > constants::MAX_MULTICALL_CALLS {
+                return Err(RouterError::InvalidCall);
+            }
+
+            let mut results = Vec::with_capacity(calls.len());
+            for call in calls.iter() {
+                results.push(self.dispatch_call(call)?);
+            }
+
+            Ok(results)
+        }
+
+        // ========================================
+        // FUNÇÕES INTERNAS (VALIDAÇÕES E CÁLCULOS)
+        // ========================================
+
+        /// Valida se o deadline não expirou
+        fn ensure_deadline(&self, deadline: u64) -> Result<(), RouterError> {
+            let current_time = self.env().block_timestamp();
+            if current_time > deadline {
+                return Err(RouterError::Expired);
+            }
+            Ok(())
+        }
+
+        /// Valida endereços dos tokens
+        fn validate_addresses(&self, token_a: AccountId, token_b: AccountId) -> Result<(), RouterError> {
+            let zero_address = AccountId::from([0u8; 32]);
+            if token_a == zero_address || token_b == zero_address {
+                return Err(RouterError::ZeroAddress);
+            }
+            if token_a == token_b {
+                return Err(RouterError::IdenticalAddresses);
+            }
+            Ok(())
+        }
+
+        /// Valida path de swap
+        fn validate_path(&self, path: &Vec<AccountId>) -> Result<(), RouterError> {
+            if path.len() < 2 {
+                return Err(RouterError::InvalidPath);
+            }
+            
+            let zero_address = AccountId::from([0u8; 32]);
+            for token in path {
+                if *token == zero_address {
+                    return Err(RouterError::ZeroAddress);
+                }
+            }
+            
+            Ok(())
+        }
+
+        /// Conta cujo tier de staking define o desconto do swap
+        fn fee_trader(&self, to: AccountId) -> AccountId {
+            let caller = self.env().caller();
+            if self.is_trusted_aggregator(caller) {
+                to
+            } else {
+                caller
+            }
+        }
+
+        /// Valida se o caller é o admin
+        fn ensure_admin(&self) -> Result<(), RouterError> {
+            if self.env().caller() != self.admin {
+                return Err(RouterError::Unauthorized);
+            }
+            Ok(())
+        }
+
+        /// Lógica comum de swap com input exato (com ou sem referral)
+        fn swap_exact_internal(
+            &mut self,
+            amount_in: Balance,
+            amount_out_min: Balance,
+            path: Vec<AccountId>,
+            to: AccountId,
+            deadline: u64,
+            referral: Option<(AccountId, u16)>,
+        ) -> Result<Vec<Balance>, RouterError> {
+            // Validações iniciais
+            self.ensure_deadline(deadline)?;
+            self.validate_path(&path)?;
+            
+            if amount_in == 0 {
+                return Err(RouterError::InsufficientOutputAmount);
+            }
+
+            // Fee de referral sai do input antes do swap
+            let referral_fee = match referral {
+                Some((referrer, fee_bps)) => {
+                    self.validate_referral(referrer, fee_bps)?;
+                    Self::referral_fee_of(amount_in, fee_bps)?
+                }
+                None => 0,
+            };
+            let swap_amount = amount_in
+                .checked_sub(referral_fee)
+                .ok_or(RouterError::InsufficientOutputAmount)?;
+            
+            // Para TDD, implementação simplificada do swap
+            // Em produção, calcularia através de múltiplos pares
+            let fee_bps = self.get_trader_fee_bps(self.fee_trader(to));
+            let amount_out = self.calculate_output_amount(swap_amount, &path, fee_bps)?;
+            
+            // Validar slippage protection
+            if amount_out < amount_out_min {
+                return Err(RouterError::InsufficientOutputAmount);
+            }
+
+            self.finish_swap(amount_in, amount_out, path, to, referral, referral_fee)
+        }
+
+        /// Lógica comum de swap com output exato (com ou sem referral)
+        fn swap_for_exact_internal(
+            &mut self,
+            amount_out: Balance,
+            amount_in_max: Balance,
+            path: Vec<AccountId>,
+            to: AccountId,
+            deadline: u64,
+            referral: Option<(AccountId, u16)>,
+        ) -> Result<Vec<Balance>, RouterError> {
+            // Validações iniciais
+            self.ensure_deadline(deadline)?;
+            self.validate_path(&path)?;
+            
+            if amount_out == 0 {
+                return Err(RouterError::InsufficientOutputAmount);
+            }
+            
+            // Para TDD, implementação simplificada do swap reverso
+            let fee_bps = self.get_trader_fee_bps(self.fee_trader(to));
+            let swap_amount = self.calculate_input_amount(amount_out, &path, fee_bps)?;
+
+            // Input bruto tal que gross - fee(gross) >= swap_amount
+            let (amount_in, referral_fee) = match referral {
+                Some((referrer, fee_bps)) => {
+                    self.validate_referral(referrer, fee_bps)?;
+                    let amount_in = Self::gross_up_for_fee(swap_amount, fee_bps)?;
+                    let fee = amount_in
+                        .checked_sub(swap_amount)
+                        .ok_or(RouterError::ExcessiveInputAmount)?;
+                    (amount_in, fee)
+                }
+                None => (swap_amount, 0),
+            };
+            
+            // Validar slippage protection
+            if amount_in > amount_in_max {
+                return Err(RouterError::ExcessiveInputAmount);
+            }
+
+            self.finish_swap(amount_in, amount_out, path, to, referral, referral_fee)
+        }
+
+        /// Cobra a fee de referral do caller, contabiliza e emite o evento de swap
+        fn finish_swap(
+            &mut self,
+            amount_in: Balance,
+            amount_out: Balance,
+            path: Vec<AccountId>,
+            to: AccountId,
+            referral: Option<(AccountId, u16)>,
+            referral_fee: Balance,
+        ) -> Result<Vec<Balance>, RouterError> {
+            let referrer = referral.map(|(referrer, _)| referrer);
+            let referral_fee = match referrer {
+                Some(referrer) => {
+                    // Só credita o que de fato entrou no router nesta chamada
+                    let received = self.collect_referral_fee(path[0], referral_fee)?;
+                    self.accrue_referral(referrer, path[0], amount_in, received)?;
+                    received
+                }
+                None => 0,
+            };
+
+            // Emitir evento
+            self.env().emit_event(Swap {
+                sender: self.env().caller(),
+                amount_in,
+                amount_out,
+                path,
+                to,
+                referrer,
+                referral_fee,
+            });
+
+            // Retornar amounts array (input + output)
+            Ok(vec![amount_in, amount_out])
+        }
+
+        /// Valida referrer e fee contra o teto da governança
+        fn validate_referral(&self, referrer: AccountId, fee_bps: u16) -> Result<(), RouterError> {
+            if referrer == AccountId::from([0u8; 32]) {
+                return Err(RouterError::ZeroAddress);
+            }
+            if referrer == self.env().caller() {
+                return Err(RouterError::SelfReferral);
+            }
+            if fee_bps > self.max_referral_fee_bps {
+                return Err(RouterError::ReferralFeeTooHigh);
+            }
+            Ok(())
+        }
+
+        /// Fee de referral sobre um amount bruto (arredondada para baixo)
+        fn referral_fee_of(amount: Balance, fee_bps: u16) -> Result<Balance, RouterError> {
+            amount
+                .checked_mul(Balance::from(fee_bps))
+                .and_then(|value| value.checked_div(constants::BPS_DENOMINATOR))
+                .ok_or(RouterError::InsufficientOutputAmount)
+        }
+
+        /// Menor input bruto cujo líquido (após a fee) cobre `net_amount`
+        fn gross_up_for_fee(net_amount: Balance, fee_bps: u16) -> Result<Balance, RouterError> {
+            let net_bps = constants::BPS_DENOMINATOR
+                .checked_sub(Balance::from(fee_bps))
+                .ok_or(RouterError::ReferralFeeTooHigh)?;
+            // Arredondar para cima: ceil(net * 10000 / (10000 - bps))
+            let gross = net_amount
+                .checked_mul(constants::BPS_DENOMINATOR)
+                .and_then(|value| value.checked_add(net_bps.checked_sub(1)?))
+                .and_then(|value| value.checked_div(net_bps))
+                .ok_or(RouterError::ExcessiveInputAmount)?;
+            Ok(gross)
+        }
+
+        /// Acumula a fee para resgate e atualiza as estatísticas do referrer
+        fn accrue_referral(
+            &mut self,
+            referrer: AccountId,
+            token: AccountId,
+            volume: Balance,
+            fee: Balance,
+        ) -> Result<(), RouterError> {
+            if fee > 0 {
+                let pending = self
+                    .referral_fees_of(referrer, token)
+                    .checked_add(fee)
+                    .ok_or(RouterError::InsufficientOutputAmount)?;
+                self.referral_fees.insert((referrer, token), &pending);
+            }
+
+            let mut stats = self.get_referrer_stats(referrer);
+            stats.swap_count = stats.swap_count.saturating_add(1);
+            stats.total_volume = stats.total_volume.saturating_add(volume);
+            stats.total_fees = stats.total_fees.saturating_add(fee);
+            self.referral_stats.insert(referrer, &stats);
+            Ok(())
+        }
+
+        /// Puxa a fee de referral do caller via `transfer_from`
+        ///
+        /// Retorna o amount efetivamente recebido (variação de saldo do router),
+        /// que é o único valor creditado ao referrer.
+        fn collect_referral_fee(&mut self, token: AccountId, fee: Balance) -> Result<Balance, RouterError> {
+            if fee == 0 {
+                return Ok(0);
+            }
+            if !self.env().is_contract(&token) {
+                return Err(RouterError::TransferFailed);
+            }
+
+            let router = self.env().account_id();
+            let before = self.token_balance(token, router)?;
+            build_call::<DefaultEnvironment>()
+                .call(token)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selectors::PSP22_TRANSFER_FROM))
+                        .push_arg(self.env().caller())
+                        .push_arg(router)
+                        .push_arg(fee)
+                        .push_arg(Vec::<u8>::new()),
+                )
+                .returns::<Result<(), PSP22Error>>()
+                .try_invoke()
+                .map_err(|_| RouterError::TransferFailed)?
+                .map_err(|_| RouterError::TransferFailed)??;
+            let received = self
+                .token_balance(token, router)?
+                .checked_sub(before)
+                .ok_or(RouterError::TransferFailed)?;
+
+            let collected = self
+                .referral_fees_collected(token)
+                .checked_add(received)
+                .ok_or(RouterError::TransferFailed)?;
+            self.referral_fees_collected.insert(token, &collected);
+            Ok(received)
+        }
+
+        /// Saldo PSP22 de `owner` em `token`
+        fn token_balance(&self, token: AccountId, owner: AccountId) -> Result<Balance, RouterError> {
+            build_call::<DefaultEnvironment>()
+                .call(token)
+                .exec_input(ExecutionInput::new(Selector::new(selectors::PSP22_BALANCE_OF)).push_arg(owner))
+                .returns::<Balance>()
+                .try_invoke()
+                .map_err(|_| RouterError::TransferFailed)?
+                .map_err(|_| RouterError::TransferFailed)
+        }
+
+        /// Transfere tokens PSP22 mantidos pelo router
+        fn transfer_token(&self, token: AccountId, to: AccountId, amount: Balance) -> Result<(), RouterError> {
+            build_call::<DefaultEnvironment>()
+                .call(token)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selectors::PSP22_TRANSFER))
+                        .push_arg(to)
+                        .push_arg(amount)
+                        .push_arg(Vec::<u8>::new()),
+                )
+                .returns::<Result<(), PSP22Error>>()
+                .try_invoke()
+                .map_err(|_| RouterError::TransferFailed)?
+                .map_err(|_| RouterError::TransferFailed)??;
+            Ok(())
+        }
+
+        /// Decodifica e executa uma única chamada do multicall
+        fn dispatch_call(&mut self, call: &[u8]) -> Result<Vec<u8>, RouterError> {
+            if call.len() < 4 {
+                return Err(RouterError::InvalidCall);
+            }
+            let (selector, mut input) = call.split_at(4);
+
+            match <[u8; 4]>::try_from(selector).map_err(|_| RouterError::InvalidCall)? {
+                selectors::ADD_LIQUIDITY => {
+                    let (token_a, token_b, amount_a_desired, amount_b_desired, amount_a_min, amount_b_min, to, deadline) =
+                        <(AccountId, AccountId, Balance, Balance, Balance, Balance, AccountId, u64)>::decode_all(&mut input)
+                            .map_err(|_| RouterError::InvalidCall)?;
+                    self.add_liquidity(
+                        token_a,
+                        token_b,
+                        amount_a_desired,
+                        amount_b_desired,
+                        amount_a_min,
+                        amount_b_min,
+                        to,
+                        deadline,
+                    )
+                    .map(|result| result.encode())
+                }
+                selectors::REMOVE_LIQUIDITY => {
+                    let (token_a, token_b, liquidity, amount_a_min, amount_b_min, to, deadline) =
+                        <(AccountId, AccountId, Balance, Balance, Balance, AccountId, u64)>::decode_all(&mut input)
+                            .map_err(|_| RouterError::InvalidCall)?;
+                    self.remove_liquidity(token_a, token_b, liquidity, amount_a_min, amount_b_min, to, deadline)
+                        .map(|result| result.encode())
+                }
+                selectors::SWAP_EXACT_TOKENS_FOR_TOKENS => {
+                    let (amount_in, amount_out_min, path, to, deadline) =
+                        <(Balance, Balance, Vec<AccountId>, AccountId, u64)>::decode_all(&mut input)
+                            .map_err(|_| RouterError::InvalidCall)?;
+                    self.swap_exact_tokens_for_tokens(amount_in, amount_out_min, path, to, deadline)
+                        .map(|result| result.encode())
+                }
+                selectors::SWAP_TOKENS_FOR_EXACT_TOKENS => {
+                    let (amount_out, amount_in_max, path, to, deadline) =
+                        <(Balance, Balance, Vec<AccountId>, AccountId, u64)>::decode_all(&mut input)
+                            .map_err(|_| RouterError::InvalidCall)?;
+                    self.swap_tokens_for_exact_tokens(amount_out, amount_in_max, path, to, deadline)
+                        .map(|result| result.encode())
+                }
+                selectors::SWAP_EXACT_TOKENS_FOR_TOKENS_SPLIT => {
+                    let (legs, amount_out_min, to, deadline) =
+                        <(Vec<(Vec<AccountId>, Balance)>, Balance, AccountId, u64)>::decode_all(&mut input)
+                            .map_err(|_| RouterError::InvalidCall)?;
+                    self.swap_exact_tokens_for_tokens_split(legs, amount_out_min, to, deadline)
+                        .map(|result| result.encode())
+                }
+                selectors::ZAP_IN => {
+                    let (token_in, amount_in, pair, min_lp, to, deadline) =
+                        <(AccountId, Balance, AccountId, Balance, AccountId, u64)>::decode_all(&mut input)
+                            .map_err(|_| RouterError::InvalidCall)?;
+                    self.zap_in(token_in, amount_in, pair, min_lp, to, deadline)
+                        .map(|result| result.encode())
+                }
+                selectors::ZAP_OUT => {
+                    let (pair, liquidity, token_out, min_out, to, deadline) =
+                        <(AccountId, Balance, AccountId, Balance, AccountId, u64)>::decode_all(&mut input)
+                            .map_err(|_| RouterError::InvalidCall)?;
+                    self.zap_out(pair, liquidity, token_out, min_out, to, deadline)
+                        .map(|result| result.encode())
+                }
+                selectors::SWAP_EXACT_TOKENS_FOR_TOKENS_WITH_REFERRAL => {
+                    let (amount_in, amount_out_min, path, to, deadline, referral) =
+                        <(Balance, Balance, Vec<AccountId>, AccountId, u64, Option<(AccountId, u16)>)>::decode_all(&mut input)
+                            .map_err(|_| RouterError::InvalidCall)?;
+                    self.swap_exact_tokens_for_tokens_with_referral(amount_in, amount_out_min, path, to, deadline, referral)
+                        .map(|result| result.encode())
+                }
+                selectors::SWAP_TOKENS_FOR_EXACT_TOKENS_WITH_REFERRAL => {
+                    let (amount_out, amount_in_max, path, to, deadline, referral) =
+                        <(Balance, Balance, Vec<AccountId>, AccountId, u64, Option<(AccountId, u16)>)>::decode_all(&mut input)
+                            .map_err(|_| RouterError::InvalidCall)?;
+                    self.swap_tokens_for_exact_tokens_with_referral(amount_out, amount_in_max, path, to, deadline, referral)
+                        .map(|result| result.encode())
+                }
+                _ => Err(RouterError::InvalidCall),
+            }
+        }
+
+        /// Lógica comum de zap-in (token PSP22 ou LUNES já convertido em WNative)
+        fn zap_in_internal(
+            &mut self,
+            token_in: AccountId,
+            amount_in: Balance,
+            pair: AccountId,
+            min_lp: Balance,
+            to: AccountId,
+            deadline: u64,
+        ) -> Result<Balance, RouterError> {
+            let (token_other, reserve_in, reserve_out) = self.pair_side(pair, token_in)?;
+
+            // Trocar a fração ótima do input pelo outro token, na fee do par
+            let fee_bps = self.pair_fee_bps(pair)?;
+            let swap_amount = self.get_zap_swap_amount(amount_in, reserve_in, fee_bps)?;
+            let amount_other = self.get_amount_out_with_fee(swap_amount, reserve_in, reserve_out, fee_bps)?;
+            let amount_remaining = amount_in
+                .checked_sub(swap_amount)
+                .ok_or(RouterError::InsufficientLiquidity)?;
+
+            self.env().emit_event(Swap {
+                sender: self.env().caller(),
+                amount_in: swap_amount,
+                amount_out: amount_other,
+                path: vec![token_in, token_other],
+                to: self.env().account_id(),
+                referrer: None,
+                referral_fee: 0,
+            });
+
+            // Adicionar o restante + output do swap como liquidez
+            let (_, _, liquidity) = self.add_liquidity(
+                token_in,
+                token_other,
+                amount_remaining,
+                amount_other,
+                0,
+                0,
+                to,
+                deadline,
+            )?;
+
+            if liquidity < min_lp {
+                return Err(RouterError::InsufficientLiquidity);
+            }
+
+            Ok(liquidity)
+        }
+
+        /// Lógica comum de zap-out (token PSP22 ou WNative para unwrap posterior)
+        fn zap_out_internal(
+            &mut self,
+            pair: AccountId,
+            liquidity: Balance,
+            token_out: AccountId,
+            min_out: Balance,
+            to: AccountId,
+            deadline: u64,
+        ) -> Result<Balance, RouterError> {
+            let (token_other, reserve_out, reserve_other) = self.pair_side(pair, token_out)?;
+
+            // Queimar LP tokens recebendo os dois lados no router
+            let router = self.env().account_id();
+            let (amount_kept, amount_other) =
+                self.remove_liquidity(token_out, token_other, liquidity, 0, 0, router, deadline)?;
+
+            // Trocar o outro lado pelas reserves resultantes do burn
+            let reserve_other_after = reserve_other
+                .checked_sub(amount_other)
+                .ok_or(RouterError::InsufficientLiquidity)?;
+            let reserve_out_after = reserve_out
+                .checked_sub(amount_kept)
+                .ok_or(RouterError::InsufficientLiquidity)?;
+            let fee_bps = self.pair_fee_bps(pair)?;
+            let amount_swapped =
+                self.get_amount_out_with_fee(amount_other, reserve_other_after, reserve_out_after, fee_bps)?;
+
+            let amount_out = amount_kept
+                .checked_add(amount_swapped)
+                .ok_or(RouterError::InsufficientOutputAmount)?;
+            if amount_out < min_out {
+                return Err(RouterError::InsufficientOutputAmount);
+            }
+
+            self.env().emit_event(Swap {
+                sender: self.env().caller(),
+                amount_in: amount_other,
+                amount_out: amount_swapped,
+                path: vec![token_other, token_out],
+                to,
+                referrer: None,
+                referral_fee: 0,
+            });
+
+            Ok(amount_out)
+        }
+
+        /// Lê tokens e reserves do par, orientados a partir de `token`
+        ///
+        /// # Retorna
+        /// - `(outro_token, reserve_de_token, reserve_do_outro_token)`
+        fn pair_side(&self, pair: AccountId, token: AccountId) -> Result<(AccountId, Balance, Balance), RouterError> {
+            if pair == AccountId::from([0u8; 32]) {
+                return Err(RouterError::ZeroAddress);
+            }
+
+            let token_0: AccountId = self.query_pair(pair, selectors::PAIR_TOKEN_0)?;
+            let token_1: AccountId = self.query_pair(pair, selectors::PAIR_TOKEN_1)?;
+            let (reserve_0, reserve_1, _): (Balance, Balance, Timestamp) =
+                self.query_pair(pair, selectors::PAIR_GET_RESERVES)?;
+
+            if token == token_0 {
+                Ok((token_1, reserve_0, reserve_1))
+            } else if token == token_1 {
+                Ok((token_0, reserve_1, reserve_0))
+            } else {
+                Err(RouterError::TokenNotInPair)
+            }
+        }
+
+        /// Desconto em bps da fee para um tier de staking
+        fn discount_bps(discounts: &FeeDiscounts, tier: Option<StakingTier>) -> u16 {
+            match tier {
+                Some(StakingTier::Silver) => discounts.silver_bps,
+                Some(StakingTier::Gold) => discounts.gold_bps,
+                Some(StakingTier::Platinum) => discounts.platinum_bps,
+                Some(StakingTier::Bronze) | None => 0,
+            }
+        }
+
+        /// Fee de swap com o desconto de um tier de staking aplicado
+        fn discounted_fee_bps(discounts: &FeeDiscounts, tier: Option<StakingTier>) -> u16 {
+            let discount_bps = Self::discount_bps(discounts, tier);
+            let remaining_bps = constants::BPS_DENOMINATOR.saturating_sub(u128::from(discount_bps));
+            let fee_bps = u128::from(constants::BASE_SWAP_FEE_BPS)
+                .saturating_mul(remaining_bps)
+                .checked_div(constants::BPS_DENOMINATOR)
+                .unwrap_or(0);
+            u16::try_from(fee_bps).unwrap_or(constants::BASE_SWAP_FEE_BPS)
+        }
+
+        /// Tier em cache de `account` enquanto o lock da posição estiver em vigor
+        fn cached_fee_tier(&self, account: AccountId) -> Option<StakingTier> {
+            self.staking_contract?;
+            let now = self.env().block_timestamp();
+            self.fee_tiers
+                .get(account)
+                .filter(|entry| now < entry.unlock_time)
+                .map(|entry| entry.tier)
+        }
+
+        /// Próximo valor do cache de tier dado o resultado de `get_stake`
+        fn next_fee_tier(
+            cached: Option<CachedFeeTier>,
+            position_id: u32,
+            position: Option<&StakePosition>,
+            now: Timestamp,
+        ) -> Option<CachedFeeTier> {
+            let cached = cached.filter(|entry| now < entry.unlock_time);
+            let candidate = position
+                .filter(|stake| stake.active && now < stake.unlock_time())
+                .map(|stake| CachedFeeTier {
+                    tier: stake.fee_tier(),
+                    position_id,
+                    unlock_time: stake.unlock_time(),
+                });
+            match (cached, candidate) {
+                (None, candidate) => candidate,
+                (Some(entry), None) if entry.position_id == position_id => None,
+                (Some(entry), None) => Some(entry),
+                (Some(entry), Some(next)) if entry.position_id == position_id || next.tier >= entry.tier => Some(next),
+                (Some(entry), Some(_)) => Some(entry),
+            }
+        }
+
+        /// Fee de swap atual do par em bps (estática ou dinâmica)
+        ///
+        /// Pares sem `current_fee_bps` cobram a fee estática de 995/1000.
+        fn pair_fee_bps(&self, pair: AccountId) -> Result<u16, RouterError> {
+            Ok(self
+                .query_pair(pair, selectors::PAIR_CURRENT_FEE_BPS)
+                .unwrap_or(constants::PAIR_STATIC_FEE_BPS))
+        }
+
+        /// Resolve o par de dois tokens no factory
+        fn resolve_pair(&self, token_a: AccountId, token_b: AccountId) -> Result<AccountId, RouterError> {
+            build_call::<DefaultEnvironment>()
+                .call(self.factory)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selectors::FACTORY_GET_PAIR))
+                        .push_arg(token_a)
+                        .push_arg(token_b),
+                )
+                .returns::<Option<AccountId>>()
+                .try_invoke()
+                .map_err(|_| RouterError::PairNotExists)?
+                .map_err(|_| RouterError::PairNotExists)?
+                .ok_or(RouterError::PairNotExists)
+        }
+
+        /// Submete ao par o permit do caller aprovando o router a gastar `liquidity` LP
+        fn permit_lp(
+            &self,
+            pair: AccountId,
+            liquidity: Balance,
+            deadline: u64,
+            signature: PermitSignature,
+        ) -> Result<(), RouterError> {
+            build_call::<DefaultEnvironment>()
+                .call(pair)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selectors::PAIR_PERMIT))
+                        .push_arg(self.env().caller())
+                        .push_arg(self.env().account_id())
+                        .push_arg(liquidity)
+                        .push_arg(deadline)
+                        .push_arg(signature),
+                )
+                .returns::<Result<(), u8>>()
+                .try_invoke()
+                .map_err(|_| RouterError::PermitFailed)?
+                .map_err(|_| RouterError::PermitFailed)?
+                .map_err(|_| RouterError::PermitFailed)
+        }
+
+        /// Move `liquidity` LP do caller para o par usando a allowance do permit
+        fn transfer_lp_to_pair(
+            &self,
+            pair: AccountId,
+            liquidity: Balance,
+        ) -> Result<(), RouterError> {
+            build_call::<DefaultEnvironment>()
+                .call(pair)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selectors::PSP22_TRANSFER_FROM))
+                        .push_arg(self.env().caller())
+                        .push_arg(pair)
+                        .push_arg(liquidity)
+                        .push_arg(Vec::<u8>::new()),
+                )
+                .returns::<Result<(), PSP22Error>>()
+                .try_invoke()
+                .map_err(|_| RouterError::TransferFailed)?
+                .map_err(|_| RouterError::TransferFailed)??;
+            Ok(())
+        }
+
+        /// Queima o LP depositado no par e envia os tokens subjacentes a `to`
+        fn burn_lp(
+            &self,
+            pair: AccountId,
+            to: AccountId,
+        ) -> Result<(Balance, Balance), RouterError> {
+            build_call::<DefaultEnvironment>()
+                .call(pair)
+                .exec_input(ExecutionInput::new(Selector::new(selectors::PAIR_BURN)).push_arg(to))
+                .returns::<Result<(Balance, Balance), u8>>()
+                .try_invoke()
+                .map_err(|_| RouterError::InsufficientLiquidity)?
+                .map_err(|_| RouterError::InsufficientLiquidity)?
+                .map_err(|_| RouterError::InsufficientLiquidity)
+        }
+
+        /// Orienta os amounts do `burn` (ordem token_0/token_1) para token_a/token_b
+        fn burned_amounts(
+            token_a: AccountId,
+            token_0: AccountId,
+            amount_0: Balance,
+            amount_1: Balance,
+        ) -> (Balance, Balance) {
+            if token_a == token_0 {
+                (amount_0, amount_1)
+            } else {
+                (amount_1, amount_0)
+            }
+        }
+
+        /// Proteção de slippage sobre os amounts retirados
+        fn ensure_min_amounts(
+            amount_a: Balance,
+            amount_b: Balance,
+            amount_a_min: Balance,
+            amount_b_min: Balance,
+        ) -> Result<(), RouterError> {
+            if amount_a < amount_a_min {
+                return Err(RouterError::InsufficientAAmount);
+            }
+            if amount_b < amount_b_min {
+                return Err(RouterError::InsufficientBAmount);
+            }
+            Ok(())
+        }
+
+        /// Produto constante com fee `fee_numerator / fee_denominator` retida no input
+        fn amount_out_for_fee(
+            amount_in: Balance,
+            reserve_in: Balance,
+            reserve_out: Balance,
+            fee_numerator: u128,
+            fee_denominator: u128,
+        ) -> Result<Balance, RouterError> {
+            if amount_in == 0 {
+                return Err(RouterError::InsufficientOutputAmount);
+            }
+            if reserve_in == 0 || reserve_out == 0 {
+                return Err(RouterError::InsufficientLiquidity);
+            }
+
+            // U256 evita overflow no produto intermediário
+            let amount_in_with_fee = U256::from(amount_in)
+                .saturating_mul(U256::from(fee_numerator));
+            let numerator = amount_in_with_fee.saturating_mul(U256::from(reserve_out));
+            let denominator = U256::from(reserve_in)
+                .saturating_mul(U256::from(fee_denominator))
+                .saturating_add(amount_in_with_fee);
+
+            let amount_out = numerator
+                .checked_div(denominator)
+                .ok_or(RouterError::InsufficientLiquidity)?;
+
+            u128::try_from(amount_out).map_err(|_| RouterError::InsufficientOutputAmount)
+        }
+
+        /// Converte LUNES nativo recebido pelo router em WNative
+        fn wrap_native(&self, amount: Balance) -> Result<(), RouterError> {
+            build_call::<DefaultEnvironment>()
+                .call(self.wnative)
+                .transferred_value(amount)
+                .exec_input(ExecutionInput::new(Selector::new(selectors::WNATIVE_DEPOSIT)))
+                .returns::<Result<(), WnativeError>>()
+                .try_invoke()
+                .map_err(|_| RouterError::TransferFailed)?
+                .map_err(|_| RouterError::TransferFailed)?
+                .map_err(|_| RouterError::TransferFailed)
+        }
+
+        /// Converte WNative do router de volta em LUNES nativo
+        fn unwrap_native(&self, amount: Balance) -> Result<(), RouterError> {
+            build_call::<DefaultEnvironment>()
+                .call(self.wnative)
+                .exec_input(ExecutionInput::new(Selector::new(selectors::WNATIVE_WITHDRAW)).push_arg(amount))
+                .returns::<Result<(), WnativeError>>()
+                .try_invoke()
+                .map_err(|_| RouterError::TransferFailed)?
+                .map_err(|_| RouterError::TransferFailed)?
+                .map_err(|_| RouterError::TransferFailed)
+        }
+
+        /// Variação de saldo entre duas leituras (saldo menor = falha na transferência)
+        fn received_amount(before: Balance, after: Balance) -> Result<Balance, RouterError> {
+            after.checked_sub(before).ok_or(RouterError::TransferFailed)
+        }
+
+        /// Consulta uma mensagem sem argumentos do Pair Contract
+        fn query_pair<R: scale::Decode>(&self, pair: AccountId, selector: [u8; 4]) -> Result<R, RouterError> {
+            build_call::<DefaultEnvironment>()
+                .call(pair)
+                .exec_input(ExecutionInput::new(Selector::new(selector)))
+                .returns::<R>()
+                .try_invoke()
+                .map_err(|_| RouterError::PairNotExists)?
+                .map_err(|_| RouterError::PairNotExists)
+        }
+
+        /// Valida as legs de um split-route swap
+        fn validate_split_legs(&self, legs: &[(Vec<AccountId>, Balance)]) -> Result<(), RouterError> {
+            if legs.is_empty() || legs.len() > constants::MAX_SPLIT_LEGS {
+                return Err(RouterError::InvalidSplit);
+            }
+
+            let (first_path, _) = &legs[0];
+            self.validate_path(first_path)?;
+            let token_in = first_path[0];
+            let token_out = first_path[first_path.len().saturating_sub(1)];
+
+            for (path, _) in legs.iter().skip(1) {
+                self.validate_path(path)?;
+                if path[0] != token_in || path[path.len().saturating_sub(1)] != token_out {
+                    return Err(RouterError::InvalidSplit);
+                }
+            }
+
+            Ok(())
+        }
+
+        /// Reserves `(reserve_in, reserve_out)` e fee de cada hop de `path`
+        fn route_hops(&self, path: &[AccountId]) -> Result<Vec<(Balance, Balance, u16)>, RouterError> {
+            let mut hops = Vec::with_capacity(path.len().saturating_sub(1));
+            for hop in path.windows(2) {
+                let pair = self.resolve_pair(hop[0], hop[1])?;
+                let (_, reserve_in, reserve_out) = self.pair_side(pair, hop[0])?;
+                hops.push((reserve_in, reserve_out, self.pair_fee_bps(pair)?));
+            }
+            Ok(hops)
+        }
+
+        /// Output de uma rota completa dado as reserves e a fee de cada hop
+        fn route_output(amount_in: Balance, hops: &[(Balance, Balance, u16)]) -> Result<Balance, RouterError> {
+            let mut amount = amount_in;
+            for (reserve_in, reserve_out, fee_bps) in hops {
+                let fee_numerator = constants::BPS_DENOMINATOR
+                    .checked_sub(u128::from(*fee_bps))
+                    .ok_or(RouterError::InvalidFee)?;
+                amount = Self::amount_out_for_fee(
+                    amount,
+                    *reserve_in,
+                    *reserve_out,
+                    fee_numerator,
+                    constants::BPS_DENOMINATOR,
+                )?;
+            }
+            Ok(amount)
+        }
+
+        /// Divisão gulosa de `amount_in` entre rotas com hops já resolvidos
+        ///
+        /// Cada fatia vai para a rota com maior output marginal. Como o output
+        /// de cada rota é côncavo no input, o resultado converge para a divisão
+        /// ótima conforme a granularidade aumenta.
+        fn split_quote(
+            amount_in: Balance,
+            routes: &[Vec<(Balance, Balance, u16)>],
+        ) -> Result<(Vec<Balance>, Balance), RouterError> {
+            if routes.iter().any(|hops| hops.is_empty()) {
+                return Err(RouterError::InvalidPath);
+            }
+
+            let step = amount_in
+                .checked_div(constants::SPLIT_QUOTE_STEPS)
+                .unwrap_or(0)
+                .max(1);
+            let mut shares: Vec<Balance> = vec![0; routes.len()];
+            let mut outputs: Vec<Balance> = vec![0; routes.len()];
+            let mut remaining = amount_in;
+
+            while remaining > 0 {
+                let chunk = step.min(remaining);
+                let mut best: Option<(usize, Balance, Balance)> = None;
+
+                for (index, hops) in routes.iter().enumerate() {
+                    let candidate_in = shares[index]
+                        .checked_add(chunk)
+                        .ok_or(RouterError::InsufficientOutputAmount)?;
+                    let candidate_out = match Self::route_output(candidate_in, hops) {
+                        Ok(out) => out,
+                        Err(_) => continue, // Rota sem liquidez suficiente para esta fatia
+                    };
+                    let gain = candidate_out.saturating_sub(outputs[index]);
+                    if best.is_none_or(|(_, best_gain, _)| gain > best_gain) {
+                        best = Some((index, gain, candidate_out));
+                    }
+                }
+
+                let (index, _, candidate_out) = best.ok_or(RouterError::InsufficientLiquidity)?;
+                shares[index] = shares[index]
+                    .checked_add(chunk)
+                    .ok_or(RouterError::InsufficientOutputAmount)?;
+                outputs[index] = candidate_out;
+                remaining = remaining
+                    .checked_sub(chunk)
+                    .ok_or(RouterError::InsufficientOutputAmount)?;
+            }
+
+            let total_out = outputs
+                .iter()
+                .try_fold(0u128, |acc, out| acc.checked_add(*out))
+                .ok_or(RouterError::InsufficientOutputAmount)?;
+
+            Ok((shares, total_out))
+        }
+
+        /// Calcula liquidez para add_liquidity (implementação simplificada para TDD)
+        fn calculate_liquidity(&self, amount_a: Balance, amount_b: Balance) -> Result<Balance, RouterError> {
+            if amount_a == 0 || amount_b == 0 {
+                return Err(RouterError::InsufficientLiquidity);
+            }
+            
+            // Fórmula simplificada para TDD: geometric mean
+            let liquidity = self.sqrt(amount_a.checked_mul(amount_b).ok_or(RouterError::InsufficientLiquidity)?);
+            
+            if liquidity <= constants::MINIMUM_LIQUIDITY {
+                return Err(RouterError::InsufficientLiquidity);
+            }
+            
+            Ok(liquidity)
+        }
+
+        /// Calcula amount de output para swap (implementação simplificada para TDD)
+        fn calculate_output_amount(&self, amount_in: Balance, path: &Vec<AccountId>, fee_bps: u16) -> Result<Balance, RouterError> {
+            if amount_in == 0 || path.len() < 2 {
+                return Err(RouterError::InsufficientOutputAmount);
+            }
+            
+            // Implementação simplificada para TDD
+            // Em produção, usaria as reserves dos pares e fórmula AMM
+            let fee_numerator = constants::BPS_DENOMINATOR
+                .checked_sub(u128::from(fee_bps))
+                .ok_or(RouterError::InvalidFee)?;
+            let amount_with_fee = amount_in
+                .checked_mul(fee_numerator)
+                .ok_or(RouterError::InsufficientOutputAmount)?
+                .checked_div(constants::BPS_DENOMINATOR)
+                .ok_or(RouterError::InsufficientOutputAmount)?;
+            
+            // Simular taxa de câmbio 1:1 para TDD
+            Ok(amount_with_fee)
+        }
+
+        /// Calcula amount de input para swap reverso (implementação simplificada para TDD)
+        fn calculate_input_amount(&self, amount_out: Balance, path: &Vec<AccountId>, fee_bps: u16) -> Result<Balance, RouterError> {
+            if amount_out == 0 || path.len() < 2 {
+                return Err(RouterError::ExcessiveInputAmount);
+            }
+            
+            // Implementação simplificada para TDD - swap reverso
+            let fee_numerator = constants::BPS_DENOMINATOR
+                .checked_sub(u128::from(fee_bps))
+                .ok_or(RouterError::InvalidFee)?;
+            let amount_in = amount_out
+                .checked_mul(constants::BPS_DENOMINATOR)
+                .ok_or(RouterError::ExcessiveInputAmount)?
+                .checked_div(fee_numerator)
+                .ok_or(RouterError::ExcessiveInputAmount)?;
+            
+            Ok(amount_in)
+        }
+
+        /// Implementação da raiz quadrada (Babylonian method)
+        fn sqrt(&self, value: Balance) -> Balance {
+            if value == 0 {
+                return 0;
+            }
+            
+            let mut x = value;
+            let mut y = value.checked_add(1).and_then(|sum| sum.checked_div(2)).unwrap_or(1);
+            
+            while y < x {
+                x = y;
+                y = value.checked_div(x).and_then(|div| div.checked_add(x)).and_then(|sum| sum.checked_div(2)).unwrap_or(x);
+            }
+            
+            x
+        }
+    }
+
+    // ========================================
+    // TESTES UNITÁRIOS TDD
+    // ========================================
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::DefaultEnvironment;
+
+        fn default_accounts() -> ink::env::test::DefaultAccounts<DefaultEnvironment> {
+            ink::env::test::default_accounts::<DefaultEnvironment>()
+        }
+
+        fn set_sender(sender: AccountId) {
+            ink::env::test::set_caller::<DefaultEnvironment>(sender);
+        }
+
+        fn set_timestamp(timestamp: u64) {
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(timestamp);
+        }
+
+        // ========================================
+        // TESTES BÁSICOS DE INICIALIZAÇÃO
+        // ========================================
+
+        #[ink::test]
+        fn test_new_router_initializes_correctly() {
+            let accounts = default_accounts();
+            let router = RouterContract::new(accounts.bob, accounts.charlie);
+            
+            // GREEN: Factory e WNative devem estar configurados corretamente
+            assert_eq!(router.factory(), accounts.bob);
+            assert_eq!(router.wnative(), accounts.charlie);
+        }
+
+        // ========================================
+        // TESTES DE VALIDAÇÃO DE DEADLINE
+        // ========================================
+
+        #[ink::test]
+        fn test_expired_deadline_fails() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            set_timestamp(1000); // Current time
+            
+            let mut router = RouterContract::new(accounts.bob, accounts.charlie);
+            
+            // RED: Deadline no passado deve falhar
+            let result = router.add_liquidity(
+                accounts.django, // token_a
+                accounts.eve,    // token_b
+                100,             // amount_a_desired
+                100,             // amount_b_desired
+                90,              // amount_a_min
+                90,              // amount_b_min
+                accounts.alice,  // to
+                500,             // deadline (no passado)
+            );
+            
+            assert!(result.is_err());
+            assert_eq!(result.unwrap_err(), RouterError::Expired);
+        }
+
+        // ========================================
+        // TESTES DE ADD LIQUIDITY
+        // ========================================
+
+        #[ink::test]
+        fn test_add_liquidity_success() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            set_timestamp(1000);
+            
+            let mut router = RouterContract::new(accounts.bob, accounts.charlie);
+            
+            // GREEN: Add liquidity com parâmetros válidos deve funcionar
+            let result = router.add_liquidity(
+                accounts.django, // token_a
+                accounts.eve,    // token_b
+                100,             // amount_a_desired
+                200,             // amount_b_desired
+                90,              // amount_a_min
+                180,             // amount_b_min
+                accounts.alice,  // to
+                2000,            // deadline (futuro)
+            );
+            
+            assert!(result.is_ok());
+            let (amount_a, amount_b, liquidity) = result.unwrap();
+            assert_eq!(amount_a, 100);
+            assert_eq!(amount_b, 200);
+            assert!(liquidity > 0);
+        }
+
+        #[ink::test]
+        fn test_add_liquidity_insufficient_a_amount() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            set_timestamp(1000);
+            
+            let mut router = RouterContract::new(accounts.bob, accounts.charlie);
+            
+            // RED: amount_a_min muito alto deve falhar
+            let result = router.add_liquidity(
+                accounts.django, // token_a
+                accounts.eve,    // token_b
+                100,             // amount_a_desired
+                200,             // amount_b_desired
+                150,             // amount_a_min (muito alto)
+                180,             // amount_b_min
+                accounts.alice,  // to
+                2000,            // deadline
+            );
+            
+            assert!(result.is_err());
+            assert_eq!(result.unwrap_err(), RouterError::InsufficientAAmount);
+        }
+
+        #[ink::test]
+        fn test_add_liquidity_identical_addresses() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            set_timestamp(1000);
+            
+            let mut router = RouterContract::new(accounts.bob, accounts.charlie);
+            
+            // RED: Tokens idênticos devem falhar
+            let result = router.add_liquidity(
+                accounts.django, // token_a
+                accounts.django, // token_b (igual ao A)
+                100,             // amount_a_desired
+                200,             // amount_b_desired
+                90,              // amount_a_min
+                180,             // amount_b_min
+                accounts.alice,  // to
+                2000,            // deadline
+            );
+            
+            assert!(result.is_err());
+            assert_eq!(result.unwrap_err(), RouterError::IdenticalAddresses);
+        }
+
+        // ========================================
+        // TESTES DE REMOVE LIQUIDITY
+        // ========================================
+
+        #[ink::test]
+        fn test_remove_liquidity_success() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            set_timestamp(1000);
+            
+            let mut router = RouterContract::new(accounts.bob, accounts.charlie);
+            
+            // GREEN: Remove liquidity com parâmetros válidos deve funcionar
+            let result = router.remove_liquidity(
+                accounts.django, // token_a
+                accounts.eve,    // token_b
+                200,             // liquidity
+                90,              // amount_a_min
+                90,              // amount_b_min
+                accounts.alice,  // to
+                2000,            // deadline
+            );
             
-            // Implementação simplificada para TDD
-            // Em produção, usaria as reserves dos pares e fórmula AMM
-            let amount_with_fee = amount_in
-                .checked_mul(constants::FEE_NUMERATOR)
-                .ok_or(RouterError::InsufficientOutputAmount)?
-                .checked_div(constants::FEE_DENOMINATOR)
-                .ok_or(RouterError::InsufficientOutputAmount)?;
+            assert!(result.is_ok());
+            let (amount_a, amount_b) = result.unwrap();
+            assert_eq!(amount_a, 100); // liquidity / 2
+            assert_eq!(amount_b, 100); // liquidity / 2
+        }
+
+        #[ink::test]
+        fn test_remove_liquidity_with_permit_validations() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            set_timestamp(1000);
             
-            // Simular taxa de câmbio 1:1 para TDD
-            Ok(amount_with_fee)
+            let mut router = RouterContract::new(accounts.bob, accounts.charlie);
+            let signature = PermitSignature::Sr25519([0u8; 64]);
+            
+            // RED: Deadline expirado antes de submeter o permit
+            assert_eq!(
+                router.remove_liquidity_with_permit(
+                    accounts.django, accounts.eve, 200, 90, 90, accounts.alice, 999, signature.clone(),
+                ),
+                Err(RouterError::Expired)
+            );
+            
+            // RED: Zero liquidity
+            assert_eq!(
+                router.remove_liquidity_with_permit(
+                    accounts.django, accounts.eve, 0, 90, 90, accounts.alice, 2000, signature.clone(),
+                ),
+                Err(RouterError::InsufficientLiquidity)
+            );
+            
+            // RED: Tokens idênticos
+            assert_eq!(
+                router.remove_liquidity_with_permit(
+                    accounts.django, accounts.django, 200, 90, 90, accounts.alice, 2000, signature,
+                ),
+                Err(RouterError::IdenticalAddresses)
+            );
         }
 
-        /// Calcula amount de input para swap reverso (implementação simplificada para TDD)
-        fn calculate_input_amount(&self, amount_out: Balance, path: &Vec<AccountId>) -> Result<Balance, RouterError> {
-            if amount_out == 0 || path.len() < 2 {
-                return Err(RouterError::ExcessiveInputAmount);
-            }
+        #[ink::test]
+        fn test_remove_liquidity_with_permit_burn_amounts() {
+            let accounts = default_accounts();
+
+            // GREEN: Amounts do burn orientados conforme a ordem do caller
+            assert_eq!(
+                RouterContract::burned_amounts(accounts.django, accounts.django, 70, 30),
+                (70, 30)
+            );
+            assert_eq!(
+                RouterContract::burned_amounts(accounts.eve, accounts.django, 70, 30),
+                (30, 70)
+            );
+
+            // RED: Slippage aplicado sobre o que o par realmente devolveu
+            assert_eq!(
+                RouterContract::ensure_min_amounts(70, 30, 71, 0),
+                Err(RouterError::InsufficientAAmount)
+            );
+            assert_eq!(
+                RouterContract::ensure_min_amounts(70, 30, 70, 31),
+                Err(RouterError::InsufficientBAmount)
+            );
+            assert_eq!(RouterContract::ensure_min_amounts(70, 30, 70, 30), Ok(()));
+        }
+
+        #[ink::test]
+        fn test_remove_liquidity_zero_liquidity() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            set_timestamp(1000);
             
-            // Implementação simplificada para TDD - swap reverso
-            let amount_in = amount_out
-                .checked_mul(constants::FEE_DENOMINATOR)
-                .ok_or(RouterError::ExcessiveInputAmount)?
-                .checked_div(constants::FEE_NUMERATOR)
-                .ok_or(RouterError::ExcessiveInputAmount)?;
+            let mut router = RouterContract::new(accounts.bob, accounts.charlie);
             
-            Ok(amount_in)
+            // RED: Zero liquidity deve falhar
+            let result = router.remove_liquidity(
+                accounts.django, // token_a
+                accounts.eve,    // token_b
+                0,               // liquidity (zero)
+                90,              // amount_a_min
+                90,              // amount_b_min
+                accounts.alice,  // to
+                2000,            // deadline
+            );
+            
+            assert!(result.is_err());
+            assert_eq!(result.unwrap_err(), RouterError::InsufficientLiquidity);
         }
 
-        /// Implementação da raiz quadrada (Babylonian method)
-        fn sqrt(&self, value: Balance) -> Balance {
-            if value == 0 {
-                return 0;
-            }
+        #[ink::test]
+        fn test_remove_liquidity_insufficient_b_amount() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            set_timestamp(1000);
             
-            let mut x = value;
-            let mut y = value.checked_add(1).and_then(|sum| sum.checked_div(2)).unwrap_or(1);
+            let mut router = RouterContract::new(accounts.bob, accounts.charlie);
             
-            while y < x {
-                x = y;
-                y = value.checked_div(x).and_then(|div| div.checked_add(x)).and_then(|sum| sum.checked_div(2)).unwrap_or(x);
-            }
+            // RED: amount_b_min muito alto deve falhar
+            let result = router.remove_liquidity(
+                accounts.django, // token_a
+                accounts.eve,    // token_b
+                200,             // liquidity
+                90,              // amount_a_min
+                150,             // amount_b_min (muito alto)
+                accounts.alice,  // to
+                2000,            // deadline
+            );
             
-            x
+            assert!(result.is_err());
+            assert_eq!(result.unwrap_err(), RouterError::InsufficientBAmount);
         }
-    }
 
-    // ========================================
-    // TESTES UNITÁRIOS TDD
-    // ========================================
+        // ========================================
+        // TESTES DE SWAP EXACT TOKENS FOR TOKENS
+        // ========================================
 
-    #[cfg(test)]
-    mod tests {
-        use super::*;
-        use ink::env::DefaultEnvironment;
+        #[ink::test]
+        fn test_swap_exact_tokens_success() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            set_timestamp(1000);
+            
+            let mut router = RouterContract::new(accounts.bob, accounts.charlie);
+            
+            let path = vec![accounts.django, accounts.eve];
+            
+            // GREEN: Swap com parâmetros válidos deve funcionar
+            let result = router.swap_exact_tokens_for_tokens(
+                100,            // amount_in
+                90,             // amount_out_min
+                path,           // path
+                accounts.alice, // to
+                2000,           // deadline
+            );
+            
+            assert!(result.is_ok());
+            let amounts = result.unwrap();
+            assert_eq!(amounts.len(), 2);
+            assert_eq!(amounts[0], 100); // amount_in
+            assert!(amounts[1] >= 90);   // amount_out >= min
+        }
 
-        fn default_accounts() -> ink::env::test::DefaultAccounts<DefaultEnvironment> {
-            ink::env::test::default_accounts::<DefaultEnvironment>()
+        #[ink::test]
+        fn test_swap_exact_tokens_zero_input() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            set_timestamp(1000);
+            
+            let mut router = RouterContract::new(accounts.bob, accounts.charlie);
+            
+            let path = vec![accounts.django, accounts.eve];
+            
+            // RED: Zero input deve falhar
+            let result = router.swap_exact_tokens_for_tokens(
+                0,              // amount_in (zero)
+                90,             // amount_out_min
+                path,           // path
+                accounts.alice, // to
+                2000,           // deadline
+            );
+            
+            assert!(result.is_err());
+            assert_eq!(result.unwrap_err(), RouterError::InsufficientOutputAmount);
         }
 
-        fn set_sender(sender: AccountId) {
-            ink::env::test::set_caller::<DefaultEnvironment>(sender);
+        #[ink::test]
+        fn test_swap_exact_tokens_insufficient_output() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            set_timestamp(1000);
+            
+            let mut router = RouterContract::new(accounts.bob, accounts.charlie);
+            
+            let path = vec![accounts.django, accounts.eve];
+            
+            // RED: amount_out_min muito alto deve falhar (slippage protection)
+            let result = router.swap_exact_tokens_for_tokens(
+                100,            // amount_in
+                150,            // amount_out_min (muito alto)
+                path,           // path
+                accounts.alice, // to
+                2000,           // deadline
+            );
+            
+            assert!(result.is_err());
+            assert_eq!(result.unwrap_err(), RouterError::InsufficientOutputAmount);
         }
 
-        fn set_timestamp(timestamp: u64) {
-            ink::env::test::set_block_timestamp::<DefaultEnvironment>(timestamp);
+        #[ink::test]
+        fn test_swap_exact_tokens_invalid_path() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            set_timestamp(1000);
+            
+            let mut router = RouterContract::new(accounts.bob, accounts.charlie);
+            
+            let path = vec![accounts.django]; // Path muito curto
+            
+            // RED: Path inválido deve falhar
+            let result = router.swap_exact_tokens_for_tokens(
+                100,            // amount_in
+                90,             // amount_out_min
+                path,           // path (inválido)
+                accounts.alice, // to
+                2000,           // deadline
+            );
+            
+            assert!(result.is_err());
+            assert_eq!(result.unwrap_err(), RouterError::InvalidPath);
         }
 
         // ========================================
-        // TESTES BÁSICOS DE INICIALIZAÇÃO
+        // TESTES DE SWAP TOKENS FOR EXACT TOKENS
         // ========================================
 
         #[ink::test]
-        fn test_new_router_initializes_correctly() {
+        fn test_swap_tokens_for_exact_success() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            set_timestamp(1000);
+            
+            let mut router = RouterContract::new(accounts.bob, accounts.charlie);
+            
+            let path = vec![accounts.django, accounts.eve];
+            
+            // GREEN: Swap reverso com parâmetros válidos deve funcionar
+            let result = router.swap_tokens_for_exact_tokens(
+                100,            // amount_out
+                110,            // amount_in_max
+                path,           // path
+                accounts.alice, // to
+                2000,           // deadline
+            );
+            
+            assert!(result.is_ok());
+            let amounts = result.unwrap();
+            assert_eq!(amounts.len(), 2);
+            assert!(amounts[0] <= 110); // amount_in <= max
+            assert_eq!(amounts[1], 100); // amount_out exato
+        }
+
+        #[ink::test]
+        fn test_swap_tokens_for_exact_zero_output() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            set_timestamp(1000);
+            
+            let mut router = RouterContract::new(accounts.bob, accounts.charlie);
+            
+            let path = vec![accounts.django, accounts.eve];
+            
+            // RED: Zero output deve falhar
+            let result = router.swap_tokens_for_exact_tokens(
+                0,              // amount_out (zero)
+                110,            // amount_in_max
+                path,           // path
+                accounts.alice, // to
+                2000,           // deadline
+            );
+            
+            assert!(result.is_err());
+            assert_eq!(result.unwrap_err(), RouterError::InsufficientOutputAmount);
+        }
+
+        #[ink::test]
+        fn test_swap_tokens_for_exact_excessive_input() {
             let accounts = default_accounts();
-            let router = RouterContract::new(accounts.bob, accounts.charlie);
+            set_sender(accounts.alice);
+            set_timestamp(1000);
             
-            // GREEN: Factory e WNative devem estar configurados corretamente
-            assert_eq!(router.factory(), accounts.bob);
-            assert_eq!(router.wnative(), accounts.charlie);
+            let mut router = RouterContract::new(accounts.bob, accounts.charlie);
+            
+            let path = vec![accounts.django, accounts.eve];
+            
+            // RED: amount_in_max muito baixo deve falhar (slippage protection)
+            let result = router.swap_tokens_for_exact_tokens(
+                100,            // amount_out
+                90,             // amount_in_max (muito baixo)
+                path,           // path
+                accounts.alice, // to
+                2000,           // deadline
+            );
+            
+            assert!(result.is_err());
+            assert_eq!(result.unwrap_err(), RouterError::ExcessiveInputAmount);
         }
 
         // ========================================
-        // TESTES DE VALIDAÇÃO DE DEADLINE
+        // TESTES DE VALIDAÇÃO DE PATH E EDGE CASES
         // ========================================
 
         #[ink::test]
-        fn test_expired_deadline_fails() {
+        fn test_path_with_zero_address() {
             let accounts = default_accounts();
             set_sender(accounts.alice);
-            set_timestamp(1000); // Current time
+            set_timestamp(1000);
             
             let mut router = RouterContract::new(accounts.bob, accounts.charlie);
             
-            // RED: Deadline no passado deve falhar
-            let result = router.add_liquidity(
-                accounts.django, // token_a
-                accounts.eve,    // token_b
-                100,             // amount_a_desired
-                100,             // amount_b_desired
-                90,              // amount_a_min
-                90,              // amount_b_min
-                accounts.alice,  // to
-                500,             // deadline (no passado)
+            let zero_address = AccountId::from([0u8; 32]);
+            let path = vec![accounts.django, zero_address]; // Zero address no path
+            
+            // RED: Path com zero address deve falhar
+            let result = router.swap_exact_tokens_for_tokens(
+                100,            // amount_in
+                90,             // amount_out_min
+                path,           // path (com zero address)
+                accounts.alice, // to
+                2000,           // deadline
             );
             
             assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), RouterError::Expired);
+            assert_eq!(result.unwrap_err(), RouterError::ZeroAddress);
+        }
+
+        #[ink::test]
+        fn test_calculate_liquidity_edge_cases() {
+            let accounts = default_accounts();
+            let router = RouterContract::new(accounts.bob, accounts.charlie);
+            
+            // RED: Zero amounts devem falhar
+            let result = router.calculate_liquidity(0, 100);
+            assert!(result.is_err());
+            assert_eq!(result.unwrap_err(), RouterError::InsufficientLiquidity);
+            
+            let result = router.calculate_liquidity(100, 0);
+            assert!(result.is_err());
+            assert_eq!(result.unwrap_err(), RouterError::InsufficientLiquidity);
+            
+            // GREEN: Amounts válidos devem funcionar
+            let result = router.calculate_liquidity(100, 200);
+            assert!(result.is_ok());
+            assert!(result.unwrap() > constants::MINIMUM_LIQUIDITY);
         }
 
         // ========================================
-        // TESTES DE ADD LIQUIDITY
+        // TESTES DE SPLIT-ROUTE SWAP
         // ========================================
 
         #[ink::test]
-        fn test_add_liquidity_success() {
+        fn test_split_swap_success() {
             let accounts = default_accounts();
             set_sender(accounts.alice);
             set_timestamp(1000);
-            
+
             let mut router = RouterContract::new(accounts.bob, accounts.charlie);
-            
-            // GREEN: Add liquidity com parâmetros válidos deve funcionar
-            let result = router.add_liquidity(
-                accounts.django, // token_a
-                accounts.eve,    // token_b
-                100,             // amount_a_desired
-                200,             // amount_b_desired
-                90,              // amount_a_min
-                180,             // amount_b_min
-                accounts.alice,  // to
-                2000,            // deadline (futuro)
+
+            let direct = vec![accounts.django, accounts.eve];
+            let via_frank = vec![accounts.django, accounts.frank, accounts.eve];
+
+            // GREEN: Duas legs com mesmo token de entrada e saída
+            let result = router.swap_exact_tokens_for_tokens_split(
+                vec![(direct, 600), (via_frank, 400)],
+                990,            // amount_out_min agregado
+                accounts.alice, // to
+                2000,           // deadline
             );
-            
+
             assert!(result.is_ok());
-            let (amount_a, amount_b, liquidity) = result.unwrap();
-            assert_eq!(amount_a, 100);
-            assert_eq!(amount_b, 200);
-            assert!(liquidity > 0);
+            let amounts = result.unwrap();
+            assert_eq!(amounts.len(), 2);
+            assert!(amounts.iter().sum::<Balance>() >= 990);
         }
 
         #[ink::test]
-        fn test_add_liquidity_insufficient_a_amount() {
+        fn test_split_swap_enforces_aggregate_minimum() {
             let accounts = default_accounts();
             set_sender(accounts.alice);
             set_timestamp(1000);
-            
+
             let mut router = RouterContract::new(accounts.bob, accounts.charlie);
-            
-            // RED: amount_a_min muito alto deve falhar
-            let result = router.add_liquidity(
-                accounts.django, // token_a
-                accounts.eve,    // token_b
-                100,             // amount_a_desired
-                200,             // amount_b_desired
-                150,             // amount_a_min (muito alto)
-                180,             // amount_b_min
-                accounts.alice,  // to
-                2000,            // deadline
+
+            // RED: Cada leg isolada seria aceitável, mas o total não atinge o mínimo
+            let result = router.swap_exact_tokens_for_tokens_split(
+                vec![
+                    (vec![accounts.django, accounts.eve], 500),
+                    (vec![accounts.django, accounts.frank, accounts.eve], 500),
+                ],
+                1000,           // amount_out_min agregado (acima do possível)
+                accounts.alice, // to
+                2000,           // deadline
             );
-            
-            assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), RouterError::InsufficientAAmount);
+
+            assert_eq!(result.unwrap_err(), RouterError::InsufficientOutputAmount);
         }
 
         #[ink::test]
-        fn test_add_liquidity_identical_addresses() {
+        fn test_split_swap_rejects_mismatched_legs() {
             let accounts = default_accounts();
             set_sender(accounts.alice);
             set_timestamp(1000);
-            
+
             let mut router = RouterContract::new(accounts.bob, accounts.charlie);
-            
-            // RED: Tokens idênticos devem falhar
-            let result = router.add_liquidity(
-                accounts.django, // token_a
-                accounts.django, // token_b (igual ao A)
-                100,             // amount_a_desired
-                200,             // amount_b_desired
-                90,              // amount_a_min
-                180,             // amount_b_min
-                accounts.alice,  // to
-                2000,            // deadline
+
+            // RED: Legs terminando em tokens diferentes
+            let result = router.swap_exact_tokens_for_tokens_split(
+                vec![
+                    (vec![accounts.django, accounts.eve], 500),
+                    (vec![accounts.django, accounts.frank], 500),
+                ],
+                0,
+                accounts.alice,
+                2000,
             );
-            
-            assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), RouterError::IdenticalAddresses);
+            assert_eq!(result.unwrap_err(), RouterError::InvalidSplit);
+
+            // RED: Mais legs do que o permitido
+            let path = vec![accounts.django, accounts.eve];
+            let result = router.swap_exact_tokens_for_tokens_split(
+                vec![(path.clone(), 1), (path.clone(), 1), (path.clone(), 1), (path, 1)],
+                0,
+                accounts.alice,
+                2000,
+            );
+            assert_eq!(result.unwrap_err(), RouterError::InvalidSplit);
+        }
+
+        #[ink::test]
+        fn test_quote_split_balances_equal_routes() {
+            let accounts = default_accounts();
+            let router = RouterContract::new(accounts.bob, accounts.charlie);
+
+            let pool = (1_000_000, 1_000_000, constants::PAIR_STATIC_FEE_BPS);
+
+            // GREEN: Rotas idênticas devem receber metade do input cada
+            let (shares, total_out) = RouterContract::split_quote(100_000, &[vec![pool], vec![pool]]).unwrap();
+            assert_eq!(shares, vec![50_000, 50_000]);
+
+            // GREEN: Dividir rende mais do que usar uma única rota
+            let single = router.get_amount_out_with_fee(100_000, pool.0, pool.1, pool.2).unwrap();
+            assert!(total_out > single);
+        }
+
+        #[ink::test]
+        fn test_quote_split_favors_deeper_route() {
+            let accounts = default_accounts();
+            let router = RouterContract::new(accounts.bob, accounts.charlie);
+
+            // GREEN: A rota mais profunda recebe a maior fatia
+            let (shares, _) = RouterContract::split_quote(
+                100_000,
+                &[
+                    vec![(1_000_000, 1_000_000, 50)],
+                    vec![(3_000_000, 3_000_000, 50)],
+                    vec![(500_000, 1_000_000, 50), (1_000_000, 500_000, 50)],
+                ],
+            )
+            .unwrap();
+            assert_eq!(shares.iter().sum::<Balance>(), 100_000);
+            assert!(shares[1] > shares[0]);
+
+            // GREEN: A fee de cada par pesa na divisão: mesma profundidade, fee maior recebe menos
+            let (shares, _) = RouterContract::split_quote(
+                100_000,
+                &[vec![(1_000_000, 1_000_000, 50)], vec![(1_000_000, 1_000_000, 300)]],
+            )
+            .unwrap();
+            assert!(shares[0] > shares[1]);
+
+            // RED: Apenas uma rota não é um split
+            let path = vec![accounts.django, accounts.eve];
+            let result = router.quote_split(100_000, vec![path]);
+            assert_eq!(result.unwrap_err(), RouterError::InvalidSplit);
+        }
+
+        #[ink::test]
+        fn test_get_amount_out_formula() {
+            let accounts = default_accounts();
+            let router = RouterContract::new(accounts.bob, accounts.charlie);
+
+            let fee = constants::PAIR_STATIC_FEE_BPS;
+
+            // GREEN: Fee do par (0.5%): 1000 * 9950 * 10000 / (10000 * 10000 + 1000 * 9950) = 904
+            assert_eq!(router.get_amount_out_with_fee(1000, 10_000, 10_000, fee).unwrap(), 904);
+
+            // RED: Reserves vazias
+            assert_eq!(
+                router.get_amount_out_with_fee(1000, 0, 10_000, fee).unwrap_err(),
+                RouterError::InsufficientLiquidity
+            );
+
+            // GREEN: Valores grandes não causam overflow
+            let big = u128::MAX / 2;
+            assert!(router.get_amount_out_with_fee(big, big, big, fee).is_ok());
         }
 
         // ========================================
-        // TESTES DE REMOVE LIQUIDITY
+        // TESTES DE MULTICALL
         // ========================================
 
+        fn encode_call<T: Encode>(selector: [u8; 4], args: T) -> Vec<u8> {
+            let mut call = selector.to_vec();
+            args.encode_to(&mut call);
+            call
+        }
+
         #[ink::test]
-        fn test_remove_liquidity_success() {
+        fn test_get_amount_out_with_dynamic_fee() {
+            let accounts = default_accounts();
+            let router = RouterContract::new(accounts.bob, accounts.charlie);
+
+            // GREEN: Fee estática do par (50 bps) equivale a 995/1000
+            assert_eq!(
+                router.get_amount_out_with_fee(1000, 10_000, 10_000, constants::PAIR_STATIC_FEE_BPS),
+                RouterContract::amount_out_for_fee(1000, 10_000, 10_000, 995, 1000)
+            );
+            assert_eq!(router.get_amount_out_with_fee(1000, 10_000, 10_000, 50), Ok(904));
+
+            // GREEN: Fee maior reduz o output cotado
+            let low = router.get_amount_out_with_fee(1000, 10_000, 10_000, 10).unwrap();
+            let high = router.get_amount_out_with_fee(1000, 10_000, 10_000, 100).unwrap();
+            assert!(low > high);
+
+            // RED: Fee acima de 100%
+            assert_eq!(
+                router.get_amount_out_with_fee(1000, 10_000, 10_000, 10_001),
+                Err(RouterError::InvalidFee)
+            );
+
+            // RED: get_amounts_out valida o path antes de consultar os pares
+            assert_eq!(router.get_amounts_out(1000, vec![accounts.django]), Err(RouterError::InvalidPath));
+        }
+
+        #[ink::test]
+        fn test_set_fee_discounts_governance() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            let mut router = RouterContract::new(accounts.bob, accounts.charlie);
+            let discounts = FeeDiscounts { silver_bps: 500, gold_bps: 1_500, platinum_bps: 5_000 };
+
+            // RED: Apenas o admin define a tabela
+            set_sender(accounts.django);
+            assert_eq!(router.set_fee_discounts(Some(accounts.eve), discounts), Err(RouterError::Unauthorized));
+
+            // RED: Tiers fora de ordem ou desconto acima de 100%
+            set_sender(accounts.alice);
+            let unordered = FeeDiscounts { silver_bps: 2_000, ..discounts };
+            assert_eq!(router.set_fee_discounts(Some(accounts.eve), unordered), Err(RouterError::InvalidFeeDiscount));
+            let above_full = FeeDiscounts { platinum_bps: 10_001, ..discounts };
+            assert_eq!(router.set_fee_discounts(Some(accounts.eve), above_full), Err(RouterError::InvalidFeeDiscount));
+
+            // GREEN: Tabela e Staking Contract atualizados
+            assert!(router.set_fee_discounts(Some(accounts.eve), discounts).is_ok());
+            assert_eq!(router.fee_discounts(), discounts);
+            assert_eq!(router.staking_contract(), Some(accounts.eve));
+        }
+
+        #[ink::test]
+        fn test_discounted_fee_by_staking_tier() {
+            let accounts = default_accounts();
+            let router = RouterContract::new(accounts.bob, accounts.charlie);
+            let discounts = router.fee_discounts();
+
+            // GREEN: Bronze e não-stakers pagam a fee cheia do par
+            assert_eq!(RouterContract::discounted_fee_bps(&discounts, None), 50);
+            assert_eq!(RouterContract::discounted_fee_bps(&discounts, Some(StakingTier::Bronze)), 50);
+
+            // GREEN: 10% / 20% / 30% de desconto sobre 50 bps
+            assert_eq!(RouterContract::discounted_fee_bps(&discounts, Some(StakingTier::Silver)), 45);
+            assert_eq!(RouterContract::discounted_fee_bps(&discounts, Some(StakingTier::Gold)), 40);
+            assert_eq!(RouterContract::discounted_fee_bps(&discounts, Some(StakingTier::Platinum)), 35);
+
+            // GREEN: Sem Staking Contract configurado a fee é a cheia
+            assert_eq!(router.get_trader_fee_bps(accounts.alice), constants::BASE_SWAP_FEE_BPS);
+        }
+
+        fn stake_position(amount: Balance, duration: u64, tier: StakingTier) -> StakePosition {
+            StakePosition {
+                amount,
+                start_time: 0,
+                duration,
+                last_claim: 0,
+                pending_rewards: 0,
+                active: true,
+                tier,
+                early_adopter_tier: staking_contract::staking_contract::EarlyAdopterTier::None,
+            }
+        }
+
+        #[ink::test]
+        fn test_cached_fee_tier_requires_lock_in_force() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            let mut router = RouterContract::new(accounts.bob, accounts.charlie);
+            let whale: Balance = 1_000_000_000_000_000_000;
+            let gold = stake_position(whale, 1_000, StakingTier::Gold);
+
+            // RED: Sem Staking Contract não há de onde ler a posição
+            assert_eq!(router.refresh_fee_tier(accounts.django, 0), Err(RouterError::StakingUnavailable));
+
+            // GREEN: Posição ativa com lock em vigor entra no cache
+            let cached = RouterContract::next_fee_tier(None, 0, Some(&gold), 500).unwrap();
+            assert_eq!(cached, CachedFeeTier { tier: gold.fee_tier(), position_id: 0, unlock_time: 1_000 });
+
+            // RED: Lock vencido ou posição encerrada não dão desconto
+            assert_eq!(RouterContract::next_fee_tier(None, 0, Some(&gold), 1_000), None);
+            let closed = StakePosition { active: false, ..stake_position(whale, 1_000, StakingTier::Gold) };
+            assert_eq!(RouterContract::next_fee_tier(Some(cached), 0, Some(&closed), 500), None);
+
+            // GREEN: Posição menor não rebaixa o cache de outra posição
+            let bronze = stake_position(whale, 2_000, StakingTier::Bronze);
+            assert_eq!(RouterContract::next_fee_tier(Some(cached), 1, Some(&bronze), 500), Some(cached));
+
+            // GREEN: Swap executado usa o cache, sem cross-call, até o fim do lock
+            router.set_fee_discounts(Some(accounts.eve), router.fee_discounts()).unwrap();
+            router.fee_tiers.insert(accounts.django, &cached);
+            set_timestamp(999);
+            assert_eq!(router.get_trader_fee_bps(accounts.django), 40);
+            assert_eq!(router.get_trader_discount_bps(accounts.django), constants::DEFAULT_GOLD_DISCOUNT_BPS);
+            let path = vec![accounts.django, accounts.eve];
+            set_sender(accounts.django);
+            assert_eq!(router.swap_exact_tokens_for_tokens(10_000, 0, path.clone(), accounts.django, 10_000), Ok(vec![10_000, 9_960]));
+
+            // RED: Lock vencido → fee cheia no swap
+            set_timestamp(1_000);
+            assert_eq!(router.get_trader_fee_bps(accounts.django), constants::BASE_SWAP_FEE_BPS);
+            assert_eq!(router.swap_exact_tokens_for_tokens(10_000, 0, path, accounts.django, 10_000), Ok(vec![10_000, 9_950]));
+        }
+
+        #[ink::test]
+        fn test_discounted_fee_improves_swap_quote() {
+            let accounts = default_accounts();
+            let router = RouterContract::new(accounts.bob, accounts.charlie);
+            let path = vec![accounts.django, accounts.eve];
+
+            // GREEN: Fee cheia equivale a 995/1000; fee com desconto rende mais output
+            assert_eq!(router.calculate_output_amount(10_000, &path, 50), Ok(9_950));
+            assert_eq!(router.calculate_output_amount(10_000, &path, 35), Ok(9_965));
+            assert_eq!(router.calculate_input_amount(9_950, &path, 50), Ok(10_000));
+            assert!(router.calculate_input_amount(9_950, &path, 35).unwrap() < 10_000);
+        }
+
+        #[ink::test]
+        fn test_trusted_aggregator_uses_recipient_tier() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            let mut router = RouterContract::new(accounts.bob, accounts.charlie);
+
+            // RED: Apenas o admin marca agregadores
+            set_sender(accounts.django);
+            assert_eq!(router.set_trusted_aggregator(accounts.django, true), Err(RouterError::Unauthorized));
+
+            // GREEN: Caller comum paga pelo próprio tier
+            assert_eq!(router.fee_trader(accounts.eve), accounts.django);
+
+            // GREEN: Agregador confiável repassa o tier do destinatário
+            set_sender(accounts.alice);
+            assert!(router.set_trusted_aggregator(accounts.django, true).is_ok());
+            assert!(router.is_trusted_aggregator(accounts.django));
+            set_sender(accounts.django);
+            assert_eq!(router.fee_trader(accounts.eve), accounts.eve);
+
+            // GREEN: Desmarcado volta ao caller
+            set_sender(accounts.alice);
+            assert!(router.set_trusted_aggregator(accounts.django, false).is_ok());
+            set_sender(accounts.django);
+            assert_eq!(router.fee_trader(accounts.eve), accounts.django);
+        }
+
+        #[ink::test]
+        fn test_multicall_returns_each_result() {
             let accounts = default_accounts();
             set_sender(accounts.alice);
             set_timestamp(1000);
-            
+
             let mut router = RouterContract::new(accounts.bob, accounts.charlie);
-            
-            // GREEN: Remove liquidity com parâmetros válidos deve funcionar
-            let result = router.remove_liquidity(
-                accounts.django, // token_a
-                accounts.eve,    // token_b
-                200,             // liquidity
-                90,              // amount_a_min
-                90,              // amount_b_min
-                accounts.alice,  // to
-                2000,            // deadline
+
+            let remove = encode_call(
+                selectors::REMOVE_LIQUIDITY,
+                (accounts.django, accounts.eve, 200u128, 90u128, 90u128, accounts.alice, 2000u64),
             );
-            
-            assert!(result.is_ok());
-            let (amount_a, amount_b) = result.unwrap();
-            assert_eq!(amount_a, 100); // liquidity / 2
-            assert_eq!(amount_b, 100); // liquidity / 2
+            let swap = encode_call(
+                selectors::SWAP_EXACT_TOKENS_FOR_TOKENS,
+                (100u128, 90u128, vec![accounts.django, accounts.eve], accounts.alice, 2000u64),
+            );
+
+            // GREEN: Remove liquidez e faz swap de um dos lados em uma transação
+            let results = router.multicall(vec![remove, swap]).unwrap();
+            assert_eq!(results.len(), 2);
+
+            let (amount_a, amount_b) = <(Balance, Balance)>::decode_all(&mut &results[0][..]).unwrap();
+            assert_eq!((amount_a, amount_b), (100, 100));
+
+            let amounts = <Vec<Balance>>::decode_all(&mut &results[1][..]).unwrap();
+            assert_eq!(amounts[0], 100);
         }
 
         #[ink::test]
-        fn test_remove_liquidity_zero_liquidity() {
+        fn test_multicall_fails_atomically() {
             let accounts = default_accounts();
             set_sender(accounts.alice);
             set_timestamp(1000);
-            
+
             let mut router = RouterContract::new(accounts.bob, accounts.charlie);
-            
-            // RED: Zero liquidity deve falhar
-            let result = router.remove_liquidity(
-                accounts.django, // token_a
-                accounts.eve,    // token_b
-                0,               // liquidity (zero)
-                90,              // amount_a_min
-                90,              // amount_b_min
-                accounts.alice,  // to
-                2000,            // deadline
+
+            let ok_swap = encode_call(
+                selectors::SWAP_EXACT_TOKENS_FOR_TOKENS,
+                (100u128, 90u128, vec![accounts.django, accounts.eve], accounts.alice, 2000u64),
             );
-            
-            assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), RouterError::InsufficientLiquidity);
+            let expired_swap = encode_call(
+                selectors::SWAP_EXACT_TOKENS_FOR_TOKENS,
+                (100u128, 90u128, vec![accounts.django, accounts.eve], accounts.alice, 500u64),
+            );
+
+            // RED: O deadline de cada chamada continua sendo verificado
+            let result = router.multicall(vec![ok_swap, expired_swap]);
+            assert_eq!(result.unwrap_err(), RouterError::Expired);
         }
 
         #[ink::test]
-        fn test_remove_liquidity_insufficient_b_amount() {
+        fn test_multicall_rejects_invalid_calls() {
             let accounts = default_accounts();
             set_sender(accounts.alice);
             set_timestamp(1000);
-            
+
             let mut router = RouterContract::new(accounts.bob, accounts.charlie);
-            
-            // RED: amount_b_min muito alto deve falhar
-            let result = router.remove_liquidity(
-                accounts.django, // token_a
-                accounts.eve,    // token_b
-                200,             // liquidity
-                90,              // amount_a_min
-                150,             // amount_b_min (muito alto)
-                accounts.alice,  // to
-                2000,            // deadline
-            );
-            
-            assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), RouterError::InsufficientBAmount);
+
+            // RED: Lista vazia
+            assert_eq!(router.multicall(vec![]).unwrap_err(), RouterError::InvalidCall);
+
+            // RED: Selector desconhecido (multicall aninhado não é permitido)
+            let nested = encode_call(ink::selector_bytes!("multicall"), Vec::<Vec<u8>>::new());
+            assert_eq!(router.multicall(vec![nested]).unwrap_err(), RouterError::InvalidCall);
+
+            // RED: Argumentos truncados
+            let truncated = encode_call(selectors::SWAP_EXACT_TOKENS_FOR_TOKENS, 100u128);
+            assert_eq!(router.multicall(vec![truncated]).unwrap_err(), RouterError::InvalidCall);
         }
 
         // ========================================
-        // TESTES DE SWAP EXACT TOKENS FOR TOKENS
+        // TESTES DE REFERRAL
         // ========================================
 
         #[ink::test]
-        fn test_swap_exact_tokens_success() {
+        fn test_referral_fee_requires_collection() {
             let accounts = default_accounts();
             set_sender(accounts.alice);
             set_timestamp(1000);
-            
+
             let mut router = RouterContract::new(accounts.bob, accounts.charlie);
-            
             let path = vec![accounts.django, accounts.eve];
-            
-            // GREEN: Swap com parâmetros válidos deve funcionar
-            let result = router.swap_exact_tokens_for_tokens(
-                100,            // amount_in
-                90,             // amount_out_min
-                path,           // path
-                accounts.alice, // to
-                2000,           // deadline
+
+            // GREEN: 0.3% de 10000 = 30 para o referrer
+            assert_eq!(RouterContract::referral_fee_of(10_000, 30), Ok(30));
+
+            // RED: Fee não pode ser puxada do caller, nada é creditado
+            assert_eq!(
+                router
+                    .swap_exact_tokens_for_tokens_with_referral(
+                        10_000,
+                        9_900,
+                        path.clone(),
+                        accounts.alice,
+                        2000,
+                        Some((accounts.frank, 30)),
+                    )
+                    .unwrap_err(),
+                RouterError::TransferFailed
             );
-            
-            assert!(result.is_ok());
-            let amounts = result.unwrap();
-            assert_eq!(amounts.len(), 2);
-            assert_eq!(amounts[0], 100); // amount_in
-            assert!(amounts[1] >= 90);   // amount_out >= min
+            assert_eq!(router.referral_fees_of(accounts.frank, accounts.django), 0);
+            assert_eq!(router.get_referrer_stats(accounts.frank), ReferralStats::default());
+
+            // GREEN: Fee zero não exige cobrança, só contabiliza volume
+            let amounts = router
+                .swap_exact_tokens_for_tokens_with_referral(
+                    10_000,
+                    9_900,
+                    path.clone(),
+                    accounts.alice,
+                    2000,
+                    Some((accounts.frank, 0)),
+                )
+                .unwrap();
+            assert_eq!(amounts, vec![10_000, 9_950]);
+
+            // GREEN: Sem referral, nenhuma fee é retida
+            router
+                .swap_exact_tokens_for_tokens_with_referral(1_000, 0, path, accounts.alice, 2000, None)
+                .unwrap();
+
+            let stats = router.get_referrer_stats(accounts.frank);
+            assert_eq!(stats.swap_count, 1);
+            assert_eq!(stats.total_volume, 10_000);
+            assert_eq!(stats.total_fees, 0);
         }
 
         #[ink::test]
-        fn test_swap_exact_tokens_zero_input() {
+        fn test_referral_exact_output_charges_on_top() {
             let accounts = default_accounts();
             set_sender(accounts.alice);
             set_timestamp(1000);
-            
+
             let mut router = RouterContract::new(accounts.bob, accounts.charlie);
-            
             let path = vec![accounts.django, accounts.eve];
-            
-            // RED: Zero input deve falhar
-            let result = router.swap_exact_tokens_for_tokens(
-                0,              // amount_in (zero)
-                90,             // amount_out_min
-                path,           // path
-                accounts.alice, // to
-                2000,           // deadline
+
+            // GREEN: Swap precisa de 1000; bruto = ceil(1000 * 10000 / 9970) = 1004
+            assert_eq!(RouterContract::gross_up_for_fee(1_000, 30), Ok(1_004));
+
+            // RED: amount_in_max considera a fee
+            assert_eq!(
+                router
+                    .swap_tokens_for_exact_tokens_with_referral(
+                        997,
+                        1_003,
+                        path,
+                        accounts.alice,
+                        2000,
+                        Some((accounts.frank, 30)),
+                    )
+                    .unwrap_err(),
+                RouterError::ExcessiveInputAmount
             );
-            
-            assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), RouterError::InsufficientOutputAmount);
         }
 
         #[ink::test]
-        fn test_swap_exact_tokens_insufficient_output() {
+        fn test_self_referral_rejected() {
             let accounts = default_accounts();
             set_sender(accounts.alice);
             set_timestamp(1000);
-            
+
             let mut router = RouterContract::new(accounts.bob, accounts.charlie);
-            
             let path = vec![accounts.django, accounts.eve];
-            
-            // RED: amount_out_min muito alto deve falhar (slippage protection)
-            let result = router.swap_exact_tokens_for_tokens(
-                100,            // amount_in
-                150,            // amount_out_min (muito alto)
-                path,           // path
-                accounts.alice, // to
-                2000,           // deadline
+
+            // RED: Caller não pode indicar a si mesmo
+            assert_eq!(
+                router
+                    .swap_exact_tokens_for_tokens_with_referral(1_000, 0, path, accounts.alice, 2000, Some((accounts.alice, 10)))
+                    .unwrap_err(),
+                RouterError::SelfReferral
             );
-            
-            assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), RouterError::InsufficientOutputAmount);
         }
 
         #[ink::test]
-        fn test_swap_exact_tokens_invalid_path() {
+        fn test_referral_fee_capped_by_governance() {
             let accounts = default_accounts();
             set_sender(accounts.alice);
             set_timestamp(1000);
-            
+
             let mut router = RouterContract::new(accounts.bob, accounts.charlie);
-            
-            let path = vec![accounts.django]; // Path muito curto
-            
-            // RED: Path inválido deve falhar
-            let result = router.swap_exact_tokens_for_tokens(
-                100,            // amount_in
-                90,             // amount_out_min
-                path,           // path (inválido)
-                accounts.alice, // to
-                2000,           // deadline
-            );
-            
-            assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), RouterError::InvalidPath);
-        }
+            let path = vec![accounts.django, accounts.eve];
+            assert_eq!(router.admin(), accounts.alice);
 
-        // ========================================
-        // TESTES DE SWAP TOKENS FOR EXACT TOKENS
-        // ========================================
+            // RED: Acima do teto padrão
+            assert_eq!(
+                router
+                    .swap_exact_tokens_for_tokens_with_referral(1_000, 0, path.clone(), accounts.alice, 2000, Some((accounts.frank, 31)))
+                    .unwrap_err(),
+                RouterError::ReferralFeeTooHigh
+            );
 
-        #[ink::test]
-        fn test_swap_tokens_for_exact_success() {
-            let accounts = default_accounts();
+            // RED: Apenas o admin altera o teto, e nunca acima de 1%
+            set_sender(accounts.eve);
+            assert_eq!(router.set_max_referral_fee_bps(50).unwrap_err(), RouterError::Unauthorized);
             set_sender(accounts.alice);
-            set_timestamp(1000);
-            
-            let mut router = RouterContract::new(accounts.bob, accounts.charlie);
-            
-            let path = vec![accounts.django, accounts.eve];
-            
-            // GREEN: Swap reverso com parâmetros válidos deve funcionar
-            let result = router.swap_tokens_for_exact_tokens(
-                100,            // amount_out
-                110,            // amount_in_max
-                path,           // path
-                accounts.alice, // to
-                2000,           // deadline
+            assert_eq!(router.set_max_referral_fee_bps(101).unwrap_err(), RouterError::ReferralFeeTooHigh);
+
+            // GREEN: Novo teto aceita a fee (falha só na cobrança do token de teste)
+            assert!(router.set_max_referral_fee_bps(50).is_ok());
+            assert_eq!(router.max_referral_fee_bps(), 50);
+            assert_eq!(
+                router
+                    .swap_exact_tokens_for_tokens_with_referral(1_000, 0, path.clone(), accounts.alice, 2000, Some((accounts.frank, 31)))
+                    .unwrap_err(),
+                RouterError::TransferFailed
+            );
+
+            // RED: Referrer zero
+            assert_eq!(
+                router
+                    .swap_exact_tokens_for_tokens_with_referral(1_000, 0, path, accounts.alice, 2000, Some((AccountId::from([0u8; 32]), 10)))
+                    .unwrap_err(),
+                RouterError::ZeroAddress
             );
-            
-            assert!(result.is_ok());
-            let amounts = result.unwrap();
-            assert_eq!(amounts.len(), 2);
-            assert!(amounts[0] <= 110); // amount_in <= max
-            assert_eq!(amounts[1], 100); // amount_out exato
         }
 
         #[ink::test]
-        fn test_swap_tokens_for_exact_zero_output() {
+        fn test_claim_referral_fees_requires_balance() {
             let accounts = default_accounts();
-            set_sender(accounts.alice);
-            set_timestamp(1000);
-            
+            set_sender(accounts.frank);
+
             let mut router = RouterContract::new(accounts.bob, accounts.charlie);
-            
-            let path = vec![accounts.django, accounts.eve];
-            
-            // RED: Zero output deve falhar
-            let result = router.swap_tokens_for_exact_tokens(
-                0,              // amount_out (zero)
-                110,            // amount_in_max
-                path,           // path
-                accounts.alice, // to
-                2000,           // deadline
+
+            // RED: Nada acumulado para resgatar
+            assert_eq!(
+                router.claim_referral_fees(accounts.django).unwrap_err(),
+                RouterError::NoReferralFees
             );
-            
-            assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), RouterError::InsufficientOutputAmount);
         }
 
         #[ink::test]
-        fn test_swap_tokens_for_exact_excessive_input() {
+        fn test_claim_cannot_exceed_collected_fees() {
             let accounts = default_accounts();
-            set_sender(accounts.alice);
-            set_timestamp(1000);
-            
+            set_sender(accounts.frank);
+
             let mut router = RouterContract::new(accounts.bob, accounts.charlie);
-            
-            let path = vec![accounts.django, accounts.eve];
-            
-            // RED: amount_in_max muito baixo deve falhar (slippage protection)
-            let result = router.swap_tokens_for_exact_tokens(
-                100,            // amount_out
-                90,             // amount_in_max (muito baixo)
-                path,           // path
-                accounts.alice, // to
-                2000,           // deadline
+
+            // Crédito de 100 com apenas 40 efetivamente recebidos pelo router
+            router.referral_fees.insert((accounts.frank, accounts.django), &100);
+            router.referral_fees_collected.insert(accounts.django, &40);
+
+            // RED: Resgate acima do coletado é rejeitado sem alterar o estado
+            assert_eq!(
+                router.claim_referral_fees(accounts.django).unwrap_err(),
+                RouterError::NoReferralFees
             );
-            
-            assert!(result.is_err());
-            assert_eq!(result.unwrap_err(), RouterError::ExcessiveInputAmount);
+            assert_eq!(router.referral_fees_of(accounts.frank, accounts.django), 100);
+            assert_eq!(router.referral_fees_collected(accounts.django), 40);
         }
 
         // ========================================
-        // TESTES DE VALIDAÇÃO DE PATH E EDGE CASES
+        // TESTES DE ZAP
         // ========================================
 
         #[ink::test]
-        fn test_path_with_zero_address() {
+        #[allow(clippy::arithmetic_side_effects)]
+        fn test_zap_swap_amount_matches_pool_ratio() {
+            let accounts = default_accounts();
+            let router = RouterContract::new(accounts.bob, accounts.charlie);
+
+            let (reserve_in, reserve_out) = (1_000_000_000u128, 2_000_000_000u128);
+            let amount_in = 10_000_000u128;
+
+            let fee_bps = 50u16;
+            let swap_amount = router.get_zap_swap_amount(amount_in, reserve_in, fee_bps).unwrap();
+            let amount_out = router
+                .get_amount_out_with_fee(swap_amount, reserve_in, reserve_out, fee_bps)
+                .unwrap();
+            let remaining = amount_in - swap_amount;
+
+            // GREEN: Apenas parte do input é trocada
+            assert!(swap_amount > 0);
+            assert!(swap_amount < amount_in);
+
+            // GREEN: O restante e o output têm a mesma proporção das novas reserves
+            let new_reserve_in = reserve_in + swap_amount;
+            let new_reserve_out = reserve_out - amount_out;
+            let lhs = remaining * new_reserve_out;
+            let rhs = amount_out * new_reserve_in;
+            let diff = lhs.abs_diff(rhs);
+            assert!(diff * 10_000 <= lhs, "proporção fora de 0.01%");
+        }
+
+        #[ink::test]
+        fn test_zap_swap_amount_requires_liquidity() {
+            let accounts = default_accounts();
+            let router = RouterContract::new(accounts.bob, accounts.charlie);
+
+            // RED: Par sem reserves não tem proporção definida
+            assert_eq!(
+                router.get_zap_swap_amount(1000, 0, 50).unwrap_err(),
+                RouterError::InsufficientLiquidity
+            );
+
+            // RED: Fee de 100% não tem solução
+            assert_eq!(
+                router.get_zap_swap_amount(1000, 1_000_000, 10_000).unwrap_err(),
+                RouterError::InvalidFee
+            );
+        }
+
+        #[ink::test]
+        fn test_zap_validations() {
             let accounts = default_accounts();
             set_sender(accounts.alice);
             set_timestamp(1000);
-            
+
             let mut router = RouterContract::new(accounts.bob, accounts.charlie);
-            
+
+            // RED: Deadline expirado
+            let result = router.zap_in(accounts.django, 100, accounts.eve, 0, accounts.alice, 500);
+            assert_eq!(result.unwrap_err(), RouterError::Expired);
+
+            // RED: Input zero
+            let result = router.zap_in(accounts.django, 0, accounts.eve, 0, accounts.alice, 2000);
+            assert_eq!(result.unwrap_err(), RouterError::InsufficientLiquidity);
+
+            // RED: Par com endereço zero
             let zero_address = AccountId::from([0u8; 32]);
-            let path = vec![accounts.django, zero_address]; // Zero address no path
-            
-            // RED: Path com zero address deve falhar
-            let result = router.swap_exact_tokens_for_tokens(
-                100,            // amount_in
-                90,             // amount_out_min
-                path,           // path (com zero address)
-                accounts.alice, // to
-                2000,           // deadline
-            );
-            
-            assert!(result.is_err());
+            let result = router.zap_in(accounts.django, 100, zero_address, 0, accounts.alice, 2000);
             assert_eq!(result.unwrap_err(), RouterError::ZeroAddress);
+
+            // RED: Liquidez zero no zap-out
+            let result = router.zap_out(accounts.eve, 0, accounts.django, 0, accounts.alice, 2000);
+            assert_eq!(result.unwrap_err(), RouterError::InsufficientLiquidity);
         }
 
         #[ink::test]
-        fn test_calculate_liquidity_edge_cases() {
+        fn test_zap_native_validations() {
             let accounts = default_accounts();
-            let router = RouterContract::new(accounts.bob, accounts.charlie);
-            
-            // RED: Zero amounts devem falhar
-            let result = router.calculate_liquidity(0, 100);
-            assert!(result.is_err());
+            set_sender(accounts.alice);
+            set_timestamp(1000);
+
+            let mut router = RouterContract::new(accounts.bob, accounts.charlie);
+
+            // RED: Zap-in nativo sem valor transferido
+            ink::env::test::set_value_transferred::<DefaultEnvironment>(0);
+            let result = router.zap_in_native(accounts.eve, 0, accounts.alice, 2000);
             assert_eq!(result.unwrap_err(), RouterError::InsufficientLiquidity);
-            
-            let result = router.calculate_liquidity(100, 0);
-            assert!(result.is_err());
+
+            // RED: Deadline expirado no zap-in nativo
+            ink::env::test::set_value_transferred::<DefaultEnvironment>(1_000);
+            let result = router.zap_in_native(accounts.eve, 0, accounts.alice, 500);
+            assert_eq!(result.unwrap_err(), RouterError::Expired);
+
+            // RED: Liquidez zero no zap-out nativo
+            let result = router.zap_out_native(accounts.eve, 0, 0, accounts.alice, 2000);
             assert_eq!(result.unwrap_err(), RouterError::InsufficientLiquidity);
-            
-            // GREEN: Amounts válidos devem funcionar
-            let result = router.calculate_liquidity(100, 200);
-            assert!(result.is_ok());
-            assert!(result.unwrap() > constants::MINIMUM_LIQUIDITY);
+        }
+
+        #[ink::test]
+        fn test_native_payout_uses_measured_delta() {
+            // GREEN: Paga apenas o que o unwrap creditou
+            assert_eq!(RouterContract::received_amount(5_000, 7_500), Ok(2_500));
+
+            // RED: Saldo menor após a operação nunca vira pagamento
+            assert_eq!(
+                RouterContract::received_amount(7_500, 5_000).unwrap_err(),
+                RouterError::TransferFailed
+            );
         }
 
         #[ink::test]
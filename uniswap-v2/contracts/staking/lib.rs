@@ -12,6 +12,10 @@
 /// - Reward distribution based on staking duration and amount
 /// - Anti-whale mechanisms for fair distribution
 ///
+/// ## Time Model:
+/// All durations, deadlines and reward accrual use `block_timestamp()`
+/// milliseconds (see `constants::ONE_DAY` / `constants::ONE_YEAR`).
+///
 /// ## Security Features:
 /// - Reentrancy protection
 /// - Access control for admin functions
@@ -77,6 +81,8 @@ pub mod staking_contract {
         TooManyTokens,
         /// Invalid amount provided
         InvalidAmount,
+        /// Maximum active positions per account reached
+        TooManyPositions,
     }
 
     /// Governance proposal for project listing
@@ -119,7 +125,7 @@ pub mod staking_contract {
         pub amount: Balance,
         /// Timestamp when stake was created
         pub start_time: Timestamp,
-        /// Staking duration in blocks
+        /// Staking duration in milliseconds
         pub duration: u64,
         /// Last reward claim timestamp
         pub last_claim: Timestamp,
@@ -129,14 +135,55 @@ pub mod staking_contract {
         pub active: bool,
         /// Staking tier baseado na duração
         pub tier: StakingTier,
-        /// Early adopter tier
+        /// Early adopter tier (only ever set on the account's first position, id 0)
         pub early_adopter_tier: EarlyAdopterTier,
-        /// Participação em governança (número de votos)
-        pub governance_participation: u32,
+    }
+
+    impl StakePosition {
+        /// Timestamp at which the lock ends
+        pub fn unlock_time(&self) -> Timestamp {
+            self.start_time.saturating_add(self.duration)
+        }
+
+        /// Fee-discount tier of the position
+        ///
+        /// The duration tier is capped by the amount (Silver needs
+        /// `MEDIUM_STAKE_THRESHOLD`, Gold `LARGE_STAKE_THRESHOLD`, Platinum
+        /// `WHALE_STAKE_THRESHOLD`), so a long lock of `MIN_STAKE` stays Bronze.
+        pub fn fee_tier(&self) -> StakingTier {
+            let amount_tier = if self.amount >= constants::WHALE_STAKE_THRESHOLD {
+                StakingTier::Platinum
+            } else if self.amount >= constants::LARGE_STAKE_THRESHOLD {
+                StakingTier::Gold
+            } else if self.amount >= constants::MEDIUM_STAKE_THRESHOLD {
+                StakingTier::Silver
+            } else {
+                StakingTier::Bronze
+            };
+            self.tier.min(amount_tier)
+        }
+    }
+
+    /// Solvency snapshot separating stake principal from reward funds
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct SolvencyReport {
+        /// Principal owed back to stakers (`total_staked`)
+        pub principal: Balance,
+        /// Unreserved rewards budget available for new accrual
+        pub rewards_budget: Balance,
+        /// Rewards already credited to stakers (pending rewards, governance
+        /// bonuses) plus the part of the trading rewards pool not yet credited,
+        /// including rounding dust carried to the next distribution
+        pub liabilities: Balance,
+        /// Native balance held by the contract
+        pub native_balance: Balance,
+        /// Amount by which the balance fails to cover principal + budget + liabilities
+        pub shortfall: Balance,
     }
 
     /// Tiers de staking baseados em duração
-    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode, Clone, Copy)]
+    #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, scale::Encode, scale::Decode, Clone, Copy)]
     #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
     pub enum StakingTier {
         Bronze,   // 7-30 dias - 8% APY
@@ -171,6 +218,7 @@ pub mod staking_contract {
     pub struct Staked {
         #[ink(topic)]
         pub staker: AccountId,
+        pub position_id: u32,
         pub amount: Balance,
         pub duration: u64,
         pub timestamp: Timestamp,
@@ -180,6 +228,7 @@ pub mod staking_contract {
     pub struct Unstaked {
         #[ink(topic)]
         pub staker: AccountId,
+        pub position_id: u32,
         pub amount: Balance,
         pub rewards: Balance,
         pub penalty: Balance,
@@ -190,6 +239,7 @@ pub mod staking_contract {
     pub struct RewardsClaimed {
         #[ink(topic)]
         pub staker: AccountId,
+        pub position_id: u32,
         pub amount: Balance,
         pub timestamp: Timestamp,
     }
@@ -233,6 +283,12 @@ pub mod staking_contract {
         pub timestamp: Timestamp,
     }
 
+    #[ink(event)]
+    pub struct RewardsBudgetFunded {
+        pub amount: Balance,
+        pub timestamp: Timestamp,
+    }
+
     #[ink(event)]
     pub struct TradingRewardsDistributed {
         pub total_amount: Balance,
@@ -267,6 +323,30 @@ pub mod staking_contract {
         pub timestamp: Timestamp,
     }
 
+    #[ink(event)]
+    pub struct StakeModified {
+        #[ink(topic)]
+        pub staker: AccountId,
+        pub position_id: u32,
+        pub amount: Balance,
+        pub duration: u64,
+        pub tier: StakingTier,
+        /// Rewards accrued up to the modification, kept in `pending_rewards`
+        pub settled_rewards: Balance,
+        pub timestamp: Timestamp,
+    }
+
+    #[ink(event)]
+    pub struct RewardsCapped {
+        #[ink(topic)]
+        pub staker: AccountId,
+        /// Rewards accrued by the rate schedule
+        pub accrued: Balance,
+        /// Rewards actually credited from the remaining budget
+        pub credited: Balance,
+        pub timestamp: Timestamp,
+    }
+
     #[ink(event)]
     pub struct TierUpgraded {
         #[ink(topic)]
@@ -374,11 +454,17 @@ pub mod staking_contract {
         /// Minimum stake amount (1000 LUNES)
         pub const MIN_STAKE: Balance = 100_000_000_000; // 1000 * 10^8
         
-        /// Minimum staking duration (7 days in blocks, ~2 sec per block)
-        pub const MIN_DURATION: u64 = 7 * 24 * 60 * 30; // 7 days
+        /// One day in milliseconds (unit of `block_timestamp()`)
+        pub const ONE_DAY: u64 = 24 * 60 * 60 * 1000;
+        
+        /// One year (365 days) in milliseconds, base for APY accrual
+        pub const ONE_YEAR: u64 = 365 * ONE_DAY;
+        
+        /// Minimum staking duration (7 days)
+        pub const MIN_DURATION: u64 = 7 * ONE_DAY;
         
         /// Maximum staking duration (365 days)
-        pub const MAX_DURATION: u64 = 365 * 24 * 60 * 30; // 1 year
+        pub const MAX_DURATION: u64 = ONE_YEAR;
         
         /// === REWARD RATES POR TIER (em basis points) ===
         
@@ -418,15 +504,15 @@ pub mod staking_contract {
         
         /// Top 100 early adopters: +50% for 3 months
         pub const TOP_100_BONUS: u32 = 5000; // +50% = 5000 basis points
-        pub const TOP_100_DURATION: u64 = 90 * 24 * 60 * 30; // 3 months
+        pub const TOP_100_DURATION: u64 = 90 * ONE_DAY; // 3 months
         
         /// Top 500 early adopters: +25% for 2 months  
         pub const TOP_500_BONUS: u32 = 2500; // +25% = 2500 basis points
-        pub const TOP_500_DURATION: u64 = 60 * 24 * 60 * 30; // 2 months
+        pub const TOP_500_DURATION: u64 = 60 * ONE_DAY; // 2 months
         
         /// Top 1000 early adopters: +10% for 1 month
         pub const TOP_1000_BONUS: u32 = 1000; // +10% = 1000 basis points
-        pub const TOP_1000_DURATION: u64 = 30 * 24 * 60 * 30; // 1 month
+        pub const TOP_1000_DURATION: u64 = 30 * ONE_DAY; // 1 month
         
         /// === GOVERNANCE BONUSES ===
         
@@ -453,7 +539,7 @@ pub mod staking_contract {
         pub const EARLY_PENALTY_RATE: u32 = 500; // 5% = 500 basis points
         
         /// Voting period for proposals (14 days)
-        pub const VOTING_PERIOD: u64 = 14 * 24 * 60 * 30; // 14 days in blocks
+        pub const VOTING_PERIOD: u64 = 14 * ONE_DAY; // 14 days
         
         /// Minimum voting power required to create proposal (10,000 LUNES staked)
         pub const MIN_PROPOSAL_POWER: Balance = 1_000_000_000_000; // 10,000 * 10^8
@@ -464,6 +550,9 @@ pub mod staking_contract {
         /// Maximum number of active stakers
         pub const MAX_STAKERS: u32 = 10_000;
         
+        /// Maximum number of concurrent active positions per account
+        pub const MAX_POSITIONS_PER_ACCOUNT: u32 = 20;
+        
         /// Basis points denominator
         pub const BASIS_POINTS: u32 = 10_000;
         
@@ -486,10 +575,14 @@ pub mod staking_contract {
         total_staked: Balance,
         /// Total rewards distributed
         total_rewards_distributed: Balance,
-        /// Number of active stakers
+        /// Number of accounts with at least one active position
         active_stakers: u32,
-        /// Mapping from staker to their position
-        stakes: Mapping<AccountId, StakePosition>,
+        /// Stake positions keyed by (staker, position id)
+        stakes: Mapping<(AccountId, u32), StakePosition>,
+        /// Active position ids per staker (bounded by MAX_POSITIONS_PER_ACCOUNT)
+        account_positions: Mapping<AccountId, Vec<u32>>,
+        /// Next position id per staker
+        next_position_id: Mapping<AccountId, u32>,
         /// Mapping for tracking staker addresses
         staker_addresses: Mapping<u32, AccountId>,
         /// Current staker index
@@ -513,6 +606,8 @@ pub mod staking_contract {
         tier_multipliers: Mapping<StakingTier, u32>,
         /// Bônus de governança acumulados por usuário
         governance_bonuses: Mapping<AccountId, Balance>,
+        /// Participação em governança por staker (número de votos)
+        vote_participation: Mapping<AccountId, u32>,
         /// Contador de early adopters por tier
         early_adopter_counts: Mapping<EarlyAdopterTier, u32>,
         /// Campanhas ativas (direct mapping para evitar storage collision)
@@ -523,6 +618,16 @@ pub mod staking_contract {
         total_trading_rewards_distributed: Balance,
         /// Taxa atual para criação de propostas (ajustável via governança)
         current_proposal_fee: Balance,
+        /// Orçamento de rewards ainda não reservado (separado de `total_staked`)
+        rewards_budget: Balance,
+        /// Rewards já creditados e ainda não pagos (pending rewards e bônus)
+        reserved_rewards: Balance,
+        /// Soma dos pesos de todas as posições ativas (atualizada em stake,
+        /// unstake, increase_stake e extend_duration)
+        total_staker_weight: Balance,
+        /// Parcela do `trading_rewards_pool` já creditada às posições na
+        /// distribuição paginada em curso (abatida do pool ao completar)
+        trading_rewards_in_flight: Balance,
     }
 
     impl StakingContract {
@@ -538,6 +643,8 @@ pub mod staking_contract {
                 total_rewards_distributed: 0,
                 active_stakers: 0,
                 stakes: Mapping::default(),
+                account_positions: Mapping::default(),
+                next_position_id: Mapping::default(),
                 staker_addresses: Mapping::default(),
                 staker_index: 0,
                 proposals: Mapping::default(),
@@ -550,11 +657,16 @@ pub mod staking_contract {
                 trading_rewards_contract: None,
                 tier_multipliers: Mapping::default(),
                 governance_bonuses: Mapping::default(),
+                vote_participation: Mapping::default(),
                 early_adopter_counts: Mapping::default(),
                 active_campaigns: Mapping::default(),
                 next_campaign_id: 1,
                 total_trading_rewards_distributed: 0,
                 current_proposal_fee: constants::PROPOSAL_FEE, // Inicia com 1,000 LUNES
+                rewards_budget: 0,
+                reserved_rewards: 0,
+                total_staker_weight: 0,
+                trading_rewards_in_flight: 0,
             };
             
             // Campos já inicializados diretamente no struct
@@ -605,9 +717,17 @@ pub mod staking_contract {
         // STAKING & REWARDS
         // ========================================
 
-        /// Stakes LUNES tokens for a specified duration
+        /// Stakes LUNES tokens for a specified duration in a new position
+        ///
+        /// An account may hold up to `MAX_POSITIONS_PER_ACCOUNT` concurrent
+        /// positions (e.g. a 30-day and a 365-day ladder). Returns the new
+        /// position id.
+        ///
+        /// The early adopter tier is granted once per account, to its first
+        /// position (id 0). Later positions never carry it, even if position 0
+        /// has already been unstaked.
         #[ink(message, payable)]
-        pub fn stake(&mut self, duration: u64) -> Result<(), StakingError> {
+        pub fn stake(&mut self, duration: u64) -> Result<u32, StakingError> {
             self.ensure_not_paused()?;
             self.acquire_lock()?;
             
@@ -626,24 +746,31 @@ pub mod staking_contract {
                 return Err(StakingError::InvalidDuration);
             }
             
-            if self.active_stakers >= constants::MAX_STAKERS {
+            let mut position_ids = self.account_positions.get(caller).unwrap_or_default();
+            let is_new_staker = position_ids.is_empty();
+            
+            if is_new_staker && self.active_stakers >= constants::MAX_STAKERS {
                 self.release_lock();
                 return Err(StakingError::MaxStakersReached);
             }
             
-            // Check if user already has an active stake
-            if let Some(existing_stake) = self.stakes.get(&caller) {
-                if existing_stake.active {
-                    self.release_lock();
-                    return Err(StakingError::NoActiveStake); // User already has active stake
-                }
+            if position_ids.len() >= constants::MAX_POSITIONS_PER_ACCOUNT as usize {
+                self.release_lock();
+                return Err(StakingError::TooManyPositions);
             }
             
+            let position_id = self.next_position_id.get(caller).unwrap_or(0);
+            
             // Determina tier baseado na duração
             let tier = self.calculate_staking_tier(duration);
             
-            // Determina early adopter tier
-            let early_adopter_tier = self.determine_early_adopter_tier();
+            // Early adopter tier apenas na primeira posição da conta,
+            // para que novas posições não consumam vagas do ranking
+            let early_adopter_tier = if position_id == 0 {
+                self.determine_early_adopter_tier()
+            } else {
+                EarlyAdopterTier::None
+            };
             
             // Create new stake position
             let stake_position = StakePosition {
@@ -655,47 +782,60 @@ pub mod staking_contract {
                 active: true,
                 tier,
                 early_adopter_tier,
-                governance_participation: 0,
             };
             
             // Update state
-            self.stakes.insert(&caller, &stake_position);
-            self.staker_addresses.insert(&self.staker_index, &caller);
-            self.staker_index = self.staker_index.checked_add(1).ok_or(StakingError::Overflow)?;
-            self.active_stakers = self.active_stakers.checked_add(1).ok_or(StakingError::Overflow)?;
+            self.stakes.insert((caller, position_id), &stake_position);
+            position_ids.push(position_id);
+            self.account_positions.insert(caller, &position_ids);
+            self.next_position_id.insert(caller, &position_id.checked_add(1).ok_or(StakingError::Overflow)?);
+            
+            // Endereço registrado uma única vez para a distribuição paginada
+            if position_id == 0 {
+                self.staker_addresses.insert(self.staker_index, &caller);
+                self.staker_index = self.staker_index.checked_add(1).ok_or(StakingError::Overflow)?;
+            }
+            if is_new_staker {
+                self.active_stakers = self.active_stakers.checked_add(1).ok_or(StakingError::Overflow)?;
+            }
             self.total_staked = self.total_staked.checked_add(amount)
                 .ok_or(StakingError::Overflow)?;
+            self.adjust_total_staker_weight(0, self.calculate_staker_weight(&stake_position))?;
             
             // Emit event
             self.env().emit_event(Staked {
                 staker: caller,
+                position_id,
                 amount,
                 duration,
                 timestamp: current_time,
             });
             
             self.release_lock();
-            Ok(())
+            Ok(position_id)
         }
 
-        /// Unstakes tokens and claims rewards
+        /// Unstakes a single position and claims its rewards
         #[ink(message)]
-        pub fn unstake(&mut self) -> Result<(), StakingError> {
+        pub fn unstake(&mut self, position_id: u32) -> Result<(), StakingError> {
             self.ensure_not_paused()?;
             self.acquire_lock()?;
             
             let caller = self.env().caller();
             let current_time = self.env().block_timestamp();
             
-            let mut stake = self.stakes.get(&caller).ok_or(StakingError::NoActiveStake)?;
-            
-            if !stake.active {
-                self.release_lock();
-                return Err(StakingError::NoActiveStake);
-            }
+            let mut stake = match self.stakes.get((caller, position_id)) {
+                Some(stake) if stake.active => stake,
+                _ => {
+                    self.release_lock();
+                    return Err(StakingError::NoActiveStake);
+                }
+            };
             
-            // Calculate rewards and penalties
-            let (rewards, penalty) = self.calculate_rewards_and_penalty(&stake, current_time)?;
+            // Calculate rewards (capped to the budget) and penalties
+            self.settle_rewards(caller, &mut stake, current_time)?;
+            let rewards = stake.pending_rewards;
+            let penalty = self.calculate_early_penalty(&stake, current_time)?;
             let total_amount = stake.amount.checked_add(rewards)
                 .ok_or(StakingError::Overflow)?
                 .checked_sub(penalty)
@@ -703,12 +843,27 @@ pub mod staking_contract {
             
             // Update state
             stake.active = false;
-            self.stakes.insert(&caller, &stake);
-            self.active_stakers = self.active_stakers.checked_sub(1).ok_or(StakingError::Overflow)?;
+            stake.pending_rewards = 0;
+            self.stakes.insert((caller, position_id), &stake);
+            self.reserved_rewards = self.reserved_rewards.checked_sub(rewards)
+                .ok_or(StakingError::Overflow)?;
+            // Penalidade de saída antecipada realimenta o orçamento de rewards
+            self.rewards_budget = self.rewards_budget.checked_add(penalty)
+                .ok_or(StakingError::Overflow)?;
+            
+            let mut position_ids = self.account_positions.get(caller).unwrap_or_default();
+            position_ids.retain(|id| *id != position_id);
+            if position_ids.is_empty() {
+                self.account_positions.remove(caller);
+                self.active_stakers = self.active_stakers.checked_sub(1).ok_or(StakingError::Overflow)?;
+            } else {
+                self.account_positions.insert(caller, &position_ids);
+            }
             self.total_staked = self.total_staked.checked_sub(stake.amount)
                 .ok_or(StakingError::Overflow)?;
             self.total_rewards_distributed = self.total_rewards_distributed.checked_add(rewards)
                 .ok_or(StakingError::Overflow)?;
+            self.adjust_total_staker_weight(self.calculate_staker_weight(&stake), 0)?;
             
             // Transfer tokens back to user
             if self.env().transfer(caller, total_amount).is_err() {
@@ -719,6 +874,7 @@ pub mod staking_contract {
             // Emit event
             self.env().emit_event(Unstaked {
                 staker: caller,
+                position_id,
                 amount: stake.amount,
                 rewards,
                 penalty,
@@ -729,34 +885,37 @@ pub mod staking_contract {
             Ok(())
         }
 
-        /// Claims pending rewards without unstaking
+        /// Claims pending rewards of a position without unstaking
         #[ink(message)]
-        pub fn claim_rewards(&mut self) -> Result<(), StakingError> {
+        pub fn claim_rewards(&mut self, position_id: u32) -> Result<(), StakingError> {
             self.ensure_not_paused()?;
             self.acquire_lock()?;
             
             let caller = self.env().caller();
             let current_time = self.env().block_timestamp();
             
-            let mut stake = self.stakes.get(&caller).ok_or(StakingError::NoActiveStake)?;
+            let mut stake = match self.stakes.get((caller, position_id)) {
+                Some(stake) if stake.active => stake,
+                _ => {
+                    self.release_lock();
+                    return Err(StakingError::NoActiveStake);
+                }
+            };
             
-            if !stake.active {
-                self.release_lock();
-                return Err(StakingError::NoActiveStake);
-            }
+            self.settle_rewards(caller, &mut stake, current_time)?;
+            let rewards = stake.pending_rewards;
             
-            let rewards = self.calculate_pending_rewards(&stake, current_time)?;
+            // Update stake
+            stake.pending_rewards = 0;
+            self.stakes.insert((caller, position_id), &stake);
             
             if rewards == 0 {
                 self.release_lock();
                 return Ok(()); // No rewards to claim
             }
             
-            // Update stake
-            stake.last_claim = current_time;
-            stake.pending_rewards = 0;
-            self.stakes.insert(&caller, &stake);
-            
+            self.reserved_rewards = self.reserved_rewards.checked_sub(rewards)
+                .ok_or(StakingError::Overflow)?;
             self.total_rewards_distributed = self.total_rewards_distributed.checked_add(rewards)
                 .ok_or(StakingError::Overflow)?;
             
@@ -769,6 +928,7 @@ pub mod staking_contract {
             // Emit event
             self.env().emit_event(RewardsClaimed {
                 staker: caller,
+                position_id,
                 amount: rewards,
                 timestamp: current_time,
             });
@@ -777,6 +937,128 @@ pub mod staking_contract {
             Ok(())
         }
 
+        /// Adds LUNES to an existing position
+        ///
+        /// Pending rewards are settled (within the rewards budget) at the old
+        /// amount first; the quantity
+        /// multiplier then follows the new amount. Duration and early adopter
+        /// tier are kept. Like `extend_duration`, the position must stay locked
+        /// for more than `MIN_DURATION` from now; if it would not, the lock
+        /// restarts now with the same duration, so a larger amount cannot be
+        /// added to an expired stake and withdrawn without penalty.
+        #[ink(message, payable)]
+        pub fn increase_stake(&mut self, position_id: u32) -> Result<(), StakingError> {
+            self.ensure_not_paused()?;
+            self.acquire_lock()?;
+            
+            let caller = self.env().caller();
+            let amount = self.env().transferred_value();
+            let current_time = self.env().block_timestamp();
+            
+            if amount == 0 {
+                self.release_lock();
+                return Err(StakingError::ZeroAmount);
+            }
+            
+            let mut stake = match self.stakes.get((caller, position_id)) {
+                Some(stake) if stake.active => stake,
+                _ => {
+                    self.release_lock();
+                    return Err(StakingError::NoActiveStake);
+                }
+            };
+            
+            self.settle_rewards(caller, &mut stake, current_time)?;
+            let old_weight = self.calculate_staker_weight(&stake);
+            stake.amount = stake.amount.checked_add(amount)
+                .ok_or(StakingError::Overflow)?;
+            if stake.unlock_time() <= current_time.saturating_add(constants::MIN_DURATION) {
+                stake.start_time = current_time;
+            }
+            self.stakes.insert((caller, position_id), &stake);
+            self.total_staked = self.total_staked.checked_add(amount)
+                .ok_or(StakingError::Overflow)?;
+            self.adjust_total_staker_weight(old_weight, self.calculate_staker_weight(&stake))?;
+            
+            self.env().emit_event(StakeModified {
+                staker: caller,
+                position_id,
+                amount: stake.amount,
+                duration: stake.duration,
+                tier: stake.tier,
+                settled_rewards: stake.pending_rewards,
+                timestamp: current_time,
+            });
+            
+            self.release_lock();
+            Ok(())
+        }
+
+        /// Extends the lock duration of an existing position
+        ///
+        /// `new_duration` is counted from the original start time and must be
+        /// longer than the current one. The extended lock must still run for at
+        /// least `MIN_DURATION` from now, so an expired or nearly expired stake
+        /// cannot buy a higher tier without new lock time. Pending rewards are
+        /// settled at the old tier before the tier is recomputed.
+        #[ink(message)]
+        pub fn extend_duration(&mut self, position_id: u32, new_duration: u64) -> Result<(), StakingError> {
+            self.ensure_not_paused()?;
+            self.acquire_lock()?;
+            
+            let caller = self.env().caller();
+            let current_time = self.env().block_timestamp();
+            
+            let mut stake = match self.stakes.get((caller, position_id)) {
+                Some(stake) if stake.active => stake,
+                _ => {
+                    self.release_lock();
+                    return Err(StakingError::NoActiveStake);
+                }
+            };
+            
+            if new_duration <= stake.duration || new_duration > constants::MAX_DURATION {
+                self.release_lock();
+                return Err(StakingError::InvalidDuration);
+            }
+            
+            let new_unlock_time = stake.start_time.saturating_add(new_duration);
+            if new_unlock_time <= current_time.saturating_add(constants::MIN_DURATION) {
+                self.release_lock();
+                return Err(StakingError::InvalidDuration);
+            }
+
+            self.settle_rewards(caller, &mut stake, current_time)?;
+            let old_tier = stake.tier;
+            let old_weight = self.calculate_staker_weight(&stake);
+            stake.duration = new_duration;
+            stake.tier = self.calculate_staking_tier(new_duration);
+            self.stakes.insert((caller, position_id), &stake);
+            self.adjust_total_staker_weight(old_weight, self.calculate_staker_weight(&stake))?;
+            
+            if stake.tier != old_tier {
+                self.env().emit_event(TierUpgraded {
+                    staker: caller,
+                    old_tier,
+                    new_tier: stake.tier,
+                    timestamp: current_time,
+                });
+            }
+            
+            self.env().emit_event(StakeModified {
+                staker: caller,
+                position_id,
+                amount: stake.amount,
+                duration: new_duration,
+                tier: stake.tier,
+                settled_rewards: stake.pending_rewards,
+                timestamp: current_time,
+            });
+            
+            self.release_lock();
+            Ok(())
+        }
+
         /// Creates a new project listing proposal
         #[ink(message, payable)]
         pub fn create_proposal(
@@ -956,37 +1238,76 @@ pub mod staking_contract {
 
         // === Query Methods ===
 
-        /// Gets stake information for an account
+        /// Gets a stake position (active or closed) of an account
         #[ink(message)]
-        pub fn get_stake(&self, account: AccountId) -> Option<StakePosition> {
-            self.stakes.get(&account)
+        pub fn get_stake(&self, account: AccountId, position_id: u32) -> Option<StakePosition> {
+            self.stakes.get((account, position_id))
         }
 
-        /// Gets current rewards for a staker
+        /// Number of active positions of an account
         #[ink(message)]
-        pub fn get_pending_rewards(&self, account: AccountId) -> Result<Balance, StakingError> {
-            let stake = self.stakes.get(&account).ok_or(StakingError::NoActiveStake)?;
+        pub fn get_position_count(&self, account: AccountId) -> u32 {
+            self.account_positions
+                .get(account)
+                .map(|ids| ids.len() as u32)
+                .unwrap_or(0)
+        }
+
+        /// Lists active positions of an account, paginated
+        ///
+        /// Returns up to `limit` `(position_id, position)` pairs starting at
+        /// index `start` of the account's active positions.
+        #[ink(message)]
+        pub fn get_positions(&self, account: AccountId, start: u32, limit: u32) -> Vec<(u32, StakePosition)> {
+            self.account_positions
+                .get(account)
+                .unwrap_or_default()
+                .into_iter()
+                .skip(start as usize)
+                .take(limit.min(constants::MAX_POSITIONS_PER_ACCOUNT) as usize)
+                .filter_map(|id| self.stakes.get((account, id)).map(|stake| (id, stake)))
+                .collect()
+        }
+
+        /// Highest staking tier among the active positions of an account
+        #[ink(message)]
+        pub fn get_account_tier(&self, account: AccountId) -> Option<StakingTier> {
+            self.active_positions(account)
+                .into_iter()
+                .map(|(_, stake)| stake.tier)
+                .max()
+        }
+
+        /// Gets current rewards for a stake position
+        #[ink(message)]
+        pub fn get_pending_rewards(&self, account: AccountId, position_id: u32) -> Result<Balance, StakingError> {
+            let stake = self.stakes.get((account, position_id)).ok_or(StakingError::NoActiveStake)?;
             
             if !stake.active {
                 return Err(StakingError::NoActiveStake);
             }
             
             let current_time = self.env().block_timestamp();
-            self.calculate_pending_rewards(&stake, current_time)
+            let accrued = self.calculate_pending_rewards(&stake, current_time)?;
+            stake.pending_rewards
+                .checked_add(accrued.min(self.rewards_budget))
+                .ok_or(StakingError::Overflow)
         }
 
-        /// Gets voting power for an account
+        /// Gets voting power for an account (sum of its active positions)
         #[ink(message)]
         pub fn get_voting_power(&self, account: AccountId) -> Result<Balance, StakingError> {
-            if let Some(stake) = self.stakes.get(&account) {
-                if stake.active {
-                    Ok(stake.amount)
-                } else {
-                    Ok(0)
-                }
-            } else {
-                Ok(0)
-            }
+            self.active_positions(account)
+                .iter()
+                .try_fold(0 as Balance, |power, (_, stake)| {
+                    power.checked_add(stake.amount).ok_or(StakingError::Overflow)
+                })
+        }
+
+        /// Number of governance votes recorded for an account
+        #[ink(message)]
+        pub fn get_vote_participation(&self, account: AccountId) -> u32 {
+            self.vote_participation.get(account).unwrap_or(0)
         }
 
         /// Gets proposal information
@@ -1225,12 +1546,44 @@ pub mod staking_contract {
             self.current_proposal_fee
         }
 
+        /// Orçamento de rewards ainda não reservado
+        #[ink(message)]
+        pub fn rewards_budget(&self) -> Balance {
+            self.rewards_budget
+        }
+
+        /// Relatório de solvência: principal, orçamento, passivos e déficit
+        #[ink(message)]
+        pub fn solvency(&self) -> SolvencyReport {
+            // O que já foi creditado numa distribuição em curso está em
+            // `reserved_rewards`; só o restante do pool conta à parte
+            let undistributed = self.trading_rewards_pool.saturating_sub(self.trading_rewards_in_flight);
+            let liabilities = self.reserved_rewards.saturating_add(undistributed);
+            let required = self.total_staked
+                .saturating_add(self.rewards_budget)
+                .saturating_add(liabilities);
+            let native_balance = self.env().balance();
+            SolvencyReport {
+                principal: self.total_staked,
+                rewards_budget: self.rewards_budget,
+                liabilities,
+                native_balance,
+                shortfall: required.saturating_sub(native_balance),
+            }
+        }
+
         /// Gets contract statistics
         #[ink(message)]
         pub fn get_stats(&self) -> (Balance, Balance, u32) {
             (self.total_staked, self.total_rewards_distributed, self.active_stakers)
         }
 
+        /// Gets the summed trading-reward weight of all active positions
+        #[ink(message)]
+        pub fn get_total_staker_weight(&self) -> Balance {
+            self.total_staker_weight
+        }
+
         // === Admin Methods ===
 
         /// Pauses the contract (admin only)
@@ -1310,23 +1663,41 @@ pub mod staking_contract {
             Ok(())
         }
 
-        /// Recebe trading rewards do contrato de rewards (apenas contrato autorizado)
+        /// Financia o orçamento de rewards do staking
+        ///
+        /// Aceito do contrato de trading rewards ou do owner. O valor fica no
+        /// `rewards_budget`, separado do principal (`total_staked`), e é a única
+        /// fonte de APY e bônus de governança. A parcela das trading fees que
+        /// deve ser repartida entre os stakers entra por `fund_trading_rewards_pool`.
         #[ink(message, payable)]
         pub fn fund_staking_rewards(&mut self) -> Result<(), StakingError> {
-            self.ensure_not_paused()?;
-            
-            // Verifica se é o contrato autorizado
-            let caller = self.env().caller();
-            if self.trading_rewards_contract != Some(caller) {
-                return Err(StakingError::AccessDenied);
-            }
+            let amount = self.ensure_rewards_funding()?;
+
+            self.rewards_budget = self
+                .rewards_budget
+                .checked_add(amount)
+                .ok_or(StakingError::Overflow)?;
             
-            let amount = self.env().transferred_value();
-            if amount == 0 {
-                return Err(StakingError::ZeroAmount);
-            }
+            // Emit event
+            self.env().emit_event(RewardsBudgetFunded {
+                amount,
+                timestamp: self.env().block_timestamp(),
+            });
             
-            self.trading_rewards_pool = self.trading_rewards_pool
+            Ok(())
+        }
+
+        /// Recebe a parcela de trading fees destinada aos stakers
+        ///
+        /// Chamado pelo contrato de trading rewards (ou pelo owner). O valor vai
+        /// para o `trading_rewards_pool` e é repartido pro-rata ao peso de cada
+        /// posição em `distribute_trading_rewards_paginated`; não alimenta o APY.
+        #[ink(message, payable)]
+        pub fn fund_trading_rewards_pool(&mut self) -> Result<(), StakingError> {
+            let amount = self.ensure_rewards_funding()?;
+
+            self.trading_rewards_pool = self
+                .trading_rewards_pool
                 .checked_add(amount)
                 .ok_or(StakingError::Overflow)?;
             
@@ -1339,6 +1710,23 @@ pub mod staking_contract {
             Ok(())
         }
 
+        /// Valida um aporte de rewards e retorna o valor transferido
+        fn ensure_rewards_funding(&self) -> Result<Balance, StakingError> {
+            self.ensure_not_paused()?;
+
+            // Verifica se é o contrato autorizado ou o owner
+            let caller = self.env().caller();
+            if self.trading_rewards_contract != Some(caller) && caller != self.owner {
+                return Err(StakingError::AccessDenied);
+            }
+
+            let amount = self.env().transferred_value();
+            if amount == 0 {
+                return Err(StakingError::ZeroAmount);
+            }
+            Ok(amount)
+        }
+
         /// Distribui trading rewards para todos os stakers ativos (apenas admin)
         /// Para compatibilidade, distribui tudo em uma página
         #[ink(message)]
@@ -1359,6 +1747,11 @@ pub mod staking_contract {
         ///   - processed_count: número de stakers processados
         ///   - is_complete: se a distribuição foi finalizada
         ///   - next_index: próximo índice para continuar (se não completo)
+        ///
+        /// O valor creditado entre páginas é acumulado em
+        /// `trading_rewards_in_flight` e nunca passa do pool; ao completar, só
+        /// ele sai do pool e o resto de arredondamento fica para a próxima
+        /// distribuição.
         #[ink(message)]
         pub fn distribute_trading_rewards_paginated(
             &mut self, 
@@ -1374,7 +1767,7 @@ pub mod staking_contract {
                 return Ok((0, true, None)); // Nada para distribuir
             }
             
-            let total_weight = self.calculate_total_staker_weight()?;
+            let total_weight = self.total_staker_weight;
             if total_weight == 0 {
                 self.release_lock();
                 return Ok((0, true, None)); // Nenhum staker ativo
@@ -1393,25 +1786,35 @@ pub mod staking_contract {
                 processed_count = processed_count.checked_add(1).ok_or(StakingError::Overflow)?;
                 
                 if let Some(staker) = self.staker_addresses.get(&i) {
-                    if let Some(mut stake) = self.stakes.get(&staker) {
-                        if stake.active {
-                            let weight = self.calculate_staker_weight(&stake);
-                            let reward = amount_to_distribute
-                                .checked_mul(weight)
-                                .ok_or(StakingError::Overflow)?
-                                .checked_div(total_weight)
+                    let mut rewarded = false;
+                    for (position_id, mut stake) in self.active_positions(staker) {
+                        let weight = self.calculate_staker_weight(&stake);
+                        let remaining = amount_to_distribute.saturating_sub(self.trading_rewards_in_flight);
+                        let reward = amount_to_distribute
+                            .checked_mul(weight)
+                            .ok_or(StakingError::Overflow)?
+                            .checked_div(total_weight)
+                            .ok_or(StakingError::Overflow)?
+                            .min(remaining);
+                        
+                        if reward > 0 {
+                            stake.pending_rewards = stake.pending_rewards
+                                .checked_add(reward)
+                                .ok_or(StakingError::Overflow)?;
+                            self.reserved_rewards = self.reserved_rewards
+                                .checked_add(reward)
+                                .ok_or(StakingError::Overflow)?;
+                            self.trading_rewards_in_flight = self.trading_rewards_in_flight
+                                .checked_add(reward)
                                 .ok_or(StakingError::Overflow)?;
                             
-                            if reward > 0 {
-                                stake.pending_rewards = stake.pending_rewards
-                                    .checked_add(reward)
-                                    .ok_or(StakingError::Overflow)?;
-                                
-                                self.stakes.insert(&staker, &stake);
-                                distributed_count = distributed_count.checked_add(1).ok_or(StakingError::Overflow)?;
-                            }
+                            self.stakes.insert((staker, position_id), &stake);
+                            rewarded = true;
                         }
                     }
+                    if rewarded {
+                        distributed_count = distributed_count.checked_add(1).ok_or(StakingError::Overflow)?;
+                    }
                 }
             }
             
@@ -1421,15 +1824,20 @@ pub mod staking_contract {
             // Se é a primeira página ou a distribuição está completa, atualiza estado global
             if start == 0 || is_complete {
                 if is_complete {
-                    // Finaliza a distribuição
+                    // Finaliza a distribuição; o resto de arredondamento
+                    // permanece no pool
+                    let distributed = self.trading_rewards_in_flight;
                     self.total_trading_rewards_distributed = self.total_trading_rewards_distributed
-                        .checked_add(amount_to_distribute)
+                        .checked_add(distributed)
+                        .ok_or(StakingError::Overflow)?;
+                    self.trading_rewards_pool = self.trading_rewards_pool
+                        .checked_sub(distributed)
                         .ok_or(StakingError::Overflow)?;
-                    self.trading_rewards_pool = 0;
+                    self.trading_rewards_in_flight = 0;
                     
                     // Emit event final
                     self.env().emit_event(TradingRewardsDistributed {
-                        total_amount: amount_to_distribute,
+                        total_amount: distributed,
                         stakers_count: distributed_count,
                         timestamp: self.env().block_timestamp(),
                     });
@@ -1457,29 +1865,16 @@ pub mod staking_contract {
         pub fn record_vote_participation(&mut self, voter: AccountId) -> Result<(), StakingError> {
             self.ensure_owner()?; // Por enquanto apenas admin, depois será o contrato de governança
             
-            if let Some(mut stake) = self.stakes.get(&voter) {
-                if stake.active {
-                    stake.governance_participation = stake.governance_participation
-                        .checked_add(1)
-                        .ok_or(StakingError::Overflow)?;
-                    
-                    self.stakes.insert(&voter, &stake);
-                    
-                    // Bônus por participação ativa (cada 8 votos = 200 LUNES)
-                    if stake.governance_participation % constants::MIN_VOTES_FOR_BONUS == 0 {
-                        let bonus = constants::VOTING_BONUS;
-                        let current_bonus = self.governance_bonuses.get(&voter).unwrap_or(0);
-                        let new_bonus = current_bonus.checked_add(bonus).ok_or(StakingError::Overflow)?;
-                        self.governance_bonuses.insert(&voter, &new_bonus);
-                        
-                        // Emit event
-                        self.env().emit_event(GovernanceBonusAwarded {
-                            staker: voter,
-                            bonus_type: 1, // 1 = voting bonus
-                            amount: bonus,
-                            timestamp: self.env().block_timestamp(),
-                        });
-                    }
+            if self.get_position_count(voter) > 0 {
+                let participation = self.vote_participation.get(voter).unwrap_or(0)
+                    .checked_add(1)
+                    .ok_or(StakingError::Overflow)?;
+                
+                self.vote_participation.insert(voter, &participation);
+                
+                // Bônus por participação ativa (cada 8 votos = 200 LUNES)
+                if participation % constants::MIN_VOTES_FOR_BONUS == 0 {
+                    self.award_governance_bonus(voter, constants::VOTING_BONUS, 1)?; // 1 = voting bonus
                 }
             }
             
@@ -1491,18 +1886,7 @@ pub mod staking_contract {
         pub fn reward_approved_proposal(&mut self, proposer: AccountId) -> Result<(), StakingError> {
             self.ensure_owner()?; // Por enquanto apenas admin, depois será o contrato de governança
             
-            let bonus = constants::PROPOSAL_BONUS;
-            let current_bonus = self.governance_bonuses.get(&proposer).unwrap_or(0);
-            let new_bonus = current_bonus.checked_add(bonus).ok_or(StakingError::Overflow)?;
-            self.governance_bonuses.insert(&proposer, &new_bonus);
-            
-            // Emit event
-            self.env().emit_event(GovernanceBonusAwarded {
-                staker: proposer,
-                bonus_type: 2, // 2 = proposal bonus
-                amount: bonus,
-                timestamp: self.env().block_timestamp(),
-            });
+            self.award_governance_bonus(proposer, constants::PROPOSAL_BONUS, 2)?; // 2 = proposal bonus
             
             Ok(())
         }
@@ -1512,18 +1896,7 @@ pub mod staking_contract {
         pub fn reward_implemented_proposal(&mut self, proposer: AccountId) -> Result<(), StakingError> {
             self.ensure_owner()?; // Por enquanto apenas admin, depois será o contrato de governança
             
-            let bonus = constants::IMPLEMENTATION_BONUS;
-            let current_bonus = self.governance_bonuses.get(&proposer).unwrap_or(0);
-            let new_bonus = current_bonus.checked_add(bonus).ok_or(StakingError::Overflow)?;
-            self.governance_bonuses.insert(&proposer, &new_bonus);
-            
-            // Emit event
-            self.env().emit_event(GovernanceBonusAwarded {
-                staker: proposer,
-                bonus_type: 3, // 3 = implementation bonus
-                amount: bonus,
-                timestamp: self.env().block_timestamp(),
-            });
+            self.award_governance_bonus(proposer, constants::IMPLEMENTATION_BONUS, 3)?; // 3 = implementation bonus
             
             Ok(())
         }
@@ -1544,6 +1917,8 @@ pub mod staking_contract {
             
             // Reset bonus
             self.governance_bonuses.remove(&caller);
+            self.reserved_rewards = self.reserved_rewards.checked_sub(bonus)
+                .ok_or(StakingError::Overflow)?;
             
             // Transfer LUNES
             if self.env().transfer(caller, bonus).is_err() {
@@ -1594,7 +1969,71 @@ pub mod staking_contract {
             self.locked = false;
         }
 
-        /// Calculates pending rewards for a stake position with new tier system
+        /// Active positions of an account as `(position_id, position)` pairs
+        fn active_positions(&self, account: AccountId) -> Vec<(u32, StakePosition)> {
+            self.account_positions
+                .get(account)
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|id| self.stakes.get((account, id)).map(|stake| (id, stake)))
+                .filter(|(_, stake)| stake.active)
+                .collect()
+        }
+
+        /// Reserves up to `amount` from the rewards budget, returning what was reserved
+        fn reserve_rewards(&mut self, amount: Balance) -> Result<Balance, StakingError> {
+            let reserved = amount.min(self.rewards_budget);
+            self.rewards_budget = self.rewards_budget.checked_sub(reserved)
+                .ok_or(StakingError::Overflow)?;
+            self.reserved_rewards = self.reserved_rewards.checked_add(reserved)
+                .ok_or(StakingError::Overflow)?;
+            Ok(reserved)
+        }
+
+        /// Moves rewards accrued since `last_claim` into `pending_rewards`
+        ///
+        /// Accrual is capped to the remaining rewards budget; the uncovered part
+        /// is forfeited and reported through `RewardsCapped`.
+        fn settle_rewards(&mut self, staker: AccountId, stake: &mut StakePosition, current_time: Timestamp) -> Result<(), StakingError> {
+            let accrued = self.calculate_pending_rewards(stake, current_time)?;
+            let credited = self.reserve_rewards(accrued)?;
+            stake.pending_rewards = stake.pending_rewards.checked_add(credited)
+                .ok_or(StakingError::Overflow)?;
+            stake.last_claim = current_time;
+            
+            if credited < accrued {
+                self.env().emit_event(RewardsCapped {
+                    staker,
+                    accrued,
+                    credited,
+                    timestamp: current_time,
+                });
+            }
+            Ok(())
+        }
+
+        /// Credits a governance bonus reserved from the rewards budget
+        fn award_governance_bonus(&mut self, staker: AccountId, bonus: Balance, bonus_type: u8) -> Result<(), StakingError> {
+            let amount = self.reserve_rewards(bonus)?;
+            if amount == 0 {
+                return Ok(());
+            }
+            
+            let current_bonus = self.governance_bonuses.get(staker).unwrap_or(0);
+            let new_bonus = current_bonus.checked_add(amount).ok_or(StakingError::Overflow)?;
+            self.governance_bonuses.insert(staker, &new_bonus);
+            
+            // Emit event
+            self.env().emit_event(GovernanceBonusAwarded {
+                staker,
+                bonus_type,
+                amount,
+                timestamp: self.env().block_timestamp(),
+            });
+            Ok(())
+        }
+
+        /// Calculates rewards accrued since `last_claim` with new tier system
         fn calculate_pending_rewards(&self, stake: &StakePosition, current_time: Timestamp) -> Result<Balance, StakingError> {
             let time_staked = current_time.checked_sub(stake.last_claim)
                 .ok_or(StakingError::Overflow)?;
@@ -1610,8 +2049,7 @@ pub mod staking_contract {
             let early_adopter_bonus = self.get_early_adopter_bonus(stake, current_time);
             
             // Calculate base rewards
-            // Formula: (amount * rate * time) / (basis_points * year_in_blocks)
-            let year_in_blocks: u128 = 365 * 24 * 60 * 30; // Approximate blocks per year
+            // Formula: (amount * rate * time) / (basis_points * one_year), time in ms
             
             let base_rewards = (stake.amount as u128)
                 .checked_mul(base_rate as u128)
@@ -1620,7 +2058,7 @@ pub mod staking_contract {
                 .ok_or(StakingError::Overflow)?
                 .checked_div(constants::BASIS_POINTS as u128)
                 .ok_or(StakingError::Overflow)?
-                .checked_div(year_in_blocks)
+                .checked_div(constants::ONE_YEAR as u128)
                 .ok_or(StakingError::Overflow)? as Balance;
             
             // Apply quantity multiplier
@@ -1644,11 +2082,11 @@ pub mod staking_contract {
 
         /// Calcula tier baseado na duração do stake
         fn calculate_staking_tier(&self, duration: u64) -> StakingTier {
-            if duration >= 181 * 24 * 60 * 30 {        // 181+ dias
+            if duration >= 181 * constants::ONE_DAY {        // 181+ dias
                 StakingTier::Platinum
-            } else if duration >= 91 * 24 * 60 * 30 {  // 91-180 dias
+            } else if duration >= 91 * constants::ONE_DAY {  // 91-180 dias
                 StakingTier::Gold
-            } else if duration >= 31 * 24 * 60 * 30 {  // 31-90 dias
+            } else if duration >= 31 * constants::ONE_DAY {  // 31-90 dias
                 StakingTier::Silver
             } else {                                    // 7-30 dias
                 StakingTier::Bronze
@@ -1741,30 +2179,18 @@ pub mod staking_contract {
                 .unwrap_or(0)
         }
 
-        /// Calcula peso total de todos os stakers ativos
-        fn calculate_total_staker_weight(&self) -> Result<Balance, StakingError> {
-            let mut total_weight = 0u128;
-            
-            for i in 0..self.staker_index {
-                if let Some(staker) = self.staker_addresses.get(&i) {
-                    if let Some(stake) = self.stakes.get(&staker) {
-                        if stake.active {
-                            let weight = self.calculate_staker_weight(&stake);
-                            total_weight = total_weight
-                                .checked_add(weight as u128)
-                                .ok_or(StakingError::Overflow)?;
-                        }
-                    }
-                }
-            }
-            
-            Ok(total_weight as Balance)
+        /// Substitui o peso antigo de uma posição pelo novo no total agregado
+        fn adjust_total_staker_weight(&mut self, old_weight: Balance, new_weight: Balance) -> Result<(), StakingError> {
+            self.total_staker_weight = self.total_staker_weight
+                .checked_sub(old_weight)
+                .ok_or(StakingError::Overflow)?
+                .checked_add(new_weight)
+                .ok_or(StakingError::Overflow)?;
+            Ok(())
         }
 
-        /// Calculates rewards and early unstaking penalty
-        fn calculate_rewards_and_penalty(&self, stake: &StakePosition, current_time: Timestamp) -> Result<(Balance, Balance), StakingError> {
-            let rewards = self.calculate_pending_rewards(stake, current_time)?;
-            
+        /// Calculates the early unstaking penalty
+        fn calculate_early_penalty(&self, stake: &StakePosition, current_time: Timestamp) -> Result<Balance, StakingError> {
             let time_staked = current_time.checked_sub(stake.start_time)
                 .ok_or(StakingError::Overflow)?;
             
@@ -1778,7 +2204,7 @@ pub mod staking_contract {
                 0
             };
             
-            Ok((rewards, penalty))
+            Ok(penalty)
         }
     }
 
@@ -1809,12 +2235,515 @@ pub mod staking_contract {
             
             assert!(result.is_ok());
             
-            let stake = contract.get_stake(accounts.alice).unwrap();
+            let stake = contract.get_stake(accounts.alice, 0).unwrap();
             assert_eq!(stake.amount, constants::MIN_STAKE);
             assert_eq!(stake.duration, constants::MIN_DURATION);
             assert!(stake.active);
         }
 
+        #[ink::test]
+        fn test_multiple_positions_per_account() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            
+            let mut contract = StakingContract::new(AccountId::from([0x1; 32]));
+            
+            // GREEN: ladder de 30 e 365 dias na mesma conta
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(constants::MIN_STAKE);
+            assert_eq!(contract.stake(30 * constants::ONE_DAY), Ok(0));
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(constants::MIN_STAKE * 2);
+            assert_eq!(contract.stake(constants::MAX_DURATION), Ok(1));
+            
+            assert_eq!(contract.get_stake(accounts.alice, 0).unwrap().tier, StakingTier::Bronze);
+            assert_eq!(contract.get_stake(accounts.alice, 1).unwrap().tier, StakingTier::Platinum);
+            
+            // Somente a primeira posição ocupa vaga de early adopter
+            assert_eq!(contract.get_stake(accounts.alice, 0).unwrap().early_adopter_tier, EarlyAdopterTier::Top100);
+            assert_eq!(contract.get_stake(accounts.alice, 1).unwrap().early_adopter_tier, EarlyAdopterTier::None);
+            
+            // Voting power soma as posições; a conta conta como um staker
+            assert_eq!(contract.get_voting_power(accounts.alice), Ok(constants::MIN_STAKE * 3));
+            assert_eq!(contract.get_account_tier(accounts.alice), Some(StakingTier::Platinum));
+            let (total_staked, _, active_stakers) = contract.get_stats();
+            assert_eq!(total_staked, constants::MIN_STAKE * 3);
+            assert_eq!(active_stakers, 1);
+            
+            // Listagem paginada
+            assert_eq!(contract.get_position_count(accounts.alice), 2);
+            let page = contract.get_positions(accounts.alice, 1, 10);
+            assert_eq!(page.len(), 1);
+            assert_eq!(page[0].0, 1);
+            assert_eq!(page[0].1.amount, constants::MIN_STAKE * 2);
+            assert_eq!(contract.get_positions(accounts.alice, 0, 1).len(), 1);
+            assert!(contract.get_positions(accounts.bob, 0, 10).is_empty());
+        }
+
+        #[ink::test]
+        fn test_unstake_single_position() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            
+            let mut contract = StakingContract::new(AccountId::from([0x1; 32]));
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(constants::MIN_STAKE);
+            let short = contract.stake(constants::MIN_DURATION).unwrap();
+            let long = contract.stake(constants::MAX_DURATION).unwrap();
+            test::set_account_balance::<ink::env::DefaultEnvironment>(test::callee::<ink::env::DefaultEnvironment>(), constants::MIN_STAKE * 2);
+            
+            // RED: posição inexistente
+            assert_eq!(contract.unstake(99), Err(StakingError::NoActiveStake));
+            assert_eq!(contract.claim_rewards(99), Err(StakingError::NoActiveStake));
+            
+            // GREEN: encerrar uma posição mantém a outra ativa
+            assert!(contract.unstake(short).is_ok());
+            assert!(!contract.get_stake(accounts.alice, short).unwrap().active);
+            assert!(contract.get_stake(accounts.alice, long).unwrap().active);
+            assert_eq!(contract.get_voting_power(accounts.alice), Ok(constants::MIN_STAKE));
+            assert_eq!(contract.get_position_count(accounts.alice), 1);
+            assert_eq!(contract.get_stats().2, 1);
+            
+            // RED: posição já encerrada
+            assert_eq!(contract.unstake(short), Err(StakingError::NoActiveStake));
+            
+            assert!(contract.unstake(long).is_ok());
+            assert_eq!(contract.get_stats().2, 0);
+            assert_eq!(contract.get_account_tier(accounts.alice), None);
+        }
+
+        #[ink::test]
+        fn test_total_staker_weight_tracks_positions() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(constants::MIN_STAKE);
+
+            let mut contract = StakingContract::new(AccountId::from([0x1; 32]));
+            let short = contract.stake(constants::MIN_DURATION).unwrap();
+            let long = contract.stake(constants::MAX_DURATION).unwrap();
+            test::set_account_balance::<ink::env::DefaultEnvironment>(test::callee::<ink::env::DefaultEnvironment>(), constants::MIN_STAKE * 4);
+
+            let weight_of = |contract: &StakingContract, id: u32| {
+                contract.calculate_staker_weight(&contract.get_stake(accounts.alice, id).unwrap())
+            };
+
+            // GREEN: stake soma o peso de cada posição
+            assert_eq!(contract.get_total_staker_weight(), weight_of(&contract, short) + weight_of(&contract, long));
+
+            // GREEN: increase_stake e extend_duration trocam o peso antigo pelo novo
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(constants::MEDIUM_STAKE_THRESHOLD);
+            assert!(contract.increase_stake(short).is_ok());
+            assert!(contract.extend_duration(short, 120 * constants::ONE_DAY).is_ok());
+            assert_eq!(contract.get_total_staker_weight(), weight_of(&contract, short) + weight_of(&contract, long));
+
+            // GREEN: unstake remove apenas o peso da posição encerrada
+            assert!(contract.unstake(long).is_ok());
+            assert_eq!(contract.get_total_staker_weight(), weight_of(&contract, short));
+        }
+
+        #[ink::test]
+        fn test_max_positions_per_account() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(constants::MIN_STAKE);
+            
+            let mut contract = StakingContract::new(AccountId::from([0x1; 32]));
+            for _ in 0..constants::MAX_POSITIONS_PER_ACCOUNT {
+                assert!(contract.stake(constants::MIN_DURATION).is_ok());
+            }
+            
+            // RED: limite de posições simultâneas
+            assert_eq!(contract.stake(constants::MIN_DURATION), Err(StakingError::TooManyPositions));
+        }
+
+        #[ink::test]
+        fn test_increase_stake_settles_rewards() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(constants::MIN_STAKE);
+            
+            let mut contract = StakingContract::new(AccountId::from([0x1; 32]));
+            contract.fund_staking_rewards().unwrap();
+            let position_id = contract.stake(constants::MIN_DURATION).unwrap();
+            let before = contract.get_stake(accounts.alice, position_id).unwrap();
+            
+            // RED: sem valor transferido / posição inexistente
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            assert_eq!(contract.increase_stake(position_id), Err(StakingError::ZeroAmount));
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(constants::MIN_STAKE);
+            assert_eq!(contract.increase_stake(7), Err(StakingError::NoActiveStake));
+            
+            // GREEN: rewards acumulados são liquidados antes do aporte
+            let later = before.start_time.saturating_add(1_000_000);
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(later);
+            let accrued = contract.get_pending_rewards(accounts.alice, position_id).unwrap();
+            assert!(accrued > 0);
+            
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(constants::MEDIUM_STAKE_THRESHOLD);
+            assert!(contract.increase_stake(position_id).is_ok());
+            
+            let after = contract.get_stake(accounts.alice, position_id).unwrap();
+            assert_eq!(after.amount, constants::MIN_STAKE + constants::MEDIUM_STAKE_THRESHOLD);
+            assert_eq!(after.pending_rewards, accrued);
+            assert_eq!(after.last_claim, later);
+            // Lock de MIN_DURATION já consumido em parte: reinicia no aporte
+            assert_eq!(after.start_time, later);
+            assert_eq!(after.duration, before.duration);
+            assert_eq!(after.early_adopter_tier, before.early_adopter_tier);
+            assert_eq!(contract.get_pending_rewards(accounts.alice, position_id), Ok(accrued));
+            assert_eq!(contract.get_stats().0, constants::MIN_STAKE + constants::MEDIUM_STAKE_THRESHOLD);
+            assert_eq!(contract.get_quantity_multiplier(after.amount), constants::MEDIUM_STAKER_MULTIPLIER);
+        }
+
+        #[ink::test]
+        fn test_extend_duration_recomputes_tier() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(constants::MIN_STAKE);
+            
+            let mut contract = StakingContract::new(AccountId::from([0x1; 32]));
+            let position_id = contract.stake(constants::MIN_DURATION).unwrap();
+            assert_eq!(contract.get_stake(accounts.alice, position_id).unwrap().tier, StakingTier::Bronze);
+            
+            // RED: duração precisa aumentar e respeitar o máximo
+            assert_eq!(contract.extend_duration(position_id, constants::MIN_DURATION), Err(StakingError::InvalidDuration));
+            assert_eq!(
+                contract.extend_duration(position_id, constants::MAX_DURATION + 1),
+                Err(StakingError::InvalidDuration)
+            );
+            
+            // GREEN: Bronze -> Gold mantendo o early adopter tier
+            assert!(contract.extend_duration(position_id, 120 * constants::ONE_DAY).is_ok());
+            let stake = contract.get_stake(accounts.alice, position_id).unwrap();
+            assert_eq!(stake.tier, StakingTier::Gold);
+            assert_eq!(stake.duration, 120 * constants::ONE_DAY);
+            assert_eq!(stake.early_adopter_tier, EarlyAdopterTier::Top100);
+            assert_eq!(contract.get_account_tier(accounts.alice), Some(StakingTier::Gold));
+        }
+
+        #[ink::test]
+        fn test_extend_duration_requires_remaining_lock() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(constants::MIN_STAKE);
+
+            let mut contract = StakingContract::new(AccountId::from([0x1; 32]));
+            let position_id = contract.stake(constants::MIN_DURATION).unwrap();
+
+            // RED: expired lock cannot jump to Platinum without new lock time
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(200 * constants::ONE_DAY);
+            assert_eq!(
+                contract.extend_duration(position_id, 181 * constants::ONE_DAY),
+                Err(StakingError::InvalidDuration)
+            );
+
+            // RED: unlocking exactly MIN_DURATION from now is not enough
+            assert_eq!(
+                contract.extend_duration(position_id, 207 * constants::ONE_DAY),
+                Err(StakingError::InvalidDuration)
+            );
+            assert_eq!(
+                contract
+                    .get_stake(accounts.alice, position_id)
+                    .unwrap()
+                    .tier,
+                StakingTier::Bronze
+            );
+
+            // GREEN: extension that keeps funds locked beyond MIN_DURATION
+            assert!(contract
+                .extend_duration(position_id, 208 * constants::ONE_DAY)
+                .is_ok());
+            assert_eq!(
+                contract
+                    .get_stake(accounts.alice, position_id)
+                    .unwrap()
+                    .tier,
+                StakingTier::Platinum
+            );
+        }
+
+        #[ink::test]
+        fn test_increase_stake_requires_remaining_lock() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(constants::MIN_STAKE);
+
+            let mut contract = StakingContract::new(AccountId::from([0x1; 32]));
+            let position_id = contract.stake(120 * constants::ONE_DAY).unwrap();
+
+            // GREEN: lock still runs beyond MIN_DURATION, start time is kept
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(100 * constants::ONE_DAY);
+            assert!(contract.increase_stake(position_id).is_ok());
+            assert_eq!(contract.get_stake(accounts.alice, position_id).unwrap().start_time, 0);
+
+            // RED: expired lock cannot take a top-up and exit without penalty
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(200 * constants::ONE_DAY);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(constants::MEDIUM_STAKE_THRESHOLD);
+            assert!(contract.increase_stake(position_id).is_ok());
+            let stake = contract.get_stake(accounts.alice, position_id).unwrap();
+            assert_eq!(stake.start_time, 200 * constants::ONE_DAY);
+            assert_eq!(stake.unlock_time(), 320 * constants::ONE_DAY);
+            assert!(contract.calculate_early_penalty(&stake, 200 * constants::ONE_DAY).unwrap() > 0);
+        }
+
+        #[ink::test]
+        fn test_fee_tier_requires_amount() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = StakingContract::new(AccountId::from([0x1; 32]));
+
+            // RED: MIN_STAKE locked for 181 days is Platinum by duration but not for fees
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(constants::MIN_STAKE);
+            let small = contract.stake(181 * constants::ONE_DAY).unwrap();
+            let small = contract.get_stake(accounts.alice, small).unwrap();
+            assert_eq!(small.tier, StakingTier::Platinum);
+            assert_eq!(small.fee_tier(), StakingTier::Bronze);
+
+            // GREEN: Large amount with a shorter lock is capped by duration
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(constants::WHALE_STAKE_THRESHOLD);
+            let large = contract.stake(120 * constants::ONE_DAY).unwrap();
+            let large = contract.get_stake(accounts.alice, large).unwrap();
+            assert_eq!(large.fee_tier(), StakingTier::Gold);
+            assert_eq!(large.unlock_time(), 120 * constants::ONE_DAY);
+        }
+
+        /// Stakes `amount` for `duration` and returns pending rewards after `elapsed` ms
+        fn rewards_after(amount: Balance, duration: u64, elapsed: u64) -> Balance {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(amount);
+            
+            let mut contract = StakingContract::new(AccountId::from([0x1; 32]));
+            contract.fund_staking_rewards().unwrap(); // orçamento amplo, sem teto efetivo
+            let start = ink::env::block_timestamp::<ink::env::DefaultEnvironment>();
+            let position_id = contract.stake(duration).unwrap();
+            
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(start.checked_add(elapsed).unwrap());
+            contract.get_pending_rewards(accounts.alice, position_id).unwrap()
+        }
+
+        /// `amount * bps / 10_000`
+        fn pct(amount: Balance, bps: Balance) -> Balance {
+            amount.checked_mul(bps).unwrap().checked_div(10_000).unwrap()
+        }
+
+        #[ink::test]
+        fn test_real_world_apy_per_tier() {
+            // GREEN: um ano de timestamps (ms) rende exatamente o APY do tier
+            let amount = constants::MIN_STAKE;
+            assert_eq!(rewards_after(amount, constants::MIN_DURATION, constants::ONE_YEAR), pct(amount, 800));
+            assert_eq!(rewards_after(amount, 31 * constants::ONE_DAY, constants::ONE_YEAR), pct(amount, 1000));
+            assert_eq!(rewards_after(amount, 91 * constants::ONE_DAY, constants::ONE_YEAR), pct(amount, 1200));
+            assert_eq!(rewards_after(amount, constants::MAX_DURATION, constants::ONE_YEAR), pct(amount, 1500));
+            
+            // Meio ano rende metade
+            assert_eq!(rewards_after(amount, constants::MIN_DURATION, constants::ONE_YEAR / 2), pct(amount, 400));
+        }
+
+        #[ink::test]
+        fn test_real_world_apy_over_multiple_years() {
+            // GREEN: Platinum whale (1.3x) por dois anos = 2 * 15% * 1.3
+            let amount = constants::WHALE_STAKE_THRESHOLD;
+            let two_years = constants::ONE_YEAR.checked_mul(2).unwrap();
+            assert_eq!(rewards_after(amount, constants::MAX_DURATION, two_years), pct(amount, 3900));
+            
+            // Medium staker (1.1x) Silver por três anos = 3 * 10% * 1.1
+            let amount = constants::MEDIUM_STAKE_THRESHOLD;
+            let three_years = constants::ONE_YEAR.checked_mul(3).unwrap();
+            assert_eq!(rewards_after(amount, 31 * constants::ONE_DAY, three_years), pct(amount, 3300));
+        }
+
+        #[ink::test]
+        fn test_early_adopter_bonus_window_in_days() {
+            // GREEN: Top100 (+50%) vale durante 90 dias corridos
+            let amount = constants::MIN_STAKE;
+            let month = 30 * constants::ONE_DAY;
+            let base_month = amount
+                .checked_mul(800).unwrap()
+                .checked_mul(month as Balance).unwrap()
+                .checked_div(10_000).unwrap()
+                .checked_div(constants::ONE_YEAR as Balance).unwrap();
+            assert_eq!(rewards_after(amount, constants::MIN_DURATION, month), pct(base_month, 15_000));
+            
+            // RED: após a janela o bônus não se aplica mais
+            assert_eq!(
+                rewards_after(amount, constants::MIN_DURATION, constants::ONE_YEAR),
+                pct(amount, 800)
+            );
+            assert_eq!(constants::VOTING_PERIOD, 14 * 24 * 60 * 60 * 1000);
+        }
+
+        #[ink::test]
+        fn test_fund_staking_rewards_budget() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = StakingContract::new(AccountId::from([0x1; 32]));
+            
+            // RED: valor zero / caller não autorizado
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(0);
+            assert_eq!(contract.fund_staking_rewards(), Err(StakingError::ZeroAmount));
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(constants::MIN_STAKE);
+            assert_eq!(contract.fund_staking_rewards(), Err(StakingError::AccessDenied));
+            
+            // GREEN: owner e contrato de trading rewards financiam o orçamento
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            contract.set_trading_rewards_contract(accounts.charlie).unwrap();
+            assert!(contract.fund_staking_rewards().is_ok());
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert!(contract.fund_staking_rewards().is_ok());
+            
+            assert_eq!(contract.rewards_budget(), constants::MIN_STAKE * 2);
+            assert_eq!(contract.get_stats().0, 0); // principal intocado
+        }
+
+        #[ink::test]
+        fn test_trading_rewards_pool_distributed_pro_rata() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = StakingContract::new(AccountId::from([0x1; 32]));
+            contract
+                .set_trading_rewards_contract(accounts.charlie)
+                .unwrap();
+
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(constants::MIN_STAKE);
+            let alice_position = contract.stake(constants::MIN_DURATION).unwrap();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            let bob_position = contract.stake(constants::MIN_DURATION).unwrap();
+
+            // RED: pool só recebe do contrato de trading rewards ou do owner
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.bob);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(1_000);
+            assert_eq!(
+                contract.fund_trading_rewards_pool(),
+                Err(StakingError::AccessDenied)
+            );
+
+            // GREEN: parcela de trading fees vai para o pool, não para o orçamento de APY
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.charlie);
+            assert!(contract.fund_trading_rewards_pool().is_ok());
+            assert_eq!(contract.rewards_budget(), 0);
+            assert_eq!(contract.solvency().liabilities, 1_000);
+
+            // GREEN: distribuição repassa o pool pro-rata aos stakers
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            assert!(contract.distribute_trading_rewards().is_ok());
+            assert_eq!(
+                contract
+                    .get_stake(accounts.alice, alice_position)
+                    .unwrap()
+                    .pending_rewards,
+                500
+            );
+            assert_eq!(
+                contract
+                    .get_stake(accounts.bob, bob_position)
+                    .unwrap()
+                    .pending_rewards,
+                500
+            );
+            let report = contract.solvency();
+            assert_eq!(report.rewards_budget, 0);
+            assert_eq!(report.liabilities, 1_000);
+        }
+
+        #[ink::test]
+        fn test_paginated_distribution_keeps_solvency() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = StakingContract::new(AccountId::from([0x1; 32]));
+
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(constants::MIN_STAKE);
+            for staker in [accounts.alice, accounts.bob, accounts.charlie] {
+                test::set_caller::<ink::env::DefaultEnvironment>(staker);
+                contract.stake(constants::MIN_DURATION).unwrap();
+            }
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(1_000);
+            contract.fund_trading_rewards_pool().unwrap();
+
+            // RED: creditado na primeira página não pode contar duas vezes
+            assert_eq!(
+                contract.distribute_trading_rewards_paginated(None, Some(1)),
+                Ok((1, false, Some(1)))
+            );
+            assert_eq!(contract.solvency().liabilities, 1_000);
+
+            // GREEN: ao completar, o resto de arredondamento segue no pool
+            assert_eq!(
+                contract.distribute_trading_rewards_paginated(Some(1), Some(2)),
+                Ok((2, true, None))
+            );
+            assert_eq!(contract.solvency().liabilities, 1_000);
+            assert_eq!(contract.trading_rewards_pool, 1);
+            assert_eq!(contract.trading_rewards_in_flight, 0);
+        }
+
+        #[ink::test]
+        fn test_rewards_capped_to_budget() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = StakingContract::new(AccountId::from([0x1; 32]));
+            
+            let budget: Balance = 1_000_000_000; // 10 LUNES, bem abaixo de 8% a.a.
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(budget);
+            contract.fund_staking_rewards().unwrap();
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(constants::MIN_STAKE);
+            let position_id = contract.stake(constants::MIN_DURATION).unwrap();
+            
+            let start = contract.get_stake(accounts.alice, position_id).unwrap().start_time;
+            test::set_block_timestamp::<ink::env::DefaultEnvironment>(start.checked_add(constants::ONE_YEAR).unwrap());
+            
+            // Accrual limitado ao orçamento disponível
+            assert_eq!(contract.get_pending_rewards(accounts.alice, position_id), Ok(budget));
+            
+            let callee = test::callee::<ink::env::DefaultEnvironment>();
+            test::set_account_balance::<ink::env::DefaultEnvironment>(callee, constants::MIN_STAKE.checked_add(budget).unwrap());
+            let before = test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.alice).unwrap();
+            assert!(contract.claim_rewards(position_id).is_ok());
+            let after = test::get_account_balance::<ink::env::DefaultEnvironment>(accounts.alice).unwrap();
+            assert_eq!(after.checked_sub(before), Some(budget));
+            
+            // Orçamento esgotado: nada mais acumula e o principal segue coberto
+            assert_eq!(contract.rewards_budget(), 0);
+            assert_eq!(contract.get_pending_rewards(accounts.alice, position_id), Ok(0));
+            let report = contract.solvency();
+            assert_eq!(report.principal, constants::MIN_STAKE);
+            assert_eq!(report.liabilities, 0);
+            assert_eq!(report.shortfall, 0);
+            
+            // RED: bônus de governança também depende do orçamento
+            contract.reward_approved_proposal(accounts.alice).unwrap();
+            assert_eq!(contract.claim_governance_bonus(), Err(StakingError::NoRewardsToClaim));
+        }
+
+        #[ink::test]
+        fn test_solvency_report() {
+            let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
+            test::set_caller::<ink::env::DefaultEnvironment>(accounts.alice);
+            let mut contract = StakingContract::new(AccountId::from([0x1; 32]));
+            let callee = test::callee::<ink::env::DefaultEnvironment>();
+            
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(constants::PROPOSAL_BONUS);
+            contract.fund_staking_rewards().unwrap();
+            test::set_value_transferred::<ink::env::DefaultEnvironment>(constants::MIN_STAKE);
+            contract.stake(constants::MIN_DURATION).unwrap();
+            
+            // Bônus reservado passa do orçamento para os passivos
+            contract.reward_approved_proposal(accounts.alice).unwrap();
+            
+            let funded = constants::MIN_STAKE.checked_add(constants::PROPOSAL_BONUS).unwrap();
+            test::set_account_balance::<ink::env::DefaultEnvironment>(callee, funded);
+            let report = contract.solvency();
+            assert_eq!(report.principal, constants::MIN_STAKE);
+            assert_eq!(report.rewards_budget, 0);
+            assert_eq!(report.liabilities, constants::PROPOSAL_BONUS);
+            assert_eq!(report.native_balance, funded);
+            assert_eq!(report.shortfall, 0);
+            
+            // RED: saldo abaixo de principal + passivos gera déficit
+            test::set_account_balance::<ink::env::DefaultEnvironment>(callee, constants::MIN_STAKE);
+            assert_eq!(contract.solvency().shortfall, constants::PROPOSAL_BONUS);
+        }
+
         #[ink::test]
         fn test_stake_insufficient_amount() {
             let accounts = test::default_accounts::<ink::env::DefaultEnvironment>();
@@ -1974,7 +2903,7 @@ pub mod staking_contract {
             // Setup Alice with enough stake for voting power (mais que MIN_PROPOSAL_POWER)
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
             ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(2_000_000_000_000); // 20,000 LUNES (> 10,000 requeridos)
-            assert!(staking.stake(30 * 24 * 60 * 30).is_ok()); // 30 days
+            assert!(staking.stake(30 * constants::ONE_DAY).is_ok()); // 30 days
             
             // Avançar tempo para que o stake seja considerado válido
             ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
@@ -2019,7 +2948,7 @@ pub mod staking_contract {
             // Setup Alice with enough stake
             ink::env::test::set_caller::<ink::env::DefaultEnvironment>(alice);
             ink::env::test::set_value_transferred::<ink::env::DefaultEnvironment>(2_000_000_000_000); // 20,000 LUNES
-            assert!(staking.stake(30 * 24 * 60 * 30).is_ok());
+            assert!(staking.stake(30 * constants::ONE_DAY).is_ok());
             
             // Avançar tempo para que o stake seja válido
             ink::env::test::advance_block::<ink::env::DefaultEnvironment>();
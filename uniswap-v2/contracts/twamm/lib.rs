@@ -0,0 +1,1588 @@
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+#![warn(clippy::arithmetic_side_effects)]
+#[ink::contract]
+pub mod twamm_contract {
+    use psp22::PSP22Error;
+    use ink::prelude::vec::Vec;
+    use ink::prelude::vec;
+    use ink::storage::Mapping;
+    use primitive_types::{U256, U512};
+    use ink::env::call::{build_call, ExecutionInput, Selector};
+    use ink::env::DefaultEnvironment;
+    use router_contract::router_contract::RouterError;
+
+    // ========================================
+    // TWAMM CONTRACT - ORDENS DE LONGO PRAZO (TIME-WEIGHTED AMM)
+    // ========================================
+    //
+    // Contrato companheiro do PairContract que executa ordens de longo prazo
+    // como infinitas sub-ordens virtuais distribuídas no tempo.
+    //
+    // ## Funcionamento:
+    // - Cada ordem vende `amount_in` a uma taxa constante (`sale_rate`) até a expiração
+    // - As expirações são alinhadas a `ORDER_INTERVAL`, agregando as taxas por par
+    // - A cada interação (submit, cancel, withdraw ou execute) as vendas virtuais
+    //   desde a última execução são resolvidas com a fórmula fechada do TWAMM,
+    //   intervalo a intervalo, a partir das reserves atuais do par
+    // - Apenas o fluxo líquido entre os dois lados é trocado no pool (via router),
+    //   na fee cobrada pelo par (`current_fee_bps`)
+    // - O `amount_out_min` da liquidação tem como piso o TWAP do par desde a
+    //   execução anterior, então manipular as reserves no bloco não reduz o output
+    // - Proceeds são distribuídos pro-rata via reward factors (output por unidade de sale rate),
+    //   a partir do saldo efetivamente recebido na liquidação
+    // - Se a liquidação falhar, nada é gravado; cancelamento e saque seguem com o
+    //   estado da última execução bem-sucedida
+    //
+    // ## Fórmula fechada (dois lados vendendo):
+    // c = (√(x·e1) − √(y·e0)) / (√(x·e1) + √(y·e0))
+    // x_end = √(x·y·e0/e1) · (e^(2√(e0·e1)/√(x·y)) + c) / (e^(2√(e0·e1)/√(x·y)) − c)
+    // y_end = x·y / x_end
+    // onde `e0`/`e1` são os amounts vendidos no intervalo, já descontada a fee de swap.
+
+    // ========================================
+    // EVENTOS (PARA INDEXADORES E UIS)
+    // ========================================
+
+    /// Emitido quando uma ordem de longo prazo é criada
+    #[ink(event)]
+    pub struct LongTermOrderPlaced {
+        #[ink(topic)]
+        pub order_id: u64,
+        #[ink(topic)]
+        pub owner: AccountId,
+        #[ink(topic)]
+        pub pair: AccountId,
+        pub sell_token_0: bool,
+        pub amount_in: Balance,
+        pub sale_rate: u128,
+        pub expiry: Timestamp,
+    }
+
+    /// Emitido quando uma ordem é cancelada
+    #[ink(event)]
+    pub struct LongTermOrderCancelled {
+        #[ink(topic)]
+        pub order_id: u64,
+        #[ink(topic)]
+        pub owner: AccountId,
+        pub unsold_refunded: Balance,
+        pub proceeds: Balance,
+    }
+
+    /// Emitido quando proceeds são sacados
+    #[ink(event)]
+    pub struct ProceedsWithdrawn {
+        #[ink(topic)]
+        pub order_id: u64,
+        #[ink(topic)]
+        pub owner: AccountId,
+        pub proceeds: Balance,
+    }
+
+    /// Emitido quando as vendas virtuais de um par são executadas
+    #[ink(event)]
+    pub struct VirtualOrdersExecuted {
+        #[ink(topic)]
+        pub pair: AccountId,
+        pub sold_0: Balance,
+        pub sold_1: Balance,
+        pub bought_0: Balance,
+        pub bought_1: Balance,
+        pub executed_until: Timestamp,
+    }
+
+    // ========================================
+    // ERROS ESPECÍFICOS DO TWAMM CONTRACT
+    // ========================================
+
+    /// Erros que podem ocorrer nas operações do TWAMM
+    #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub enum TwammError {
+        /// Endereço zero informado
+        ZeroAddress,
+        /// Amount inválido (zero ou pequeno demais para a duração)
+        InvalidAmount,
+        /// Número de intervalos fora do permitido
+        InvalidDuration,
+        /// Token não pertence ao par
+        TokenNotInPair,
+        /// Par sem ordens registradas
+        PoolNotFound,
+        /// Ordem não encontrada
+        OrderNotFound,
+        /// Caller não é o dono da ordem
+        Unauthorized,
+        /// Ordem não está ativa
+        OrderNotActive,
+        /// Ordem já expirou (use `withdraw_proceeds`)
+        OrderExpired,
+        /// Há intervalos pendentes; chame `execute_virtual_orders` novamente
+        ExecutionBacklog,
+        /// Reserves do par vazias
+        InsufficientLiquidity,
+        /// Consulta ao par falhou
+        PairQueryFailed,
+        /// Chamada ao router falhou
+        RouterCallFailed,
+        /// Transferência de token falhou
+        TransferFailed,
+        /// Overflow aritmético
+        Overflow,
+        /// Erro no token PSP22 subjacente
+        PSP22(PSP22Error),
+        /// Sem intervalo de TWAP desde a última execução (tente no próximo bloco)
+        TwapUnavailable,
+        /// Swap de liquidação rejeitado pelo router
+        Router(RouterError),
+    }
+
+    impl From<PSP22Error> for TwammError {
+        fn from(error: PSP22Error) -> Self {
+            TwammError::PSP22(error)
+        }
+    }
+
+    // ========================================
+    // TIPOS AUXILIARES
+    // ========================================
+
+    /// Status de uma ordem de longo prazo
+    #[derive(Debug, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub enum OrderStatus {
+        /// Vendendo ou com proceeds a sacar
+        Active,
+        /// Cancelada pelo dono
+        Cancelled,
+        /// Expirada e totalmente sacada
+        Completed,
+    }
+
+    /// Estado agregado das ordens de longo prazo de um par
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct TwammPool {
+        pub token_0: AccountId,
+        pub token_1: AccountId,
+        /// Soma das taxas de venda de token_0 (escala `RATE_SCALE` por ms)
+        pub sale_rate_0: u128,
+        /// Soma das taxas de venda de token_1 (escala `RATE_SCALE` por ms)
+        pub sale_rate_1: u128,
+        /// token_1 recebido por unidade de sale rate vendendo token_0 (escala `REWARD_SCALE`)
+        pub reward_factor_0: u128,
+        /// token_0 recebido por unidade de sale rate vendendo token_1 (escala `REWARD_SCALE`)
+        pub reward_factor_1: u128,
+        /// Timestamp até o qual as vendas virtuais foram executadas
+        pub last_execution: Timestamp,
+    }
+
+    impl TwammPool {
+        /// Pool sem ordens, executado até `now`
+        pub fn new(token_0: AccountId, token_1: AccountId, now: Timestamp) -> Self {
+            Self {
+                token_0,
+                token_1,
+                sale_rate_0: 0,
+                sale_rate_1: 0,
+                reward_factor_0: 0,
+                reward_factor_1: 0,
+                last_execution: now,
+            }
+        }
+    }
+
+    /// Ordem de longo prazo
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct LongTermOrder {
+        pub id: u64,
+        pub owner: AccountId,
+        pub pair: AccountId,
+        /// `true` se vende token_0 por token_1
+        pub sell_token_0: bool,
+        /// Taxa de venda (escala `RATE_SCALE` por ms)
+        pub sale_rate: u128,
+        pub start: Timestamp,
+        pub expiry: Timestamp,
+        /// Reward factor no último saque (ou na criação)
+        pub reward_factor_last: u128,
+        /// Momento do cancelamento, se cancelada
+        pub cancelled_at: Option<Timestamp>,
+        pub status: OrderStatus,
+    }
+
+    /// Progresso de uma ordem até a última execução do par
+    #[derive(Debug, PartialEq, Eq, Clone, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+    pub struct OrderProgress {
+        /// Amount total a vender
+        pub total: Balance,
+        /// Amount já vendido
+        pub sold: Balance,
+        /// Amount ainda não vendido (reembolsado se cancelada)
+        pub remaining: Balance,
+        /// Proceeds acumulados ainda não sacados
+        pub proceeds_available: Balance,
+        /// Progresso em basis points (10000 = 100%)
+        pub progress_bps: u16,
+    }
+
+    /// Observação dos preços cumulativos de um par
+    #[derive(Debug, Default, PartialEq, Eq, Clone, Copy, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct Observation {
+        pub price_0_cumulative: u128,
+        pub price_1_cumulative: u128,
+        pub timestamp: Timestamp,
+    }
+
+    /// Resultado agregado das vendas virtuais de uma execução
+    #[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+    pub struct VirtualTrade {
+        pub sold_0: Balance,
+        pub sold_1: Balance,
+        pub bought_0: Balance,
+        pub bought_1: Balance,
+    }
+
+    /// Vendas virtuais de um intervalo, ainda não creditadas no pool
+    #[derive(Debug, Default, PartialEq, Eq, Clone, Copy)]
+    pub struct VirtualSegment {
+        /// token_0 devido aos vendedores de token_1
+        pub bought_0: Balance,
+        /// token_1 devido aos vendedores de token_0
+        pub bought_1: Balance,
+        /// Fim do intervalo
+        pub end: Timestamp,
+        /// `true` se o intervalo termina em um limite de `ORDER_INTERVAL`
+        pub boundary: bool,
+    }
+
+    // ========================================
+    // CONSTANTES
+    // ========================================
+    mod constants {
+        /// Alinhamento das expirações (1 hora em ms)
+        pub const ORDER_INTERVAL: u64 = 60 * 60 * 1000;
+
+        /// Duração máxima de uma ordem (30 dias de intervalos)
+        pub const MAX_ORDER_INTERVALS: u32 = 24 * 30;
+
+        /// Máximo de intervalos resolvidos por execução (limita o custo por chamada)
+        pub const MAX_INTERVALS_PER_EXECUTION: u32 = 256;
+
+        /// Escala das taxas de venda
+        pub const RATE_SCALE: u128 = 1_000_000_000_000;
+
+        /// Escala dos reward factors
+        pub const REWARD_SCALE: u128 = 1_000_000_000_000_000_000;
+
+        /// Ponto fixo usado na exponencial (1.0 = 1e18)
+        pub const ONE: u128 = 1_000_000_000_000_000_000;
+
+        /// ln(2) em ponto fixo
+        pub const LN_2: u128 = 693_147_180_559_945_309;
+
+        /// Expoente máximo (acima disso o pool já está no preço de equilíbrio)
+        pub const MAX_EXPONENT: u128 = 40 * ONE;
+
+        /// Fee estática do PairContract em bps (0.5% = 995/1000), usada quando
+        /// o par não expõe `current_fee_bps`
+        pub const PAIR_STATIC_FEE_BPS: u16 = 50;
+
+        /// Escala dos preços cumulativos do Pair (UQ112)
+        pub const UQ112: u128 = 2_u128.pow(112);
+
+        /// Denominador em basis points (100% = 10000)
+        pub const BPS_DENOMINATOR: u128 = 10_000;
+
+        /// Desvio máximo da liquidação abaixo do TWAP (3%, cobre o impacto do fluxo líquido)
+        pub const MAX_TWAP_DEVIATION_BPS: u128 = 300;
+    }
+
+    /// Selectors das chamadas cross-contract
+    mod selectors {
+        pub const PAIR_TOKEN_0: [u8; 4] = ink::selector_bytes!("token_0");
+        pub const PAIR_TOKEN_1: [u8; 4] = ink::selector_bytes!("token_1");
+        pub const PAIR_GET_RESERVES: [u8; 4] = ink::selector_bytes!("get_reserves");
+        pub const PAIR_CURRENT_FEE_BPS: [u8; 4] = ink::selector_bytes!("current_fee_bps");
+        pub const PAIR_PRICE_0_CUMULATIVE_LAST: [u8; 4] = ink::selector_bytes!("price_0_cumulative_last");
+        pub const PAIR_PRICE_1_CUMULATIVE_LAST: [u8; 4] = ink::selector_bytes!("price_1_cumulative_last");
+        pub const ROUTER_SWAP_EXACT_TOKENS_FOR_TOKENS: [u8; 4] = ink::selector_bytes!("swap_exact_tokens_for_tokens");
+        pub const PSP22_TRANSFER: [u8; 4] = ink::selector_bytes!("PSP22::transfer");
+        pub const PSP22_TRANSFER_FROM: [u8; 4] = ink::selector_bytes!("PSP22::transfer_from");
+        pub const PSP22_APPROVE: [u8; 4] = ink::selector_bytes!("PSP22::approve");
+        pub const PSP22_BALANCE_OF: [u8; 4] = ink::selector_bytes!("PSP22::balance_of");
+    }
+
+    // ========================================
+    // STORAGE DO TWAMM CONTRACT
+    // ========================================
+
+    /// Chave (par, timestamp de expiração)
+    pub type ExpiryKey = (AccountId, Timestamp);
+
+    /// Valores por lado do par: (vendendo token_0, vendendo token_1)
+    pub type PerSide = (u128, u128);
+
+    #[ink(storage)]
+    pub struct TwammContract {
+        /// Router usado para liquidar o fluxo líquido no par
+        router: AccountId,
+        /// Próximo id de ordem
+        next_order_id: u64,
+        /// Estado agregado por par
+        pools: Mapping<AccountId, TwammPool>,
+        /// Taxas de venda que expiram em cada (par, timestamp): (lado 0, lado 1)
+        sale_rate_ending: Mapping<ExpiryKey, PerSide>,
+        /// Reward factors registrados em cada expiração: (lado 0, lado 1)
+        reward_factors_at_expiry: Mapping<ExpiryKey, PerSide>,
+        /// Ordens por id
+        orders: Mapping<u64, LongTermOrder>,
+        /// Preços cumulativos do par na última execução (início da janela TWAP)
+        observations: Mapping<AccountId, Observation>,
+    }
+
+    impl TwammContract {
+        /// Construtor do TWAMM Contract
+        #[ink(constructor)]
+        pub fn new(router: AccountId) -> Self {
+            Self {
+                router,
+                next_order_id: 1,
+                pools: Mapping::default(),
+                sale_rate_ending: Mapping::default(),
+                reward_factors_at_expiry: Mapping::default(),
+                orders: Mapping::default(),
+                observations: Mapping::default(),
+            }
+        }
+
+        // ========================================
+        // QUERIES (READ-ONLY)
+        // ========================================
+
+        /// Retorna o router usado na liquidação
+        #[ink(message)]
+        pub fn router(&self) -> AccountId {
+            self.router
+        }
+
+        /// Retorna o estado agregado de um par
+        #[ink(message)]
+        pub fn get_pool(&self, pair: AccountId) -> Option<TwammPool> {
+            self.pools.get(pair)
+        }
+
+        /// Retorna uma ordem pelo id
+        #[ink(message)]
+        pub fn get_order(&self, order_id: u64) -> Option<LongTermOrder> {
+            self.orders.get(order_id)
+        }
+
+        /// Progresso de uma ordem até a última execução das vendas virtuais do par
+        #[ink(message)]
+        pub fn get_order_progress(&self, order_id: u64) -> Result<OrderProgress, TwammError> {
+            let order = self.orders.get(order_id).ok_or(TwammError::OrderNotFound)?;
+            let pool = self.pools.get(order.pair).ok_or(TwammError::PoolNotFound)?;
+
+            let total = Self::amount_for(order.sale_rate, order.expiry.saturating_sub(order.start))?;
+            let sold_until = order
+                .cancelled_at
+                .unwrap_or(pool.last_execution)
+                .min(order.expiry)
+                .max(order.start);
+            let sold = Self::amount_for(order.sale_rate, sold_until.saturating_sub(order.start))?;
+            let proceeds_available = match order.status {
+                OrderStatus::Active => {
+                    let reward_factor = self.current_reward_factor(&order, &pool);
+                    Self::proceeds_for(order.sale_rate, order.reward_factor_last, reward_factor)?
+                }
+                _ => 0,
+            };
+            let progress_bps = if total == 0 {
+                0
+            } else {
+                let bps = U256::from(sold)
+                    .checked_mul(U256::from(10_000u32))
+                    .and_then(|value| value.checked_div(U256::from(total)))
+                    .ok_or(TwammError::Overflow)?;
+                u16::try_from(bps.low_u32()).map_err(|_| TwammError::Overflow)?
+            };
+
+            Ok(OrderProgress {
+                total,
+                sold,
+                remaining: total.saturating_sub(sold),
+                proceeds_available,
+                progress_bps,
+            })
+        }
+
+        // ========================================
+        // OPERAÇÕES DE ORDENS DE LONGO PRAZO
+        // ========================================
+
+        /// Cria uma ordem de longo prazo vendendo `amount_in` de `token_in`
+        ///
+        /// A ordem expira no fim do `intervals`-ésimo intervalo após o atual.
+        /// Apenas o amount divisível pela duração é depositado em escrow.
+        ///
+        /// # Retorna
+        /// - Id da nova ordem
+        #[ink(message)]
+        pub fn submit_long_term_order(
+            &mut self,
+            pair: AccountId,
+            token_in: AccountId,
+            amount_in: Balance,
+            intervals: u32,
+        ) -> Result<u64, TwammError> {
+            if pair == AccountId::from([0u8; 32]) || token_in == AccountId::from([0u8; 32]) {
+                return Err(TwammError::ZeroAddress);
+            }
+            if amount_in == 0 {
+                return Err(TwammError::InvalidAmount);
+            }
+            if intervals == 0 || intervals > constants::MAX_ORDER_INTERVALS {
+                return Err(TwammError::InvalidDuration);
+            }
+
+            let pool = match self.pools.get(pair) {
+                Some(_) => {
+                    self.execute_virtual_orders_internal(pair)?;
+                    self.pools.get(pair).ok_or(TwammError::PoolNotFound)?
+                }
+                None => {
+                    let token_0: AccountId = self.query_pair(pair, selectors::PAIR_TOKEN_0)?;
+                    let token_1: AccountId = self.query_pair(pair, selectors::PAIR_TOKEN_1)?;
+                    let (observation, _) = self.current_observation(pair)?;
+                    self.observations.insert(pair, &observation);
+                    TwammPool::new(token_0, token_1, self.env().block_timestamp())
+                }
+            };
+            if pool.last_execution < self.env().block_timestamp() {
+                return Err(TwammError::ExecutionBacklog);
+            }
+
+            let sell_token_0 = if token_in == pool.token_0 {
+                true
+            } else if token_in == pool.token_1 {
+                false
+            } else {
+                return Err(TwammError::TokenNotInPair);
+            };
+
+            let owner = self.env().caller();
+            let (order, sell_amount) = self.open_order(pair, pool, owner, sell_token_0, amount_in, intervals)?;
+            self.transfer_from_token(token_in, owner, self.env().account_id(), sell_amount)?;
+
+            self.env().emit_event(LongTermOrderPlaced {
+                order_id: order.id,
+                owner,
+                pair,
+                sell_token_0,
+                amount_in: sell_amount,
+                sale_rate: order.sale_rate,
+                expiry: order.expiry,
+            });
+
+            Ok(order.id)
+        }
+
+        /// Cancela uma ordem ativa antes da expiração (apenas o dono)
+        ///
+        /// Reembolsa o amount ainda não vendido e paga os proceeds acumulados.
+        /// Se a liquidação pendente falhar (ou houver backlog), a ordem é
+        /// cancelada na última execução do par: o que não foi executado até
+        /// ali volta ao dono.
+        ///
+        /// # Retorna
+        /// - `(unsold_refunded, proceeds)`
+        #[ink(message)]
+        pub fn cancel_long_term_order(&mut self, order_id: u64) -> Result<(Balance, Balance), TwammError> {
+            let mut order = self.owned_active_order(order_id)?;
+            if self.env().block_timestamp() >= order.expiry {
+                return Err(TwammError::OrderExpired);
+            }
+
+            self.try_execute_virtual_orders(order.pair);
+            let mut pool = self.pools.get(order.pair).ok_or(TwammError::PoolNotFound)?;
+            let (unsold, proceeds) = self.close_order(&mut order, &mut pool)?;
+
+            let (token_in, token_out) = Self::order_tokens(&order, &pool);
+            if unsold > 0 {
+                self.transfer_token(token_in, order.owner, unsold)?;
+            }
+            if proceeds > 0 {
+                self.transfer_token(token_out, order.owner, proceeds)?;
+            }
+
+            self.env().emit_event(LongTermOrderCancelled {
+                order_id,
+                owner: order.owner,
+                unsold_refunded: unsold,
+                proceeds,
+            });
+
+            Ok((unsold, proceeds))
+        }
+
+        /// Saca os proceeds acumulados de uma ordem (apenas o dono)
+        ///
+        /// Após a expiração, o saque final marca a ordem como concluída. Se a
+        /// liquidação pendente falhar, saca o que foi acumulado até a última
+        /// execução do par.
+        #[ink(message)]
+        pub fn withdraw_proceeds(&mut self, order_id: u64) -> Result<Balance, TwammError> {
+            let mut order = self.owned_active_order(order_id)?;
+            self.try_execute_virtual_orders(order.pair);
+            let pool = self.pools.get(order.pair).ok_or(TwammError::PoolNotFound)?;
+
+            let reward_factor = self.current_reward_factor(&order, &pool);
+            let proceeds = Self::proceeds_for(order.sale_rate, order.reward_factor_last, reward_factor)?;
+            order.reward_factor_last = reward_factor;
+            if pool.last_execution >= order.expiry {
+                order.status = OrderStatus::Completed;
+            }
+            self.orders.insert(order_id, &order);
+
+            if proceeds > 0 {
+                let (_, token_out) = Self::order_tokens(&order, &pool);
+                self.transfer_token(token_out, order.owner, proceeds)?;
+            }
+
+            self.env().emit_event(ProceedsWithdrawn {
+                order_id,
+                owner: order.owner,
+                proceeds,
+            });
+
+            Ok(proceeds)
+        }
+
+        /// Executa as vendas virtuais pendentes de um par (qualquer conta pode chamar)
+        #[ink(message)]
+        pub fn execute_virtual_orders(&mut self, pair: AccountId) -> Result<(), TwammError> {
+            if self.pools.get(pair).is_none() {
+                return Err(TwammError::PoolNotFound);
+            }
+            self.execute_virtual_orders_internal(pair)
+        }
+
+        // ========================================
+        // FUNÇÕES INTERNAS (ESTADO DAS ORDENS)
+        // ========================================
+
+        /// Registra uma nova ordem no pool (sem transferências)
+        ///
+        /// # Retorna
+        /// - A ordem criada e o amount que deve ser depositado em escrow
+        fn open_order(
+            &mut self,
+            pair: AccountId,
+            mut pool: TwammPool,
+            owner: AccountId,
+            sell_token_0: bool,
+            amount_in: Balance,
+            intervals: u32,
+        ) -> Result<(LongTermOrder, Balance), TwammError> {
+            let now = self.env().block_timestamp();
+            let expiry = now
+                .checked_div(constants::ORDER_INTERVAL)
+                .and_then(|current| current.checked_add(u64::from(intervals)))
+                .and_then(|last| last.checked_add(1))
+                .and_then(|end| end.checked_mul(constants::ORDER_INTERVAL))
+                .ok_or(TwammError::Overflow)?;
+            let duration = expiry.checked_sub(now).ok_or(TwammError::Overflow)?;
+
+            let sale_rate = U256::from(amount_in)
+                .checked_mul(U256::from(constants::RATE_SCALE))
+                .and_then(|value| value.checked_div(U256::from(duration)))
+                .ok_or(TwammError::Overflow)?;
+            let sale_rate = u128::try_from(sale_rate).map_err(|_| TwammError::Overflow)?;
+            let sell_amount = Self::amount_for(sale_rate, duration)?;
+            if sale_rate == 0 || sell_amount == 0 {
+                return Err(TwammError::InvalidAmount);
+            }
+
+            let ending_key = (pair, expiry);
+            let (mut ending_0, mut ending_1) = self.sale_rate_ending.get(ending_key).unwrap_or((0, 0));
+            let reward_factor_last = if sell_token_0 {
+                pool.sale_rate_0 = pool.sale_rate_0.checked_add(sale_rate).ok_or(TwammError::Overflow)?;
+                ending_0 = ending_0.checked_add(sale_rate).ok_or(TwammError::Overflow)?;
+                pool.reward_factor_0
+            } else {
+                pool.sale_rate_1 = pool.sale_rate_1.checked_add(sale_rate).ok_or(TwammError::Overflow)?;
+                ending_1 = ending_1.checked_add(sale_rate).ok_or(TwammError::Overflow)?;
+                pool.reward_factor_1
+            };
+            self.sale_rate_ending.insert(ending_key, &(ending_0, ending_1));
+            self.pools.insert(pair, &pool);
+
+            let order_id = self.next_order_id;
+            self.next_order_id = order_id.checked_add(1).ok_or(TwammError::Overflow)?;
+            let order = LongTermOrder {
+                id: order_id,
+                owner,
+                pair,
+                sell_token_0,
+                sale_rate,
+                start: now,
+                expiry,
+                reward_factor_last,
+                cancelled_at: None,
+                status: OrderStatus::Active,
+            };
+            self.orders.insert(order_id, &order);
+
+            Ok((order, sell_amount))
+        }
+
+        /// Cancela a ordem na última execução do par (sem transferências)
+        ///
+        /// Remove a taxa de venda do pool e da expiração agendada.
+        ///
+        /// # Retorna
+        /// - `(unsold, proceeds)` devidos ao dono
+        fn close_order(&mut self, order: &mut LongTermOrder, pool: &mut TwammPool) -> Result<(Balance, Balance), TwammError> {
+            let cancelled_at = pool.last_execution.max(order.start);
+            if cancelled_at >= order.expiry {
+                return Err(TwammError::OrderExpired);
+            }
+
+            let reward_factor = self.current_reward_factor(order, pool);
+            let proceeds = Self::proceeds_for(order.sale_rate, order.reward_factor_last, reward_factor)?;
+            let unsold = Self::amount_for(order.sale_rate, order.expiry.saturating_sub(cancelled_at))?;
+
+            let ending_key = (order.pair, order.expiry);
+            let (ending_0, ending_1) = self.sale_rate_ending.get(ending_key).unwrap_or((0, 0));
+            if order.sell_token_0 {
+                pool.sale_rate_0 = pool.sale_rate_0.checked_sub(order.sale_rate).ok_or(TwammError::Overflow)?;
+                let ending_0 = ending_0.checked_sub(order.sale_rate).ok_or(TwammError::Overflow)?;
+                self.sale_rate_ending.insert(ending_key, &(ending_0, ending_1));
+            } else {
+                pool.sale_rate_1 = pool.sale_rate_1.checked_sub(order.sale_rate).ok_or(TwammError::Overflow)?;
+                let ending_1 = ending_1.checked_sub(order.sale_rate).ok_or(TwammError::Overflow)?;
+                self.sale_rate_ending.insert(ending_key, &(ending_0, ending_1));
+            }
+            self.pools.insert(order.pair, pool);
+
+            order.reward_factor_last = reward_factor;
+            order.cancelled_at = Some(cancelled_at);
+            order.status = OrderStatus::Cancelled;
+            self.orders.insert(order.id, order);
+
+            Ok((unsold, proceeds))
+        }
+
+        /// Carrega uma ordem ativa verificando que o caller é o dono
+        fn owned_active_order(&self, order_id: u64) -> Result<LongTermOrder, TwammError> {
+            let order = self.orders.get(order_id).ok_or(TwammError::OrderNotFound)?;
+            if order.owner != self.env().caller() {
+                return Err(TwammError::Unauthorized);
+            }
+            if order.status != OrderStatus::Active {
+                return Err(TwammError::OrderNotActive);
+            }
+            Ok(order)
+        }
+
+        /// Reward factor aplicável à ordem (congelado na expiração, se já executada)
+        fn current_reward_factor(&self, order: &LongTermOrder, pool: &TwammPool) -> u128 {
+            let (factor_0, factor_1) = if pool.last_execution >= order.expiry {
+                self.reward_factors_at_expiry
+                    .get((order.pair, order.expiry))
+                    .unwrap_or((pool.reward_factor_0, pool.reward_factor_1))
+            } else {
+                (pool.reward_factor_0, pool.reward_factor_1)
+            };
+            if order.sell_token_0 { factor_0 } else { factor_1 }
+        }
+
+        /// Tokens (entrada, saída) de uma ordem
+        fn order_tokens(order: &LongTermOrder, pool: &TwammPool) -> (AccountId, AccountId) {
+            if order.sell_token_0 {
+                (pool.token_0, pool.token_1)
+            } else {
+                (pool.token_1, pool.token_0)
+            }
+        }
+
+        /// Resolve as vendas virtuais do par até agora, intervalo a intervalo
+        ///
+        /// Apenas simula: não grava estado nem faz chamadas externas além da
+        /// leitura das expirações agendadas. `fee_bps` é a fee cobrada pelo par.
+        fn simulate_virtual_orders(
+            &self,
+            pair: AccountId,
+            pool: &TwammPool,
+            reserves: (Balance, Balance),
+            now: Timestamp,
+            fee_bps: u16,
+        ) -> Result<(Vec<VirtualSegment>, VirtualTrade), TwammError> {
+            let mut segments = Vec::new();
+            let mut trade = VirtualTrade::default();
+            let (mut reserve_0, mut reserve_1) = reserves;
+            let (mut sale_rate_0, mut sale_rate_1) = (pool.sale_rate_0, pool.sale_rate_1);
+            let mut last_execution = pool.last_execution;
+
+            while last_execution < now && segments.len() < constants::MAX_INTERVALS_PER_EXECUTION as usize {
+                if sale_rate_0 == 0 && sale_rate_1 == 0 {
+                    // Nada vendendo: nenhuma expiração pendente, pular direto
+                    segments.push(VirtualSegment {
+                        end: now,
+                        ..VirtualSegment::default()
+                    });
+                    break;
+                }
+
+                let next_boundary = last_execution
+                    .checked_div(constants::ORDER_INTERVAL)
+                    .and_then(|current| current.checked_add(1))
+                    .and_then(|next| next.checked_mul(constants::ORDER_INTERVAL))
+                    .ok_or(TwammError::Overflow)?;
+                let segment_end = next_boundary.min(now);
+                let elapsed = segment_end.saturating_sub(last_execution);
+
+                let sold_0 = Self::amount_for(sale_rate_0, elapsed)?;
+                let sold_1 = Self::amount_for(sale_rate_1, elapsed)?;
+                let (bought_0, bought_1, end_0, end_1) = Self::compute_virtual_trade(
+                    reserve_0,
+                    reserve_1,
+                    Self::after_fee(sold_0, fee_bps)?,
+                    Self::after_fee(sold_1, fee_bps)?,
+                )?;
+                reserve_0 = end_0;
+                reserve_1 = end_1;
+
+                trade.sold_0 = trade.sold_0.checked_add(sold_0).ok_or(TwammError::Overflow)?;
+                trade.sold_1 = trade.sold_1.checked_add(sold_1).ok_or(TwammError::Overflow)?;
+                trade.bought_0 = trade.bought_0.checked_add(bought_0).ok_or(TwammError::Overflow)?;
+                trade.bought_1 = trade.bought_1.checked_add(bought_1).ok_or(TwammError::Overflow)?;
+
+                let boundary = segment_end == next_boundary;
+                segments.push(VirtualSegment {
+                    bought_0,
+                    bought_1,
+                    end: segment_end,
+                    boundary,
+                });
+                last_execution = segment_end;
+                if boundary {
+                    if let Some((ending_0, ending_1)) = self.sale_rate_ending.get((pair, next_boundary)) {
+                        sale_rate_0 = sale_rate_0.checked_sub(ending_0).ok_or(TwammError::Overflow)?;
+                        sale_rate_1 = sale_rate_1.checked_sub(ending_1).ok_or(TwammError::Overflow)?;
+                    }
+                }
+            }
+
+            Ok((segments, trade))
+        }
+
+        /// Credita os intervalos simulados no pool
+        ///
+        /// `credited` é o que cada lado efetivamente tem a receber
+        /// `(token_0, token_1)`; ele é repartido entre os intervalos na
+        /// proporção do output virtual de cada um. Atualiza reward factors,
+        /// taxas de venda e os reward factors de cada expiração.
+        fn apply_virtual_orders(
+            &mut self,
+            pair: AccountId,
+            pool: &mut TwammPool,
+            segments: &[VirtualSegment],
+            trade: &VirtualTrade,
+            credited: (Balance, Balance),
+        ) -> Result<(), TwammError> {
+            for segment in segments {
+                let bought_0 = Self::pro_rata(segment.bought_0, credited.0, trade.bought_0)?;
+                let bought_1 = Self::pro_rata(segment.bought_1, credited.1, trade.bought_1)?;
+
+                // Sellers de token_0 recebem token_1 e vice-versa
+                pool.reward_factor_0 = Self::accrue_reward(pool.reward_factor_0, bought_1, pool.sale_rate_0)?;
+                pool.reward_factor_1 = Self::accrue_reward(pool.reward_factor_1, bought_0, pool.sale_rate_1)?;
+
+                pool.last_execution = segment.end;
+                if segment.boundary {
+                    if let Some((ending_0, ending_1)) = self.sale_rate_ending.get((pair, segment.end)) {
+                        self.reward_factors_at_expiry
+                            .insert((pair, segment.end), &(pool.reward_factor_0, pool.reward_factor_1));
+                        pool.sale_rate_0 = pool.sale_rate_0.checked_sub(ending_0).ok_or(TwammError::Overflow)?;
+                        pool.sale_rate_1 = pool.sale_rate_1.checked_sub(ending_1).ok_or(TwammError::Overflow)?;
+                    }
+                }
+            }
+            Ok(())
+        }
+
+        /// Executa as vendas virtuais e liquida o fluxo líquido no par
+        ///
+        /// O estado só é gravado depois da liquidação, então uma falha não
+        /// deixa o pool parcialmente executado.
+        fn execute_virtual_orders_internal(&mut self, pair: AccountId) -> Result<(), TwammError> {
+            let mut pool = self.pools.get(pair).ok_or(TwammError::PoolNotFound)?;
+            let now = self.env().block_timestamp();
+            if pool.last_execution >= now {
+                return Ok(());
+            }
+
+            let (current, reserves) = self.current_observation(pair)?;
+            let start = self.observations.get(pair).unwrap_or(current);
+            let fee_bps = self.pair_fee_bps(pair);
+            let (segments, trade) = self.simulate_virtual_orders(pair, &pool, reserves, now, fee_bps)?;
+
+            // Apenas o lado líquido vai ao pool; o restante é casado internamente.
+            // O lado que vende o fluxo líquido recebe o casamento interno mais o
+            // output medido do swap.
+            let credited = if trade.sold_0 > trade.bought_0 && trade.bought_1 > trade.sold_1 {
+                let amount_in = trade.sold_0.checked_sub(trade.bought_0).ok_or(TwammError::Overflow)?;
+                let twap_price = Self::average_price(
+                    start.price_0_cumulative,
+                    current.price_0_cumulative,
+                    start.timestamp,
+                    current.timestamp,
+                )?;
+                let amount_out_min = Self::settlement_min_out(amount_in, reserves, twap_price, fee_bps)?;
+                let received = self.swap_via_router(pool.token_0, pool.token_1, amount_in, amount_out_min)?;
+                (trade.bought_0, trade.sold_1.checked_add(received).ok_or(TwammError::Overflow)?)
+            } else if trade.sold_1 > trade.bought_1 && trade.bought_0 > trade.sold_0 {
+                let amount_in = trade.sold_1.checked_sub(trade.bought_1).ok_or(TwammError::Overflow)?;
+                let twap_price = Self::average_price(
+                    start.price_1_cumulative,
+                    current.price_1_cumulative,
+                    start.timestamp,
+                    current.timestamp,
+                )?;
+                let amount_out_min =
+                    Self::settlement_min_out(amount_in, (reserves.1, reserves.0), twap_price, fee_bps)?;
+                let received = self.swap_via_router(pool.token_1, pool.token_0, amount_in, amount_out_min)?;
+                (trade.sold_0.checked_add(received).ok_or(TwammError::Overflow)?, trade.bought_1)
+            } else {
+                (trade.bought_0, trade.bought_1)
+            };
+
+            self.apply_virtual_orders(pair, &mut pool, &segments, &trade, credited)?;
+            self.pools.insert(pair, &pool);
+            if current.timestamp > start.timestamp {
+                self.observations.insert(pair, &current);
+            }
+
+            self.env().emit_event(VirtualOrdersExecuted {
+                pair,
+                sold_0: trade.sold_0,
+                sold_1: trade.sold_1,
+                bought_0: credited.0,
+                bought_1: credited.1,
+                executed_until: pool.last_execution,
+            });
+
+            Ok(())
+        }
+
+        /// Liquida o par antes de uma operação do dono, sem bloqueá-la
+        ///
+        /// Uma liquidação que falha (slippage contra o TWAP, router, backlog)
+        /// não grava nada; cancelamento e saque seguem com o estado da última
+        /// execução, e keepers veem o erro via `execute_virtual_orders`.
+        fn try_execute_virtual_orders(&mut self, pair: AccountId) {
+            let _ = self.execute_virtual_orders_internal(pair);
+        }
+
+        // ========================================
+        // FUNÇÕES INTERNAS (ORÁCULO TWAP)
+        // ========================================
+
+        /// Preço médio UQ112 entre duas observações
+        fn average_price(
+            cumulative_start: u128,
+            cumulative_end: u128,
+            start: Timestamp,
+            end: Timestamp,
+        ) -> Result<u128, TwammError> {
+            let elapsed = end.checked_sub(start).filter(|elapsed| *elapsed > 0).ok_or(TwammError::TwapUnavailable)?;
+            cumulative_end
+                .checked_sub(cumulative_start)
+                .ok_or(TwammError::TwapUnavailable)?
+                .checked_div(u128::from(elapsed))
+                .ok_or(TwammError::Overflow)
+        }
+
+        /// Output mínimo da liquidação: o maior entre o output de produto
+        /// constante nas reserves `(reserve_in, reserve_out)` e o TWAP menos
+        /// `MAX_TWAP_DEVIATION_BPS`, ambos na fee `fee_bps` do par
+        fn settlement_min_out(
+            amount_in: Balance,
+            reserves: (Balance, Balance),
+            twap_price: u128,
+            fee_bps: u16,
+        ) -> Result<Balance, TwammError> {
+            let (reserve_in, reserve_out) = reserves;
+            if reserve_in == 0 || reserve_out == 0 {
+                return Err(TwammError::InsufficientLiquidity);
+            }
+            let amount_in_with_fee = U256::from(Self::after_fee(amount_in, fee_bps)?);
+            let spot = amount_in_with_fee
+                .checked_mul(U256::from(reserve_out))
+                .and_then(|value| value.checked_div(U256::from(reserve_in).checked_add(amount_in_with_fee)?))
+                .ok_or(TwammError::Overflow)?;
+            let spot = u128::try_from(spot).map_err(|_| TwammError::Overflow)?;
+
+            let floor = amount_in_with_fee
+                .checked_mul(U256::from(twap_price))
+                .and_then(|value| value.checked_div(U256::from(constants::UQ112)))
+                .and_then(|value| {
+                    value.checked_mul(U256::from(
+                        constants::BPS_DENOMINATOR.checked_sub(constants::MAX_TWAP_DEVIATION_BPS)?,
+                    ))
+                })
+                .and_then(|value| value.checked_div(U256::from(constants::BPS_DENOMINATOR)))
+                .ok_or(TwammError::Overflow)?;
+            let floor = u128::try_from(floor).map_err(|_| TwammError::Overflow)?;
+            Ok(spot.max(floor))
+        }
+
+        // ========================================
+        // FUNÇÕES INTERNAS (MATEMÁTICA TWAMM)
+        // ========================================
+
+        /// Amount correspondente a uma taxa de venda durante `elapsed` ms
+        fn amount_for(sale_rate: u128, elapsed: u64) -> Result<Balance, TwammError> {
+            let amount = U256::from(sale_rate)
+                .checked_mul(U256::from(elapsed))
+                .and_then(|value| value.checked_div(U256::from(constants::RATE_SCALE)))
+                .ok_or(TwammError::Overflow)?;
+            u128::try_from(amount).map_err(|_| TwammError::Overflow)
+        }
+
+        /// Proceeds de uma ordem entre dois reward factors
+        fn proceeds_for(sale_rate: u128, factor_start: u128, factor_end: u128) -> Result<Balance, TwammError> {
+            let delta = factor_end.checked_sub(factor_start).ok_or(TwammError::Overflow)?;
+            let proceeds = U256::from(sale_rate)
+                .checked_mul(U256::from(delta))
+                .and_then(|value| value.checked_div(U256::from(constants::REWARD_SCALE)))
+                .ok_or(TwammError::Overflow)?;
+            u128::try_from(proceeds).map_err(|_| TwammError::Overflow)
+        }
+
+        /// Soma ao reward factor o output distribuído por unidade de sale rate
+        fn accrue_reward(factor: u128, amount: Balance, sale_rate: u128) -> Result<u128, TwammError> {
+            if sale_rate == 0 || amount == 0 {
+                return Ok(factor);
+            }
+            let increment = U256::from(amount)
+                .checked_mul(U256::from(constants::REWARD_SCALE))
+                .and_then(|value| value.checked_div(U256::from(sale_rate)))
+                .ok_or(TwammError::Overflow)?;
+            let increment = u128::try_from(increment).map_err(|_| TwammError::Overflow)?;
+            factor.checked_add(increment).ok_or(TwammError::Overflow)
+        }
+
+        /// Amount efetivo após uma fee de swap em bps
+        fn after_fee(amount: Balance, fee_bps: u16) -> Result<Balance, TwammError> {
+            let fee_numerator = constants::BPS_DENOMINATOR
+                .checked_sub(u128::from(fee_bps))
+                .ok_or(TwammError::Overflow)?;
+            let value = U256::from(amount)
+                .checked_mul(U256::from(fee_numerator))
+                .and_then(|value| value.checked_div(U256::from(constants::BPS_DENOMINATOR)))
+                .ok_or(TwammError::Overflow)?;
+            u128::try_from(value).map_err(|_| TwammError::Overflow)
+        }
+
+        /// Parcela `part / total` de `credited` (arredondada para baixo)
+        fn pro_rata(part: Balance, credited: Balance, total: Balance) -> Result<Balance, TwammError> {
+            if total == 0 {
+                return Ok(0);
+            }
+            let value = U256::from(part)
+                .checked_mul(U256::from(credited))
+                .and_then(|value| value.checked_div(U256::from(total)))
+                .ok_or(TwammError::Overflow)?;
+            u128::try_from(value).map_err(|_| TwammError::Overflow)
+        }
+
+        /// e^a em ponto fixo (1e18), com `a` limitado a `MAX_EXPONENT`
+        ///
+        /// Redução de faixa: e^a = 2^n · e^r, com r em [0, ln 2) via série de Taylor.
+        fn exp_fixed(exponent: U256) -> Result<U256, TwammError> {
+            let one = U256::from(constants::ONE);
+            let ln_2 = U256::from(constants::LN_2);
+            let exponent = exponent.min(U256::from(constants::MAX_EXPONENT));
+
+            let n = exponent.checked_div(ln_2).ok_or(TwammError::Overflow)?;
+            let r = n
+                .checked_mul(ln_2)
+                .and_then(|reduced| exponent.checked_sub(reduced))
+                .ok_or(TwammError::Overflow)?;
+
+            let mut term = one;
+            let mut sum = one;
+            for k in 1u32..=30 {
+                term = term
+                    .checked_mul(r)
+                    .and_then(|value| value.checked_div(one.checked_mul(U256::from(k))?))
+                    .ok_or(TwammError::Overflow)?;
+                if term.is_zero() {
+                    break;
+                }
+                sum = sum.checked_add(term).ok_or(TwammError::Overflow)?;
+            }
+
+            let power_of_two = U256::from(2u8).checked_pow(n).ok_or(TwammError::Overflow)?;
+            sum.checked_mul(power_of_two).ok_or(TwammError::Overflow)
+        }
+
+        /// Resolve um intervalo de vendas virtuais contra as reserves `(x, y)`
+        ///
+        /// # Retorna
+        /// - `(bought_0, bought_1, x_end, y_end)`: token_0 entregue aos vendedores de
+        ///   token_1, token_1 entregue aos vendedores de token_0 e as reserves finais
+        fn compute_virtual_trade(
+            reserve_0: Balance,
+            reserve_1: Balance,
+            in_0: Balance,
+            in_1: Balance,
+        ) -> Result<(Balance, Balance, Balance, Balance), TwammError> {
+            if in_0 == 0 && in_1 == 0 {
+                return Ok((0, 0, reserve_0, reserve_1));
+            }
+            if reserve_0 == 0 || reserve_1 == 0 {
+                return Err(TwammError::InsufficientLiquidity);
+            }
+
+            let x = U256::from(reserve_0);
+            let y = U256::from(reserve_1);
+            let k = x.checked_mul(y).ok_or(TwammError::Overflow)?;
+
+            let x_end = if in_1 == 0 {
+                // Apenas um lado: produto constante
+                x.checked_add(U256::from(in_0)).ok_or(TwammError::Overflow)?
+            } else if in_0 == 0 {
+                let y_end = y.checked_add(U256::from(in_1)).ok_or(TwammError::Overflow)?;
+                Self::div_ceil(k, y_end)?
+            } else {
+                let e0 = U256::from(in_0);
+                let e1 = U256::from(in_1);
+                let one = U256::from(constants::ONE);
+                let sqrt_k = k.integer_sqrt();
+                let p = x.checked_mul(e1).ok_or(TwammError::Overflow)?.integer_sqrt();
+                let q = y.checked_mul(e0).ok_or(TwammError::Overflow)?.integer_sqrt();
+
+                // Expoente: 2·√(e0·e1) / √(x·y)
+                let exponent = e0
+                    .checked_mul(e1)
+                    .ok_or(TwammError::Overflow)?
+                    .integer_sqrt()
+                    .checked_mul(U256::from(2u8))
+                    .and_then(|value| value.checked_mul(one))
+                    .and_then(|value| value.checked_div(sqrt_k))
+                    .ok_or(TwammError::Overflow)?;
+                let e = Self::exp_fixed(exponent)?;
+                let e_plus = e.checked_add(one).ok_or(TwammError::Overflow)?;
+                let e_minus = e.checked_sub(one).ok_or(TwammError::Overflow)?;
+
+                // (e + c)/(e − c) = ((e+1)·p + (e−1)·q) / ((e−1)·p + (e+1)·q)
+                let numerator = e_plus
+                    .checked_mul(p)
+                    .and_then(|value| value.checked_add(e_minus.checked_mul(q)?))
+                    .ok_or(TwammError::Overflow)?;
+                let denominator = e_minus
+                    .checked_mul(p)
+                    .and_then(|value| value.checked_add(e_plus.checked_mul(q)?))
+                    .ok_or(TwammError::Overflow)?;
+
+                // √(e0/e1) com 32 bits extras de precisão em cada raiz
+                let scale = U256::from(u128::from(u64::MAX)).checked_add(U256::one()).ok_or(TwammError::Overflow)?;
+                let sqrt_e0 = e0.checked_mul(scale).ok_or(TwammError::Overflow)?.integer_sqrt();
+                let sqrt_e1 = e1.checked_mul(scale).ok_or(TwammError::Overflow)?.integer_sqrt();
+
+                let top = sqrt_k
+                    .checked_mul(sqrt_e0)
+                    .ok_or(TwammError::Overflow)?
+                    .full_mul(numerator);
+                let bottom = U512::from(sqrt_e1)
+                    .checked_mul(U512::from(denominator))
+                    .ok_or(TwammError::Overflow)?;
+                if bottom.is_zero() {
+                    return Err(TwammError::Overflow);
+                }
+                let x_end = top
+                    .checked_add(bottom)
+                    .and_then(|value| value.checked_sub(U512::one()))
+                    .and_then(|value| value.checked_div(bottom))
+                    .ok_or(TwammError::Overflow)?;
+                U256::try_from(x_end).map_err(|_| TwammError::Overflow)?
+            };
+
+            if x_end.is_zero() {
+                return Err(TwammError::InsufficientLiquidity);
+            }
+            let y_end = Self::div_ceil(k, x_end)?;
+
+            let x_end = u128::try_from(x_end).map_err(|_| TwammError::Overflow)?;
+            let y_end = u128::try_from(y_end).map_err(|_| TwammError::Overflow)?;
+            let bought_0 = reserve_0.saturating_add(in_0).saturating_sub(x_end);
+            let bought_1 = reserve_1.saturating_add(in_1).saturating_sub(y_end);
+
+            Ok((bought_0, bought_1, x_end, y_end))
+        }
+
+        /// Divisão arredondada para cima (a favor do pool)
+        fn div_ceil(numerator: U256, denominator: U256) -> Result<U256, TwammError> {
+            numerator
+                .checked_add(denominator)
+                .and_then(|value| value.checked_sub(U256::one()))
+                .and_then(|value| value.checked_div(denominator))
+                .ok_or(TwammError::Overflow)
+        }
+
+        // ========================================
+        // FUNÇÕES INTERNAS (CHAMADAS CROSS-CONTRACT)
+        // ========================================
+
+        /// Consulta uma mensagem sem argumentos do Pair Contract
+        fn query_pair<R: scale::Decode>(&self, pair: AccountId, selector: [u8; 4]) -> Result<R, TwammError> {
+            build_call::<DefaultEnvironment>()
+                .call(pair)
+                .exec_input(ExecutionInput::new(Selector::new(selector)))
+                .returns::<R>()
+                .try_invoke()
+                .map_err(|_| TwammError::PairQueryFailed)?
+                .map_err(|_| TwammError::PairQueryFailed)
+        }
+
+        /// Fee de swap atual do par em bps (estática quando o par não expõe `current_fee_bps`)
+        fn pair_fee_bps(&self, pair: AccountId) -> u16 {
+            self.query_pair(pair, selectors::PAIR_CURRENT_FEE_BPS)
+                .unwrap_or(constants::PAIR_STATIC_FEE_BPS)
+        }
+
+        /// Preços cumulativos do par projetados até agora, junto das reserves atuais
+        fn current_observation(&self, pair: AccountId) -> Result<(Observation, (Balance, Balance)), TwammError> {
+            let now = self.env().block_timestamp();
+            let mut price_0_cumulative: u128 = self.query_pair(pair, selectors::PAIR_PRICE_0_CUMULATIVE_LAST)?;
+            let mut price_1_cumulative: u128 = self.query_pair(pair, selectors::PAIR_PRICE_1_CUMULATIVE_LAST)?;
+            let (reserve_0, reserve_1, last_update): (Balance, Balance, Timestamp) =
+                self.query_pair(pair, selectors::PAIR_GET_RESERVES)?;
+
+            // Mesmo acúmulo feito pelo Pair em `update`; reserves alteradas neste
+            // bloco não entram porque `last_update == now`
+            let elapsed = now.saturating_sub(last_update);
+            if elapsed > 0 && reserve_0 != 0 && reserve_1 != 0 {
+                let price_0 = reserve_1
+                    .checked_mul(constants::UQ112)
+                    .and_then(|price| price.checked_div(reserve_0))
+                    .ok_or(TwammError::Overflow)?;
+                let price_1 = reserve_0
+                    .checked_mul(constants::UQ112)
+                    .and_then(|price| price.checked_div(reserve_1))
+                    .ok_or(TwammError::Overflow)?;
+                price_0_cumulative = price_0
+                    .checked_mul(u128::from(elapsed))
+                    .and_then(|delta| price_0_cumulative.checked_add(delta))
+                    .ok_or(TwammError::Overflow)?;
+                price_1_cumulative = price_1
+                    .checked_mul(u128::from(elapsed))
+                    .and_then(|delta| price_1_cumulative.checked_add(delta))
+                    .ok_or(TwammError::Overflow)?;
+            }
+
+            let observation = Observation {
+                price_0_cumulative,
+                price_1_cumulative,
+                timestamp: now,
+            };
+            Ok((observation, (reserve_0, reserve_1)))
+        }
+
+        /// Troca o fluxo líquido no par via router, com output para este contrato
+        ///
+        /// Retorna a variação do saldo de `token_out` deste contrato.
+        fn swap_via_router(
+            &self,
+            token_in: AccountId,
+            token_out: AccountId,
+            amount_in: Balance,
+            amount_out_min: Balance,
+        ) -> Result<Balance, TwammError> {
+            let this = self.env().account_id();
+            let before = self.token_balance(token_out, this)?;
+
+            build_call::<DefaultEnvironment>()
+                .call(token_in)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selectors::PSP22_APPROVE))
+                        .push_arg(self.router)
+                        .push_arg(amount_in),
+                )
+                .returns::<Result<(), PSP22Error>>()
+                .try_invoke()
+                .map_err(|_| TwammError::TransferFailed)?
+                .map_err(|_| TwammError::TransferFailed)??;
+
+            build_call::<DefaultEnvironment>()
+                .call(self.router)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selectors::ROUTER_SWAP_EXACT_TOKENS_FOR_TOKENS))
+                        .push_arg(amount_in)
+                        .push_arg(amount_out_min)
+                        .push_arg(vec![token_in, token_out])
+                        .push_arg(this)
+                        .push_arg(self.env().block_timestamp()),
+                )
+                .returns::<Result<Vec<Balance>, RouterError>>()
+                .try_invoke()
+                .map_err(|_| TwammError::RouterCallFailed)?
+                .map_err(|_| TwammError::RouterCallFailed)?
+                .map_err(TwammError::Router)?;
+
+            self.token_balance(token_out, this)?
+                .checked_sub(before)
+                .ok_or(TwammError::TransferFailed)
+        }
+
+        /// Saldo PSP22 de `owner` em `token`
+        fn token_balance(&self, token: AccountId, owner: AccountId) -> Result<Balance, TwammError> {
+            build_call::<DefaultEnvironment>()
+                .call(token)
+                .exec_input(ExecutionInput::new(Selector::new(selectors::PSP22_BALANCE_OF)).push_arg(owner))
+                .returns::<Balance>()
+                .try_invoke()
+                .map_err(|_| TwammError::TransferFailed)?
+                .map_err(|_| TwammError::TransferFailed)
+        }
+
+        /// Transfere tokens PSP22 mantidos por este contrato
+        fn transfer_token(&self, token: AccountId, to: AccountId, amount: Balance) -> Result<(), TwammError> {
+            build_call::<DefaultEnvironment>()
+                .call(token)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selectors::PSP22_TRANSFER))
+                        .push_arg(to)
+                        .push_arg(amount)
+                        .push_arg(Vec::<u8>::new()),
+                )
+                .returns::<Result<(), PSP22Error>>()
+                .try_invoke()
+                .map_err(|_| TwammError::TransferFailed)?
+                .map_err(|_| TwammError::TransferFailed)??;
+            Ok(())
+        }
+
+        /// Puxa tokens PSP22 de `from` usando a allowance concedida
+        fn transfer_from_token(&self, token: AccountId, from: AccountId, to: AccountId, amount: Balance) -> Result<(), TwammError> {
+            build_call::<DefaultEnvironment>()
+                .call(token)
+                .exec_input(
+                    ExecutionInput::new(Selector::new(selectors::PSP22_TRANSFER_FROM))
+                        .push_arg(from)
+                        .push_arg(to)
+                        .push_arg(amount)
+                        .push_arg(Vec::<u8>::new()),
+                )
+                .returns::<Result<(), PSP22Error>>()
+                .try_invoke()
+                .map_err(|_| TwammError::TransferFailed)?
+                .map_err(|_| TwammError::TransferFailed)??;
+            Ok(())
+        }
+    }
+
+    // ========================================
+    // TESTES UNITÁRIOS
+    // ========================================
+
+    #[cfg(test)]
+    #[allow(clippy::arithmetic_side_effects)]
+    mod tests {
+        use super::*;
+
+        const HOUR: u64 = constants::ORDER_INTERVAL;
+
+        fn default_accounts() -> ink::env::test::DefaultAccounts<DefaultEnvironment> {
+            ink::env::test::default_accounts::<DefaultEnvironment>()
+        }
+
+        fn set_sender(sender: AccountId) {
+            ink::env::test::set_caller::<DefaultEnvironment>(sender);
+        }
+
+        fn set_timestamp(timestamp: u64) {
+            ink::env::test::set_block_timestamp::<DefaultEnvironment>(timestamp);
+        }
+
+        /// Executa as vendas virtuais na fee estática, creditando o output virtual
+        fn advance(
+            contract: &mut TwammContract,
+            pair: AccountId,
+            pool: &mut TwammPool,
+            reserves: (Balance, Balance),
+            now: Timestamp,
+        ) -> VirtualTrade {
+            let (segments, trade) = contract
+                .simulate_virtual_orders(pair, pool, reserves, now, constants::PAIR_STATIC_FEE_BPS)
+                .unwrap();
+            contract
+                .apply_virtual_orders(pair, pool, &segments, &trade, (trade.bought_0, trade.bought_1))
+                .unwrap();
+            trade
+        }
+
+        /// Registra um pool sem consultar o par
+        fn new_pool(contract: &mut TwammContract, pair: AccountId) -> TwammPool {
+            let accounts = default_accounts();
+            let pool = TwammPool::new(
+                accounts.django,
+                accounts.eve,
+                ink::env::block_timestamp::<DefaultEnvironment>(),
+            );
+            contract.pools.insert(pair, &pool);
+            pool
+        }
+
+        #[ink::test]
+        fn test_exp_fixed_precision() {
+            let one = U256::from(constants::ONE);
+
+            // GREEN: e^0 = 1
+            assert_eq!(TwammContract::exp_fixed(U256::zero()).unwrap(), one);
+
+            // GREEN: e^1 ≈ 2.718281828459045235 (erro < 1e-12)
+            let e = TwammContract::exp_fixed(one).unwrap().as_u128();
+            assert!(e.abs_diff(2_718_281_828_459_045_235) < 1_000_000);
+
+            // GREEN: e^(3·ln 2) = 8
+            let eight = TwammContract::exp_fixed(U256::from(constants::LN_2 * 3)).unwrap().as_u128();
+            assert!(eight.abs_diff(8 * constants::ONE) < 1_000_000);
+        }
+
+        #[ink::test]
+        fn test_virtual_trade_one_sided_matches_constant_product() {
+            // GREEN: Só token_0 vendendo: x_end = x + in, y_end = ceil(k / x_end)
+            let (bought_0, bought_1, x_end, y_end) =
+                TwammContract::compute_virtual_trade(1_000_000, 1_000_000, 1_000, 0).unwrap();
+            assert_eq!(bought_0, 0);
+            assert_eq!(x_end, 1_001_000);
+            assert_eq!(y_end, 999_001);
+            assert_eq!(bought_1, 999);
+
+            // RED: Pool vazio
+            assert_eq!(
+                TwammContract::compute_virtual_trade(0, 1_000, 10, 0),
+                Err(TwammError::InsufficientLiquidity)
+            );
+        }
+
+        #[ink::test]
+        fn test_settlement_min_out_floors_at_twap() {
+            let uq112 = constants::UQ112;
+
+            // GREEN: TWAP 1:1 em 1000 ms
+            let price = TwammContract::average_price(0, uq112 * 1_000, 0, 1_000).unwrap();
+            assert_eq!(price, uq112);
+
+            // GREEN: Reserves manipuladas (spot baixo) não reduzem o mínimo:
+            // 1000 * 0.995 * 1.0 * 0.97 = 965
+            let manipulated = (2_000_000, 500_000);
+            assert_eq!(TwammContract::settlement_min_out(1_000, manipulated, price, 50), Ok(965));
+
+            // GREEN: Spot acima do piso prevalece, cotado na fee do par (0.5%):
+            // 995 * 1e6 / (1e6 + 995) = 994, o mesmo que o par entrega
+            let balanced = (1_000_000, 1_000_000);
+            assert_eq!(TwammContract::settlement_min_out(1_000, balanced, price, 50), Ok(994));
+            // RED: A 0.3% o mínimo (996) ficaria acima do que o par paga
+            assert_eq!(TwammContract::settlement_min_out(1_000, balanced, price, 30), Ok(996));
+
+            // RED: Sem tempo decorrido desde a última observação
+            assert_eq!(
+                TwammContract::average_price(0, uq112, 1_000, 1_000),
+                Err(TwammError::TwapUnavailable)
+            );
+        }
+
+        #[ink::test]
+        fn test_virtual_trade_two_sided() {
+            // GREEN: Vendas iguais no preço do pool se cancelam sem mover o preço
+            let (bought_0, bought_1, x_end, y_end) =
+                TwammContract::compute_virtual_trade(1_000_000, 1_000_000, 1_000, 1_000).unwrap();
+            assert_eq!((x_end, y_end), (1_000_000, 1_000_000));
+            assert_eq!((bought_0, bought_1), (1_000, 1_000));
+
+            // GREEN: Lado maior move o preço menos do que um swap isolado
+            let (_, bought_1, x_end, y_end) =
+                TwammContract::compute_virtual_trade(1_000_000_000, 1_000_000_000, 100_000_000, 10_000_000).unwrap();
+            let (_, isolated_1, _, _) =
+                TwammContract::compute_virtual_trade(1_000_000_000, 1_000_000_000, 100_000_000, 0).unwrap();
+            assert!(x_end > 1_000_000_000 && y_end < 1_000_000_000);
+            assert!(bought_1 > isolated_1);
+
+            // GREEN: Invariante k preservado (arredondamento a favor do pool)
+            let k = 1_000_000_000u128 * 1_000_000_000u128;
+            assert!(x_end * y_end >= k);
+            assert!(x_end * (y_end - 1) < k);
+        }
+
+        #[ink::test]
+        fn test_virtual_orders_track_progress_and_expiry() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            set_timestamp(0);
+
+            let mut contract = TwammContract::new(accounts.bob);
+            let pair = accounts.charlie;
+            let pool = new_pool(&mut contract, pair);
+
+            // GREEN: Ordem de 2 intervalos vendendo token_0 (expira em 3h)
+            let (order, sell_amount) = contract.open_order(pair, pool, accounts.alice, true, 10_800_000, 2).unwrap();
+            assert_eq!(order.expiry, 3 * HOUR);
+            assert_eq!(sell_amount, 10_800_000);
+
+            // GREEN: Após metade da duração, metade vendida e proceeds acumulados
+            let mut pool = contract.get_pool(pair).unwrap();
+            let trade = advance(&mut contract, pair, &mut pool, (1_000_000_000, 1_000_000_000), order.expiry / 2);
+            contract.pools.insert(pair, &pool);
+            assert_eq!(trade.sold_0, 5_400_000);
+            assert_eq!(trade.sold_1, 0);
+            let progress = contract.get_order_progress(order.id).unwrap();
+            assert_eq!(progress.sold, 5_400_000);
+            assert_eq!(progress.progress_bps, 5_000);
+            assert!(progress.proceeds_available > 0 && progress.proceeds_available <= trade.bought_1);
+
+            // GREEN: Após a expiração, a taxa sai do pool e o reward factor fica congelado
+            let trade = advance(&mut contract, pair, &mut pool, (1_005_400_000, 994_600_000), order.expiry + HOUR);
+            contract.pools.insert(pair, &pool);
+            assert_eq!(trade.sold_0, 5_400_000);
+            assert_eq!(pool.sale_rate_0, 0);
+            assert_eq!(pool.last_execution, order.expiry + HOUR);
+            let progress = contract.get_order_progress(order.id).unwrap();
+            assert_eq!(progress.progress_bps, 10_000);
+            assert_eq!(progress.remaining, 0);
+            assert!(contract.reward_factors_at_expiry.get((pair, order.expiry)).is_some());
+        }
+
+        #[ink::test]
+        fn test_pro_rata_proceeds_between_orders() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            set_timestamp(0);
+
+            let mut contract = TwammContract::new(accounts.bob);
+            let pair = accounts.charlie;
+            let pool = new_pool(&mut contract, pair);
+
+            // GREEN: Duas ordens com mesma expiração e amounts 1:3
+            let (small, _) = contract.open_order(pair, pool, accounts.alice, true, 7_200_000, 1).unwrap();
+            let pool = contract.get_pool(pair).unwrap();
+            let (large, _) = contract.open_order(pair, pool, accounts.frank, true, 21_600_000, 1).unwrap();
+
+            let mut pool = contract.get_pool(pair).unwrap();
+            advance(&mut contract, pair, &mut pool, (1_000_000_000, 1_000_000_000), small.expiry);
+            contract.pools.insert(pair, &pool);
+
+            let small_proceeds = contract.get_order_progress(small.id).unwrap().proceeds_available;
+            let large_proceeds = contract.get_order_progress(large.id).unwrap().proceeds_available;
+            assert!(small_proceeds > 0);
+            assert!((small_proceeds * 3).abs_diff(large_proceeds) <= 3);
+        }
+
+        #[ink::test]
+        fn test_proceeds_follow_settled_output() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            set_timestamp(0);
+
+            let mut contract = TwammContract::new(accounts.bob);
+            let pair = accounts.charlie;
+            let pool = new_pool(&mut contract, pair);
+            let (order, _) = contract.open_order(pair, pool, accounts.alice, true, 7_200_000, 1).unwrap();
+
+            // GREEN: O swap de liquidação entregou só metade do output virtual
+            let mut pool = contract.get_pool(pair).unwrap();
+            let (segments, trade) = contract
+                .simulate_virtual_orders(pair, &pool, (1_000_000_000, 1_000_000_000), order.expiry, 50)
+                .unwrap();
+            assert!(contract.get_pool(pair).unwrap().last_execution < order.expiry);
+            let received = trade.bought_1 / 2;
+            contract
+                .apply_virtual_orders(pair, &mut pool, &segments, &trade, (0, received))
+                .unwrap();
+            contract.pools.insert(pair, &pool);
+
+            // GREEN: A ordem recebe o saldo efetivo, nunca o output virtual
+            let proceeds = contract.get_order_progress(order.id).unwrap().proceeds_available;
+            assert!(proceeds <= received);
+            assert!(received - proceeds <= 1);
+        }
+
+        #[ink::test]
+        fn test_cancel_at_last_execution_when_settlement_is_stuck() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            set_timestamp(0);
+
+            let mut contract = TwammContract::new(accounts.bob);
+            let pair = accounts.charlie;
+            let pool = new_pool(&mut contract, pair);
+            let (mut order, sell_amount) = contract.open_order(pair, pool, accounts.alice, true, 7_200_000, 1).unwrap();
+
+            // GREEN: Primeira hora executada; a liquidação seguinte falha e o pool para
+            let mut pool = contract.get_pool(pair).unwrap();
+            advance(&mut contract, pair, &mut pool, (1_000_000_000, 1_000_000_000), HOUR);
+            contract.pools.insert(pair, &pool);
+            set_timestamp(HOUR + HOUR / 2);
+
+            // GREEN: Cancelamento na última execução: o não executado volta ao dono
+            let (unsold, proceeds) = contract.close_order(&mut order, &mut pool).unwrap();
+            assert_eq!(order.cancelled_at, Some(HOUR));
+            assert_eq!(unsold, sell_amount / 2);
+            assert!(proceeds > 0);
+            assert_eq!(contract.get_pool(pair).unwrap().sale_rate_0, 0);
+            assert_eq!(contract.get_order(order.id).unwrap().status, OrderStatus::Cancelled);
+
+            // RED: Ordem já executada até a expiração não é cancelável
+            let pool = contract.get_pool(pair).unwrap();
+            let (mut other, _) = contract.open_order(pair, pool, accounts.alice, true, 3_600_000, 1).unwrap();
+            let mut pool = contract.get_pool(pair).unwrap();
+            pool.last_execution = other.expiry;
+            assert_eq!(contract.close_order(&mut other, &mut pool), Err(TwammError::OrderExpired));
+        }
+
+        #[ink::test]
+        fn test_order_validations() {
+            let accounts = default_accounts();
+            set_sender(accounts.alice);
+            set_timestamp(0);
+
+            let mut contract = TwammContract::new(accounts.bob);
+            let pair = accounts.charlie;
+
+            // RED: Parâmetros inválidos
+            assert_eq!(
+                contract.submit_long_term_order(AccountId::from([0u8; 32]), accounts.django, 100, 1),
+                Err(TwammError::ZeroAddress)
+            );
+            assert_eq!(contract.submit_long_term_order(pair, accounts.django, 0, 1), Err(TwammError::InvalidAmount));
+            assert_eq!(contract.submit_long_term_order(pair, accounts.django, 100, 0), Err(TwammError::InvalidDuration));
+            assert_eq!(
+                contract.submit_long_term_order(pair, accounts.django, 100, constants::MAX_ORDER_INTERVALS + 1),
+                Err(TwammError::InvalidDuration)
+            );
+            assert_eq!(contract.execute_virtual_orders(pair), Err(TwammError::PoolNotFound));
+
+            // RED: Apenas o dono cancela/saca
+            let pool = new_pool(&mut contract, pair);
+            let (order, _) = contract.open_order(pair, pool, accounts.alice, true, 1_000_000, 1).unwrap();
+            set_sender(accounts.frank);
+            assert_eq!(contract.cancel_long_term_order(order.id), Err(TwammError::Unauthorized));
+            assert_eq!(contract.withdraw_proceeds(order.id), Err(TwammError::Unauthorized));
+
+            // RED: Amount pequeno demais para a duração
+            let pool = contract.get_pool(pair).unwrap();
+            assert_eq!(
+                contract.open_order(pair, pool, accounts.alice, true, 1, 1).map(|(order, _)| order.id),
+                Err(TwammError::InvalidAmount)
+            );
+        }
+    }
+}